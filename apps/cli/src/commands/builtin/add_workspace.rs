@@ -2,6 +2,7 @@
 //!
 //! This command provides an interactive flow for adding a new workspace.
 
+use std::collections::HashSet;
 use std::env::current_dir;
 
 use crate::commands::{Command, CommandCtx};
@@ -33,17 +34,28 @@ impl AddWorkspaceCommand {
     pub fn new() -> Self {
         Self
     }
+}
+
+/// Slugify a name to create a valid workspace ID.
+///
+/// Converts the name to lowercase and replaces special characters with hyphens.
+pub(crate) fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .replace(|c: char| !c.is_alphanumeric(), "-")
+        .replace("--", "-")
+        .trim_matches('-')
+        .to_string()
+}
 
-    /// Slugify a name to create a valid workspace ID.
-    ///
-    /// Converts the name to lowercase and replaces special characters with hyphens.
-    fn slugify(&self, name: &str) -> String {
-        name.to_lowercase()
-            .replace(|c: char| !c.is_alphanumeric(), "-")
-            .replace("--", "-")
-            .trim_matches('-')
-            .to_string()
+/// Make an id unique by adding a number when it is taken.
+pub(crate) fn unique_id(slug: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(slug) {
+        return slug.to_string();
     }
+    (2..)
+        .map(|n| format!("{}-{}", slug, n))
+        .find(|id| !taken.contains(id))
+        .unwrap()
 }
 
 impl Default for AddWorkspaceCommand {
@@ -84,14 +96,15 @@ impl Command for AddWorkspaceCommand {
         };
 
         // Generate slugified ID from name
-        let id = self.slugify(&name);
+        let id = slugify(&name);
 
         // Step 2: Get tags (with suggestions from existing workspaces)
         // Collect all unique tags from existing workspaces
         let all_tags: Vec<String> = {
-            let workspaces = ctx.workspace_repo().get_workspaces();
+            let workspaces = ctx.workspace_repo().get_workspace_entries();
             let mut tags: Vec<String> = workspaces
                 .iter()
+                .flatten()
                 .flat_map(|w| w.tags.iter().map(|t| t.name.clone()))
                 .collect();
             tags.sort();
@@ -114,7 +127,7 @@ impl Command for AddWorkspaceCommand {
             .split(',')
             .map(|s| s.trim().to_lowercase())
             .filter(|s| !s.is_empty())
-            .collect::<std::collections::HashSet<_>>() // Deduplicate
+            .collect::<HashSet<_>>() // Deduplicate
             .into_iter()
             .collect();
         tags.sort(); // Sort alphabetically for consistent ordering
@@ -132,14 +145,15 @@ impl Command for AddWorkspaceCommand {
             Some(true) => {
                 let _ = ctx.restore();
 
-                let workspace = &ctx.workspace_repo().create_workspace(
+                match ctx.workspace_repo().create_workspace(
                     name,
                     tags,
                     current_dir().unwrap().to_str().unwrap().to_string(),
                     id,
-                );
-
-                PrettyDisplay {}.display(workspace);
+                ) {
+                    Ok(workspace) => PrettyDisplay {}.display(&workspace),
+                    Err(err) => eprintln!("Error: {}", err),
+                }
             }
             _ => {
                 // User cancelled or selected No
@@ -0,0 +1,95 @@
+//! Clone Workspace command for the command palette.
+//!
+//! This command clones a repository into the configured directory layout and adds it as a
+//! workspace.
+
+use crate::commands::workspaces::clone::{
+    CloneRequest, clone_workspace, run_on_create, start_session,
+};
+use crate::commands::{Command, CommandCtx};
+use crate::utils::display::{PrettyDisplay, RafaeltabDisplay};
+
+/// Command to clone a repository as a new workspace.
+///
+/// This command guides the user through the following steps:
+/// 1. Enter the url of the repository
+/// 2. Choose whether to run the worktree `onCreate` commands, when there are any
+/// 3. Choose whether to start the tmux session of the workspace
+#[derive(Debug)]
+pub struct CloneWorkspaceCommand;
+
+impl CloneWorkspaceCommand {
+    /// Create a new clone workspace command.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CloneWorkspaceCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Command for CloneWorkspaceCommand {
+    fn name(&self) -> &str {
+        "Clone Workspace"
+    }
+
+    fn description(&self) -> &str {
+        "Clone a repository and add it as a workspace"
+    }
+
+    fn run(&self, ctx: &mut CommandCtx) {
+        let url = match ctx.input("Repository url") {
+            Some(url) if !url.trim().is_empty() => url.trim().to_string(),
+            _ => return,
+        };
+
+        let worktree_config = ctx.worktree_storage().read();
+        let has_on_create = worktree_config
+            .as_ref()
+            .is_some_and(|x| !x.on_create.is_empty());
+        let run_commands = if has_on_create {
+            match ctx.confirm("Run the worktree onCreate commands?", true) {
+                Some(choice) => choice,
+                None => return,
+            }
+        } else {
+            false
+        };
+        let start = match ctx.confirm("Start its tmux session?", true) {
+            Some(choice) => choice,
+            None => return,
+        };
+
+        let _ = ctx.restore();
+        let clone_path = ctx.clone_storage().read().and_then(|x| x.path);
+        let request = CloneRequest {
+            url,
+            name: None,
+            tags: vec![],
+            path: None,
+        };
+        let workspace = match clone_workspace(ctx.workspace_repo(), clone_path.as_deref(), request)
+        {
+            Ok(workspace) => workspace,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                return;
+            }
+        };
+
+        if run_commands && let Err(err) = run_on_create(&workspace, worktree_config.as_ref()) {
+            eprintln!("Error: {}", err);
+        }
+        if start
+            && let Err(err) =
+                start_session(ctx.description_repo(), ctx.session_repo(), &workspace.id)
+        {
+            eprintln!("Error: {}", err);
+        }
+
+        PrettyDisplay {}.display(&workspace);
+    }
+}
@@ -0,0 +1,128 @@
+//! Edit Workspace command for the command palette.
+//!
+//! This command changes the root and tags of a workspace.
+
+use std::env::current_dir;
+
+use crate::commands::builtin::select_workspace::select_workspace;
+use crate::commands::workspaces::edit::edit_tags;
+use crate::commands::{Command, CommandCtx};
+use crate::domain::tmux_workspaces::repositories::workspace::workspace_repository::WorkspaceUpdate;
+use crate::tui::picker_ctx::ExistingTagsSuggestionProvider;
+use crate::utils::display::{PrettyDisplay, RafaeltabDisplay};
+
+/// Command to edit a workspace.
+///
+/// This command guides the user through the following steps:
+/// 1. Pick the workspace
+/// 2. Choose whether to move its root to the current directory
+/// 3. Enter tags to add and tags to remove
+/// 4. Confirm the changes
+#[derive(Debug)]
+pub struct EditWorkspaceCommand;
+
+impl EditWorkspaceCommand {
+    /// Create a new edit workspace command.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for EditWorkspaceCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+impl Command for EditWorkspaceCommand {
+    fn name(&self) -> &str {
+        "Edit Workspace"
+    }
+
+    fn description(&self) -> &str {
+        "Change the root or tags of a workspace"
+    }
+
+    fn run(&self, ctx: &mut CommandCtx) {
+        let Some(workspace) = select_workspace(ctx, "Workspace to edit") else {
+            return;
+        };
+
+        let cwd = current_dir().unwrap().to_str().unwrap().to_string();
+        let root = if cwd == workspace.path {
+            None
+        } else {
+            let prompt = format!("Move the root from '{}' to '{}'?", workspace.path, cwd);
+            match ctx.confirm(&prompt, false) {
+                Some(true) => Some(cwd),
+                Some(false) => None,
+                None => return,
+            }
+        };
+
+        let current_tags: Vec<String> = workspace.tags.iter().map(|t| t.name.clone()).collect();
+        let mut all_tags: Vec<String> = ctx
+            .workspace_repo()
+            .get_workspace_entries()
+            .iter()
+            .flatten()
+            .flat_map(|w| w.tags.iter().map(|t| t.name.clone()))
+            .collect();
+        all_tags.sort();
+        all_tags.dedup();
+
+        let provider = ExistingTagsSuggestionProvider::new(all_tags);
+        let Some(add) =
+            ctx.input_with_suggestions("Tags to add (comma-separated)", Box::new(provider))
+        else {
+            return;
+        };
+        let provider = ExistingTagsSuggestionProvider::new(current_tags.clone());
+        let Some(remove) =
+            ctx.input_with_suggestions("Tags to remove (comma-separated)", Box::new(provider))
+        else {
+            return;
+        };
+        let tags = edit_tags(&current_tags, None, &parse_tags(&add), &parse_tags(&remove))
+            .filter(|tags| *tags != current_tags);
+
+        if root.is_none() && tags.is_none() {
+            return;
+        }
+
+        let tags_display = match &tags {
+            Some(tags) if tags.is_empty() => "(none)".to_string(),
+            Some(tags) => tags.join(", "),
+            None => current_tags.join(", "),
+        };
+        let prompt = format!(
+            "Save '{}' at '{}' with tags [{}]?",
+            workspace.name,
+            root.as_deref().unwrap_or(&workspace.path),
+            tags_display
+        );
+        if ctx.confirm(&prompt, true) != Some(true) {
+            return;
+        }
+
+        let _ = ctx.restore();
+        let update = WorkspaceUpdate {
+            root,
+            tags,
+            ..Default::default()
+        };
+        match ctx.workspace_repo().update_workspace(&workspace.id, update) {
+            Ok(Some(workspace)) => PrettyDisplay {}.display(&workspace),
+            Ok(None) => eprintln!("Error: workspace '{}' does not exist", workspace.id),
+            Err(err) => eprintln!("Error: {}", err),
+        }
+    }
+}
@@ -4,5 +4,18 @@
 //! such as adding workspaces, switching tmux sessions, etc.
 
 pub mod add_workspace;
+pub mod clone_workspace;
+pub mod edit_workspace;
+pub mod new_workspace;
+pub mod remove_workspace;
+pub mod rename_workspace;
+mod select_workspace;
+pub mod stop_session;
 
 pub use add_workspace::AddWorkspaceCommand;
+pub use clone_workspace::CloneWorkspaceCommand;
+pub use edit_workspace::EditWorkspaceCommand;
+pub use new_workspace::NewWorkspaceCommand;
+pub use remove_workspace::RemoveWorkspaceCommand;
+pub use rename_workspace::RenameWorkspaceCommand;
+pub use stop_session::StopSessionCommand;
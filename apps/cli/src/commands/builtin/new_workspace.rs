@@ -0,0 +1,137 @@
+//! New Workspace command for the command palette.
+//!
+//! This command creates a project from one of the configured templates in the current directory
+//! and adds it as a workspace.
+
+use std::collections::HashSet;
+
+use crate::commands::workspaces::clone::start_session;
+use crate::commands::workspaces::new::{NewWorkspaceRequest, new_workspace};
+use crate::commands::{Command, CommandCtx};
+use crate::tui::PickerItem;
+use crate::tui::picker_ctx::ExistingTagsSuggestionProvider;
+use crate::tui::pickers::SimpleItem;
+use crate::utils::display::{PrettyDisplay, RafaeltabDisplay};
+
+/// Command to create a new workspace from a template.
+///
+/// This command guides the user through the following steps:
+/// 1. Pick a template
+/// 2. Enter the workspace name
+/// 3. Enter tags (with suggestions from existing workspaces)
+/// 4. Choose whether to start the tmux session of the workspace
+#[derive(Debug)]
+pub struct NewWorkspaceCommand;
+
+impl NewWorkspaceCommand {
+    /// Create a new new workspace command.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for NewWorkspaceCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Command for NewWorkspaceCommand {
+    fn name(&self) -> &str {
+        "New Workspace"
+    }
+
+    fn description(&self) -> &str {
+        "Create a project from a template in the current directory"
+    }
+
+    fn run(&self, ctx: &mut CommandCtx) {
+        let templates = ctx.template_storage().read();
+        if templates.is_empty() {
+            let _ = ctx.restore();
+            eprintln!("Error: there are no templates configured");
+            return;
+        }
+
+        let items: Vec<SimpleItem> = templates
+            .iter()
+            .map(|x| match &x.description {
+                Some(description) => SimpleItem::new(format!("{} - {}", x.name, description)),
+                None => SimpleItem::new(x.name.clone()),
+            })
+            .collect();
+        let Some(selected) = ctx.select(&items, "Template") else {
+            return;
+        };
+        let Some(template) = items
+            .iter()
+            .position(|x| x.search_text() == selected.search_text())
+            .map(|index| templates[index].name.clone())
+        else {
+            return;
+        };
+
+        let name = match ctx.input("Workspace name") {
+            Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+            _ => return,
+        };
+
+        let mut all_tags: Vec<String> = ctx
+            .workspace_repo()
+            .get_workspace_entries()
+            .iter()
+            .flatten()
+            .flat_map(|w| w.tags.iter().map(|t| t.name.clone()))
+            .collect();
+        all_tags.sort();
+        all_tags.dedup();
+        let tags_provider = ExistingTagsSuggestionProvider::new(all_tags);
+        let Some(tags_input) =
+            ctx.input_with_suggestions("Tags (comma-separated)", Box::new(tags_provider))
+        else {
+            return;
+        };
+        let mut tags: Vec<String> = tags_input
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        tags.sort();
+
+        let start = match ctx.confirm("Start its tmux session?", true) {
+            Some(choice) => choice,
+            None => return,
+        };
+
+        let _ = ctx.restore();
+        let request = NewWorkspaceRequest {
+            name,
+            template,
+            tags,
+            path: None,
+        };
+        let workspace = match new_workspace(
+            ctx.workspace_repo(),
+            ctx.tmux_storage(),
+            &templates,
+            request,
+        ) {
+            Ok(workspace) => workspace,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                return;
+            }
+        };
+
+        if start
+            && let Err(err) =
+                start_session(ctx.description_repo(), ctx.session_repo(), &workspace.id)
+        {
+            eprintln!("Error: {}", err);
+        }
+
+        PrettyDisplay {}.display(&workspace);
+    }
+}
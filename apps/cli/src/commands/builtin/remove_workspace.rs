@@ -0,0 +1,85 @@
+//! Remove Workspace command for the command palette.
+//!
+//! This command removes a workspace and offers to clean up its tmux sessions.
+
+use crate::commands::builtin::select_workspace::select_workspace;
+use crate::commands::workspaces::references::{count_session_config, running_sessions};
+use crate::commands::workspaces::remove::remove_workspace;
+use crate::commands::{Command, CommandCtx};
+
+/// Command to remove a workspace.
+///
+/// This command guides the user through the following steps:
+/// 1. Pick the workspace
+/// 2. Confirm the removal
+/// 3. Choose whether to kill its running tmux sessions
+/// 4. Choose whether to remove its `tmux.sessions` configuration
+#[derive(Debug)]
+pub struct RemoveWorkspaceCommand;
+
+impl RemoveWorkspaceCommand {
+    /// Create a new remove workspace command.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RemoveWorkspaceCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Command for RemoveWorkspaceCommand {
+    fn name(&self) -> &str {
+        "Remove Workspace"
+    }
+
+    fn description(&self) -> &str {
+        "Remove a workspace and optionally its tmux sessions"
+    }
+
+    fn run(&self, ctx: &mut CommandCtx) {
+        let Some(workspace) = select_workspace(ctx, "Workspace to remove") else {
+            return;
+        };
+
+        let prompt = format!("Remove workspace '{}' ({})?", workspace.name, workspace.id);
+        if ctx.confirm(&prompt, false) != Some(true) {
+            return;
+        }
+
+        let sessions = running_sessions(ctx.description_repo(), &workspace.id);
+        let kill_sessions = if sessions.is_empty() {
+            false
+        } else {
+            let prompt = format!("Kill {} running tmux session(s)?", sessions.len());
+            match ctx.confirm(&prompt, true) {
+                Some(choice) => choice,
+                None => return,
+            }
+        };
+
+        let remove_config = if count_session_config(ctx.config_storage(), &workspace.id) == 0 {
+            false
+        } else {
+            match ctx.confirm("Remove its configuration from tmux.sessions?", true) {
+                Some(choice) => choice,
+                None => return,
+            }
+        };
+
+        let _ = ctx.restore();
+        let kill = if kill_sessions { &sessions[..] } else { &[] };
+        match remove_workspace(
+            ctx.config_storage(),
+            ctx.session_repo(),
+            &workspace.id,
+            kill,
+            remove_config,
+        ) {
+            Ok(()) => println!("Removed workspace '{}' ({})", workspace.name, workspace.id),
+            Err(err) => eprintln!("Error: {}", err),
+        }
+    }
+}
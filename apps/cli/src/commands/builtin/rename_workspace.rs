@@ -0,0 +1,103 @@
+//! Rename Workspace command for the command palette.
+//!
+//! This command changes the name of a workspace, optionally deriving a new id from it.
+
+use crate::commands::builtin::add_workspace::slugify;
+use crate::commands::builtin::select_workspace::select_workspace;
+use crate::commands::workspaces::references::running_sessions;
+use crate::commands::workspaces::rename::{SessionAction, apply_session_action, rename_workspace};
+use crate::commands::{Command, CommandCtx};
+use crate::utils::display::{PrettyDisplay, RafaeltabDisplay};
+
+/// Command to rename a workspace.
+///
+/// This command guides the user through the following steps:
+/// 1. Pick the workspace
+/// 2. Enter the new name
+/// 3. Choose whether the id should be derived from the new name
+/// 4. When the id changes, choose whether its running tmux session moves along or is killed
+#[derive(Debug)]
+pub struct RenameWorkspaceCommand;
+
+impl RenameWorkspaceCommand {
+    /// Create a new rename workspace command.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RenameWorkspaceCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Command for RenameWorkspaceCommand {
+    fn name(&self) -> &str {
+        "Rename Workspace"
+    }
+
+    fn description(&self) -> &str {
+        "Change the name of a workspace"
+    }
+
+    fn run(&self, ctx: &mut CommandCtx) {
+        let Some(workspace) = select_workspace(ctx, "Workspace to rename") else {
+            return;
+        };
+
+        let name = match ctx.input("New name") {
+            Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+            _ => return,
+        };
+
+        let new_id = slugify(&name);
+        let reslug = if new_id == workspace.id {
+            false
+        } else {
+            let prompt = format!("Change the id from '{}' to '{}'?", workspace.id, new_id);
+            match ctx.confirm(&prompt, false) {
+                Some(choice) => choice,
+                None => return,
+            }
+        };
+
+        let sessions = if reslug {
+            running_sessions(ctx.description_repo(), &workspace.id)
+        } else {
+            vec![]
+        };
+        let action = if sessions.is_empty() {
+            SessionAction::Keep
+        } else {
+            let prompt = format!(
+                "Move {} running tmux session(s) to the new id?",
+                sessions.len()
+            );
+            match ctx.confirm(&prompt, true) {
+                Some(true) => SessionAction::Move,
+                Some(false) => match ctx.confirm("Kill them instead?", false) {
+                    Some(true) => SessionAction::Kill,
+                    Some(false) => SessionAction::Keep,
+                    None => return,
+                },
+                None => return,
+            }
+        };
+
+        let _ = ctx.restore();
+        match rename_workspace(
+            ctx.workspace_repo(),
+            ctx.config_storage(),
+            &workspace.id,
+            name,
+            reslug.then_some(new_id),
+        ) {
+            Ok(workspace) => {
+                apply_session_action(ctx.session_repo(), &sessions, &workspace, action);
+                PrettyDisplay {}.display(&workspace)
+            }
+            Err(err) => eprintln!("Error: {}", err),
+        }
+    }
+}
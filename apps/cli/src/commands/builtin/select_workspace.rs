@@ -0,0 +1,39 @@
+//! Picking a workspace in the command palette.
+
+use crate::commands::CommandCtx;
+use crate::domain::tmux_workspaces::aggregates::workspaces::workspace::Workspace;
+use crate::storage::frecency::Frecency;
+use crate::tui::PickerItem;
+use crate::tui::pickers::SimpleItem;
+use crate::utils::ranking::sort_by_rank;
+
+/// Let the user pick one of the workspaces, the most important and most used ones come first.
+///
+/// Workspaces whose root can not be resolved are left out. Returns `None` when there are no
+/// workspaces or the user cancels.
+pub(crate) fn select_workspace(ctx: &mut CommandCtx, prompt: &str) -> Option<Workspace> {
+    let workspaces = sort_by_rank(
+        ctx.workspace_repo()
+            .get_workspace_entries()
+            .into_iter()
+            .flatten()
+            .collect(),
+        &ctx.frecency_storage().read(),
+        chrono::Utc::now().timestamp(),
+        |workspace| (workspace.importance, Frecency::workspace_key(&workspace.id)),
+    );
+    if workspaces.is_empty() {
+        return None;
+    }
+
+    let items: Vec<SimpleItem> = workspaces
+        .iter()
+        .map(|x| SimpleItem::new(format!("{} ({})", x.name, x.id)))
+        .collect();
+    let selected = ctx.select(&items, prompt)?;
+
+    let index = items
+        .iter()
+        .position(|x| x.search_text() == selected.search_text())?;
+    workspaces.into_iter().nth(index)
+}
@@ -0,0 +1,82 @@
+//! Stop Session command for the command palette.
+//!
+//! This command kills a running tmux session together with the sessions of its worktrees.
+
+use crate::commands::tmux::stop::{StopTarget, sessions_to_stop};
+use crate::commands::{Command, CommandCtx};
+use crate::domain::tmux_workspaces::aggregates::tmux::include_fields_builder::IncludeFieldsBuilder;
+use crate::tui::PickerItem;
+use crate::tui::pickers::SimpleItem;
+
+/// Command to stop a tmux session.
+///
+/// This command guides the user through the following steps:
+/// 1. Pick a running session
+/// 2. Confirm when programs other than shells are still running in it
+#[derive(Debug)]
+pub struct StopSessionCommand;
+
+impl StopSessionCommand {
+    /// Create a new stop session command.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for StopSessionCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Command for StopSessionCommand {
+    fn name(&self) -> &str {
+        "Stop Session"
+    }
+
+    fn description(&self) -> &str {
+        "Kill a tmux session and the sessions of its worktrees"
+    }
+
+    fn run(&self, ctx: &mut CommandCtx) {
+        let items: Vec<SimpleItem> = ctx
+            .session_repo()
+            .get_sessions(None, IncludeFieldsBuilder::new().build_session())
+            .into_iter()
+            .map(|x| SimpleItem::new(x.name))
+            .collect();
+        if items.is_empty() {
+            let _ = ctx.restore();
+            eprintln!("Error: there are no running sessions");
+            return;
+        }
+        let Some(selected) = ctx.select(&items, "Session to stop") else {
+            return;
+        };
+        let name = selected.search_text().to_string();
+
+        let sessions = sessions_to_stop(
+            ctx.description_repo(),
+            ctx.session_repo(),
+            &StopTarget::Name(&name),
+        );
+        let mut programs: Vec<&str> = sessions
+            .iter()
+            .flat_map(|x| x.programs.iter().map(|x| x.as_str()))
+            .collect();
+        programs.sort();
+        programs.dedup();
+        if !programs.is_empty() {
+            let prompt = format!("Still running {}, stop anyway?", programs.join(", "));
+            if ctx.confirm(&prompt, false) != Some(true) {
+                return;
+            }
+        }
+
+        let _ = ctx.restore();
+        for stop in &sessions {
+            ctx.session_repo().kill_session(Some(&stop.session));
+            println!("Stopped '{}'", stop.session.name);
+        }
+    }
+}
@@ -1,9 +1,27 @@
-use std::fmt::Debug;
+use std::{
+    fmt::{Debug, Display},
+    process::exit,
+};
 
 pub trait RafaeltabCommand<TArgs> {
     fn execute(&self, args: TArgs);
 }
 
+/// Ends a command line command when a result failed
+pub trait OrExit<T> {
+    /// The value, or print the error and exit when there is none
+    fn or_exit(self) -> T;
+}
+
+impl<T, E: Display> OrExit<T> for Result<T, E> {
+    fn or_exit(self) -> T {
+        self.unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            exit(1)
+        })
+    }
+}
+
 /// Trait for commands that can be displayed and executed in the command palette.
 ///
 /// This trait extends `PickerItem` to allow commands to be displayed in
@@ -6,7 +6,15 @@
 use std::io::{self};
 use std::rc::Rc;
 
+use crate::domain::tmux_workspaces::repositories::tmux::description_repository::SessionDescriptionRepository;
+use crate::domain::tmux_workspaces::repositories::tmux::session_repository::TmuxSessionRepository;
 use crate::domain::tmux_workspaces::repositories::workspace::workspace_repository::WorkspaceRepository;
+use crate::storage::clone::CloneStorage;
+use crate::storage::config::ConfigStorage;
+use crate::storage::frecency::FrecencyStorage;
+use crate::storage::template::TemplateStorage;
+use crate::storage::tmux::TmuxStorage;
+use crate::storage::worktree::WorktreeStorage;
 use crate::tui::PickerItem;
 use crate::tui::picker_ctx::{PickerCtx, SuggestionProvider};
 
@@ -14,7 +22,9 @@ use crate::tui::picker_ctx::{PickerCtx, SuggestionProvider};
 ///
 /// `CommandCtx` provides commands with access to:
 /// - Picker methods (select, input, confirm, etc.)
-/// - Workspace repository for data access (optional)
+/// - Workspace repository and tmux, worktree, clone and template storage for data access
+/// - Tmux session repositories for the running sessions
+/// - The usage history used to rank picker items
 /// - Terminal for TUI operations
 ///
 /// # Example
@@ -37,16 +47,30 @@ use crate::tui::picker_ctx::{PickerCtx, SuggestionProvider};
 pub struct CommandCtx {
     picker_ctx: PickerCtx,
     workspace_repo: Rc<dyn WorkspaceRepository>,
+    config_storage: &'static dyn ConfigStorage,
+    description_repo: Rc<dyn SessionDescriptionRepository>,
+    session_repo: Rc<dyn TmuxSessionRepository>,
+    frecency_storage: &'static dyn FrecencyStorage,
 }
 
 impl CommandCtx {
     /// Create a new command context.
-    pub fn new(workspace_repo: Rc<dyn WorkspaceRepository>) -> io::Result<Self> {
+    pub fn new(
+        workspace_repo: Rc<dyn WorkspaceRepository>,
+        config_storage: &'static dyn ConfigStorage,
+        description_repo: Rc<dyn SessionDescriptionRepository>,
+        session_repo: Rc<dyn TmuxSessionRepository>,
+        frecency_storage: &'static dyn FrecencyStorage,
+    ) -> io::Result<Self> {
         let picker_ctx = PickerCtx::new()?;
 
         Ok(Self {
             picker_ctx,
             workspace_repo,
+            config_storage,
+            description_repo,
+            session_repo,
+            frecency_storage,
         })
     }
 
@@ -55,6 +79,46 @@ impl CommandCtx {
         self.workspace_repo.as_ref()
     }
 
+    /// Access the whole configuration, for commands that change several parts of it at once.
+    pub fn config_storage(&self) -> &dyn ConfigStorage {
+        self.config_storage
+    }
+
+    /// Access the tmux configuration.
+    pub fn tmux_storage(&self) -> &dyn TmuxStorage {
+        self.config_storage
+    }
+
+    /// Access the session descriptions, which include the running sessions.
+    pub fn description_repo(&self) -> &dyn SessionDescriptionRepository {
+        self.description_repo.as_ref()
+    }
+
+    /// Access the tmux session repository.
+    pub fn session_repo(&self) -> &dyn TmuxSessionRepository {
+        self.session_repo.as_ref()
+    }
+
+    /// Access the usage history.
+    pub fn frecency_storage(&self) -> &dyn FrecencyStorage {
+        self.frecency_storage
+    }
+
+    /// Access the global worktree configuration.
+    pub fn worktree_storage(&self) -> &dyn WorktreeStorage {
+        self.config_storage
+    }
+
+    /// Access the settings for cloning repositories.
+    pub fn clone_storage(&self) -> &dyn CloneStorage {
+        self.config_storage
+    }
+
+    /// Access the project templates.
+    pub fn template_storage(&self) -> &dyn TemplateStorage {
+        self.config_storage
+    }
+
     /// Display a select picker and return the selected item.
     ///
     /// # Arguments
@@ -11,13 +11,15 @@ use ratatui::widgets::{Paragraph, WidgetRef};
 
 use crate::commands::registry::CommandRegistry;
 use crate::commands::{Command, CommandCtx};
+use crate::storage::frecency::Frecency;
 use crate::tui::picker_item::PickerItem;
 use crate::tui::theme::Theme;
+use crate::utils::ranking::sort_by_rank;
 
 /// The main command palette command.
 ///
 /// This command displays a picker with all registered commands and
-/// executes the selected command.
+/// executes the selected command. The most used commands are listed first.
 ///
 /// # Example
 ///
@@ -73,10 +75,22 @@ impl Command for CommandPalette {
                 command: cmd.clone(),
             })
             .collect();
+        let items = sort_by_rank(
+            items,
+            &ctx.frecency_storage().read(),
+            chrono::Utc::now().timestamp(),
+            |item| (0, Frecency::command_key(&item.name)),
+        );
 
         // Create and run the picker
         let res = ctx.select(&items, "Run a command");
         if let Some(cmd) = res {
+            if let Err(err) = ctx
+                .frecency_storage()
+                .visit(&Frecency::command_key(&cmd.name))
+            {
+                eprintln!("Could not record the command: {}", err);
+            }
             cmd.command.run(ctx);
         }
         // If None, user cancelled - just return
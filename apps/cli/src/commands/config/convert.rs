@@ -0,0 +1,167 @@
+//! Command to convert a configuration file between JSON, TOML and YAML
+
+use std::{fs, io, path::Path, process::exit};
+
+use serde_json::Value;
+
+use crate::{commands::command::RafaeltabCommand, storage::kinds::format::ConfigFormat};
+
+#[derive(Default)]
+pub struct ConfigConvertCommand;
+
+pub struct ConfigConvertOptions {
+    /// The configuration file to read
+    pub input: String,
+    /// The file to write, its extension decides the format
+    pub output: String,
+    /// Overwrite the output file when it already exists
+    pub force: bool,
+}
+
+impl RafaeltabCommand<ConfigConvertOptions> for ConfigConvertCommand {
+    fn execute(&self, options: ConfigConvertOptions) {
+        match convert(&options) {
+            Ok(()) => println!("✓ Converted {} to {}", options.input, options.output),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        }
+    }
+}
+
+fn convert(options: &ConfigConvertOptions) -> Result<(), io::Error> {
+    let input_format = ConfigFormat::from_path(&options.input);
+    let output_format = ConfigFormat::from_path(&options.output);
+
+    if Path::new(&options.output).exists() && !options.force {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists, use --force to overwrite it",
+                options.output
+            ),
+        ));
+    }
+
+    // The raw contents are converted so keys this version does not know about are kept
+    let content = fs::read_to_string(&options.input)?;
+    let mut data: Value = input_format.parse(&content).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", options.input, err),
+        )
+    })?;
+    if output_format == ConfigFormat::Toml {
+        remove_nulls(&mut data);
+    }
+
+    let converted = output_format.serialize(&data)?;
+    let round_trip: Value = output_format.parse(&converted)?;
+    if round_trip != data {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Converting to {:?} would lose data, the file was not written",
+                output_format
+            ),
+        ));
+    }
+
+    fs::write(&options.output, converted)
+}
+
+/// Remove `null` members from every object.
+///
+/// TOML has no null, and every setting treats `null` the same as leaving it out.
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, x| !x.is_null());
+            map.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "workspaces": [
+            { "id": "api", "name": "Api", "root": "~/api", "tags": ["work"] }
+        ],
+        "tmux": {
+            "sessions": [{ "workspace": "api", "windows": [{ "name": "nvim", "command": "nvim ." }] }],
+            "defaultWindows": [{ "name": "zsh" }]
+        },
+        "worktree": { "onCreate": ["pnpm install"] }
+    }"#;
+
+    #[test]
+    fn should_convert_json_to_toml_and_back() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let json = temp_dir.path().join("config.json");
+        let toml = temp_dir.path().join("config.toml");
+        let back = temp_dir.path().join("back.json");
+        fs::write(&json, CONFIG).unwrap();
+
+        for (input, output) in [(&json, &toml), (&toml, &back)] {
+            convert(&ConfigConvertOptions {
+                input: input.to_string_lossy().to_string(),
+                output: output.to_string_lossy().to_string(),
+                force: false,
+            })
+            .unwrap();
+        }
+
+        let original: Value = serde_json::from_str(CONFIG).unwrap();
+        let converted: Value = serde_json::from_str(&fs::read_to_string(&back).unwrap()).unwrap();
+        assert_eq!(converted, original);
+    }
+
+    #[test]
+    fn should_keep_unknown_keys_and_drop_nulls_for_toml() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let json = temp_dir.path().join("config.json");
+        let toml = temp_dir.path().join("config.toml");
+        fs::write(
+            &json,
+            r#"{ "workspaces": [{ "id": "api", "name": "Api", "root": "~/api", "tags": null }], "futureSetting": { "enabled": true } }"#,
+        )
+        .unwrap();
+
+        convert(&ConfigConvertOptions {
+            input: json.to_string_lossy().to_string(),
+            output: toml.to_string_lossy().to_string(),
+            force: false,
+        })
+        .unwrap();
+
+        let converted: Value = ConfigFormat::Toml
+            .parse(&fs::read_to_string(&toml).unwrap())
+            .unwrap();
+        assert_eq!(converted["futureSetting"]["enabled"], true);
+        assert!(converted["workspaces"][0].get("tags").is_none());
+    }
+
+    #[test]
+    fn should_not_overwrite_without_force() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let json = temp_dir.path().join("config.json");
+        let yaml = temp_dir.path().join("config.yaml");
+        fs::write(&json, CONFIG).unwrap();
+        fs::write(&yaml, "existing").unwrap();
+
+        let result = convert(&ConfigConvertOptions {
+            input: json.to_string_lossy().to_string(),
+            output: yaml.to_string_lossy().to_string(),
+            force: false,
+        });
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&yaml).unwrap(), "existing");
+    }
+}
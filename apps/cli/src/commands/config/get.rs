@@ -0,0 +1,42 @@
+//! Command to print a value from the configuration
+
+use std::process::exit;
+
+use crate::{
+    commands::{
+        command::RafaeltabCommand,
+        config::values::{ConfigStorages, ConfigValue},
+    },
+    storage::config_path::ConfigPath,
+    utils::display::RafaeltabDisplay,
+};
+
+#[derive(Default)]
+pub struct ConfigGetCommand;
+
+pub struct ConfigGetOptions<'a> {
+    /// Path of the value, the whole configuration when omitted
+    pub path: Option<String>,
+    pub storages: ConfigStorages<'a>,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+impl RafaeltabCommand<ConfigGetOptions<'_>> for ConfigGetCommand {
+    fn execute(&self, options: ConfigGetOptions) {
+        let config = options.storages.read();
+        let value = match &options.path {
+            None => Ok(config.clone()),
+            Some(path) => path
+                .parse::<ConfigPath>()
+                .and_then(|path| path.get(&config).cloned()),
+        };
+
+        match value {
+            Ok(value) => options.display.display(&ConfigValue { value }),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        }
+    }
+}
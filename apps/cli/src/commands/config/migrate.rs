@@ -0,0 +1,113 @@
+//! Command to migrate the configuration files to the current version
+
+use std::{fs, io, process::exit};
+
+use similar::TextDiff;
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    storage::{
+        kinds::format::ConfigFormat,
+        migrations::{CURRENT_VERSION, backup_message, migrate, migrate_file},
+    },
+};
+
+#[derive(Default)]
+pub struct ConfigMigrateCommand;
+
+pub struct ConfigMigrateOptions {
+    /// The configuration files to migrate, from lowest to highest precedence
+    pub paths: Vec<String>,
+    /// Print the changes instead of writing them
+    pub dry_run: bool,
+}
+
+impl RafaeltabCommand<ConfigMigrateOptions> for ConfigMigrateCommand {
+    fn execute(&self, options: ConfigMigrateOptions) {
+        let mut failed = false;
+        for path in &options.paths {
+            let result = if options.dry_run {
+                preview(path)
+            } else {
+                migrate_file(path).map(|migrated| match migrated {
+                    Some(migrated) => format!(
+                        "✓ Migrated {} from version {} to {}, {}",
+                        path,
+                        migrated.from,
+                        CURRENT_VERSION,
+                        backup_message(path, &migrated.backup)
+                    ),
+                    None => format!("✓ {} is up to date", path),
+                })
+            };
+
+            match result {
+                Ok(output) => println!("{}", output),
+                Err(err) => {
+                    eprintln!("Error: {}: {}", path, err);
+                    failed = true;
+                }
+            }
+        }
+
+        if failed {
+            exit(1);
+        }
+    }
+}
+
+/// Describe the migration of a file as a unified diff, without writing anything
+fn preview(path: &str) -> Result<String, io::Error> {
+    let original = fs::read_to_string(path)?;
+    let Some(migrated) = migrate(ConfigFormat::from_path(path), &original)? else {
+        return Ok(format!("✓ {} is up to date", path));
+    };
+
+    let mut output = format!(
+        "{} would be migrated from version {} to {}:\n",
+        path, migrated.from, CURRENT_VERSION
+    );
+    for description in &migrated.applied {
+        output.push_str(&format!("  - {}\n", description));
+    }
+    if ConfigFormat::from_path(path).has_comments() {
+        output.push_str("  Comments are not kept\n");
+    }
+    let diff = TextDiff::from_lines(&original, &migrated.content)
+        .unified_diff()
+        .header(path, &format!("{} (migrated)", path))
+        .to_string();
+    output.push_str(diff.trim_end());
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_preview_without_writing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let path = path.to_str().unwrap();
+        let original = "{\n  \"workspaces\": [],\n  \"worktree\": null\n}";
+        fs::write(path, original).unwrap();
+
+        let output = preview(path).unwrap();
+
+        assert!(output.contains("from version 0 to 1"));
+        assert!(output.contains("-  \"worktree\": null"));
+        assert!(output.contains("+  \"version\": 1,"));
+        assert_eq!(fs::read_to_string(path).unwrap(), original);
+    }
+
+    #[test]
+    fn should_report_current_files_as_up_to_date() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let path = path.to_str().unwrap();
+        fs::write(path, r#"{ "version": 1 }"#).unwrap();
+
+        assert!(preview(path).unwrap().contains("is up to date"));
+    }
+}
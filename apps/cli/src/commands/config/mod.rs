@@ -0,0 +1,10 @@
+//! Commands for managing the configuration files
+
+pub mod convert;
+pub mod get;
+pub mod migrate;
+pub mod schema;
+pub mod set;
+pub mod unset;
+pub mod validate;
+pub mod values;
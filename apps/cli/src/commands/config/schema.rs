@@ -0,0 +1,16 @@
+//! Command to print the JSON Schema of the configuration file
+
+use crate::{commands::command::RafaeltabCommand, storage::schema::config_schema};
+
+#[derive(Default)]
+pub struct ConfigSchemaCommand;
+
+pub struct ConfigSchemaOptions;
+
+impl RafaeltabCommand<ConfigSchemaOptions> for ConfigSchemaCommand {
+    fn execute(&self, _options: ConfigSchemaOptions) {
+        let schema =
+            serde_json::to_string_pretty(&config_schema()).expect("Schema is always valid JSON");
+        println!("{}", schema);
+    }
+}
@@ -0,0 +1,77 @@
+//! Command to change a value in the configuration
+
+use std::process::exit;
+
+use serde_json::Value;
+
+use crate::{
+    commands::{
+        command::RafaeltabCommand,
+        config::values::{ConfigStorages, ConfigValue},
+    },
+    storage::config_path::ConfigPath,
+    utils::display::RafaeltabDisplay,
+};
+
+#[derive(Default)]
+pub struct ConfigSetCommand;
+
+pub struct ConfigSetOptions<'a> {
+    pub path: String,
+    /// The new value, parsed as JSON when possible and used as a string otherwise
+    pub value: String,
+    pub storages: ConfigStorages<'a>,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+impl RafaeltabCommand<ConfigSetOptions<'_>> for ConfigSetCommand {
+    fn execute(&self, options: ConfigSetOptions) {
+        match set(&options) {
+            Ok(value) => options.display.display(&ConfigValue { value }),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        }
+    }
+}
+
+fn set(options: &ConfigSetOptions) -> Result<Value, String> {
+    let path = options
+        .path
+        .parse::<ConfigPath>()
+        .map_err(|err| err.to_string())?;
+    let value = parse_value(&options.value);
+
+    let mut config = options.storages.read();
+    let resolved = path
+        .set(&mut config, value.clone())
+        .map_err(|err| err.to_string())?;
+    options.storages.write(config)?;
+
+    // Values the configuration can not hold are dropped when it is written, so read it back
+    let config = options.storages.read();
+    match resolved.get(&config) {
+        Ok(stored) => Ok(stored.clone()),
+        Err(_) => Err(format!("`{}` can not be set to {}", options.path, value)),
+    }
+}
+
+fn parse_value(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_json_values_and_fall_back_to_strings() {
+        assert_eq!(parse_value("true"), json!(true));
+        assert_eq!(parse_value("[\"a\"]"), json!(["a"]));
+        assert_eq!(parse_value("pnpm install"), json!("pnpm install"));
+        assert_eq!(parse_value("\"42\""), json!("42"));
+    }
+}
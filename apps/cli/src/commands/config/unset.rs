@@ -0,0 +1,46 @@
+//! Command to remove a value from the configuration
+
+use std::process::exit;
+
+use crate::{
+    commands::{
+        command::RafaeltabCommand,
+        config::values::{ConfigStorages, RemovedConfigValue},
+    },
+    storage::config_path::ConfigPath,
+    utils::display::RafaeltabDisplay,
+};
+
+#[derive(Default)]
+pub struct ConfigUnsetCommand;
+
+pub struct ConfigUnsetOptions<'a> {
+    pub path: String,
+    pub storages: ConfigStorages<'a>,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+impl RafaeltabCommand<ConfigUnsetOptions<'_>> for ConfigUnsetCommand {
+    fn execute(&self, options: ConfigUnsetOptions) {
+        match unset(&options) {
+            Ok(removed) => options.display.display(&removed),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        }
+    }
+}
+
+fn unset(options: &ConfigUnsetOptions) -> Result<RemovedConfigValue, String> {
+    let path = options
+        .path
+        .parse::<ConfigPath>()
+        .map_err(|err| err.to_string())?;
+
+    let mut config = options.storages.read();
+    let value = path.unset(&mut config).map_err(|err| err.to_string())?;
+    options.storages.write(config)?;
+
+    Ok(RemovedConfigValue { path, value })
+}
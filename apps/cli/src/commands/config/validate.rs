@@ -0,0 +1,41 @@
+//! Command to validate the configuration files
+
+use std::process::exit;
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    storage::validation::validate_files,
+    utils::display::{RafaeltabDisplay, ToDynVec},
+};
+
+#[derive(Default)]
+pub struct ConfigValidateCommand;
+
+pub struct ConfigValidateOptions<'a> {
+    /// The configuration files to validate, from lowest to highest precedence
+    pub paths: Vec<String>,
+    /// Whether problems are printed as json
+    pub json: bool,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+impl RafaeltabCommand<ConfigValidateOptions<'_>> for ConfigValidateCommand {
+    fn execute(&self, options: ConfigValidateOptions) {
+        let problems = validate_files(&options.paths);
+
+        if options.json || !problems.is_empty() {
+            options.display.display_list(problems.to_dyn_vec());
+        } else {
+            for path in &options.paths {
+                println!("✓ {}", path);
+            }
+        }
+
+        if !problems.is_empty() {
+            if !options.json {
+                eprintln!("Found {} problem(s)", problems.len());
+            }
+            exit(1);
+        }
+    }
+}
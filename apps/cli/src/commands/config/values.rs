@@ -0,0 +1,120 @@
+//! The merged configuration as a single document, shared by `config get`, `set` and `unset`
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{
+    storage::{
+        clone::CloneConfig,
+        config::{ConfigStorage, ConfigUpdate},
+        config_path::ConfigPath,
+        storage_interface::Storage,
+        template::Template,
+        tmux::Tmux,
+        validation::check_config_value,
+        workspace::Workspace,
+        worktree::WorktreeConfig,
+    },
+    utils::display::RafaeltabDisplayItem,
+};
+
+/// The storages that together make up the configuration
+pub struct ConfigStorages<'a> {
+    pub config_storage: &'a dyn ConfigStorage,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigDocument {
+    workspaces: Vec<Workspace>,
+    tmux: Tmux,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    worktree: Option<WorktreeConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    clone: Option<CloneConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    templates: Vec<Template>,
+}
+
+impl ConfigStorages<'_> {
+    fn document(&self) -> ConfigDocument {
+        ConfigDocument {
+            workspaces: Storage::<Vec<Workspace>>::read(self.config_storage),
+            tmux: Storage::<Tmux>::read(self.config_storage),
+            worktree: Storage::<Option<WorktreeConfig>>::read(self.config_storage),
+            clone: Storage::<Option<CloneConfig>>::read(self.config_storage),
+            templates: Storage::<Vec<Template>>::read(self.config_storage),
+        }
+    }
+
+    /// The merged configuration of all files
+    pub fn read(&self) -> Value {
+        serde_json::to_value(self.document()).expect("Configuration is always valid JSON")
+    }
+
+    /// Check an updated configuration against the typed structs and write the parts that changed
+    pub fn write(&self, value: Value) -> Result<(), String> {
+        if let Some((path, message)) = check_config_value(&value).into_iter().next() {
+            return Err(if path.is_empty() {
+                message
+            } else {
+                format!("{}: {}", path, message)
+            });
+        }
+        let updated: ConfigDocument =
+            serde_json::from_value(value).map_err(|err| err.to_string())?;
+        let current = self.document();
+
+        // The changed parts are written together so a failure leaves every file as it was
+        let update = ConfigUpdate {
+            workspaces: (updated.workspaces != current.workspaces).then_some(updated.workspaces),
+            tmux: (updated.tmux != current.tmux).then_some(updated.tmux),
+            worktree: (updated.worktree != current.worktree).then_some(updated.worktree),
+            clone: (updated.clone != current.clone).then_some(updated.clone),
+            templates: (updated.templates != current.templates).then_some(updated.templates),
+        };
+        if update.is_empty() {
+            return Ok(());
+        }
+        self.config_storage
+            .write_config(&update)
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// A value in the configuration
+pub struct ConfigValue {
+    pub value: Value,
+}
+
+impl RafaeltabDisplayItem for ConfigValue {
+    fn to_json(&self) -> Value {
+        self.value.clone()
+    }
+
+    fn to_pretty_string(&self) -> String {
+        match &self.value {
+            Value::String(value) => value.clone(),
+            value => serde_json::to_string_pretty(value).expect("Values are always valid JSON"),
+        }
+    }
+}
+
+/// A value that was removed from the configuration
+pub struct RemovedConfigValue {
+    pub path: ConfigPath,
+    pub value: Value,
+}
+
+impl RafaeltabDisplayItem for RemovedConfigValue {
+    fn to_json(&self) -> Value {
+        json!({
+            "path": self.path.to_string(),
+            "removed": self.value,
+        })
+    }
+
+    fn to_pretty_string(&self) -> String {
+        format!("✓ Removed {}", self.path)
+    }
+}
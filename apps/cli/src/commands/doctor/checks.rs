@@ -0,0 +1,488 @@
+//! The checks `doctor` runs by default
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use duct::cmd;
+
+use crate::{
+    commands::workspaces::references::remove_session_config,
+    domain::worktree::config::MergedWorktreeConfig,
+    infrastructure::git::{list_worktrees, prune_worktrees},
+    storage::{tmux::Session, validation::validate_files, workspace::Workspace},
+    utils::{
+        display::RafaeltabDisplayItem, interpolation::InterpolationContext, path::expand_path,
+    },
+};
+
+use super::{CheckOutcome, CheckRegistry, DoctorCheck, DoctorContext};
+
+/// The checks in the order they are reported, the configuration is validated last so problems
+/// fixed by earlier checks are not reported
+pub fn default_registry() -> CheckRegistry {
+    let mut registry = CheckRegistry::new();
+    registry
+        .register(VersionCheck::tmux())
+        .register(VersionCheck::git())
+        .register(WorkspaceRootsCheck)
+        .register(EnvironmentCheck)
+        .register(SessionsCheck)
+        .register(WorktreesCheck)
+        .register(SymlinksCheck)
+        .register(ConfigCheck);
+    registry
+}
+
+/// Checks that a program is installed and recent enough
+pub struct VersionCheck {
+    name: &'static str,
+    command: &'static [&'static str],
+    minimum: (u32, u32),
+    /// What needs the minimum version
+    reason: &'static str,
+}
+
+impl VersionCheck {
+    pub fn tmux() -> Self {
+        VersionCheck {
+            name: "tmux",
+            command: &["tmux", "-V"],
+            minimum: (3, 2),
+            reason: "`list-sessions -f` filters and `new-session -e`",
+        }
+    }
+
+    pub fn git() -> Self {
+        VersionCheck {
+            name: "git",
+            command: &["git", "--version"],
+            minimum: (2, 11),
+            reason: "`worktree list --porcelain` and `status --porcelain=v2`",
+        }
+    }
+}
+
+impl DoctorCheck for VersionCheck {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn needs_config(&self) -> bool {
+        false
+    }
+
+    fn run(&self, _context: &DoctorContext) -> CheckOutcome {
+        let (major, minor) = self.minimum;
+        let output = match cmd(self.command[0], &self.command[1..])
+            .stderr_null()
+            .read()
+        {
+            Ok(output) => output.trim().to_string(),
+            Err(_) => {
+                return CheckOutcome::fail(format!("{} is not installed", self.name)).with_hint(
+                    format!("install {} {}.{} or newer", self.name, major, minor),
+                );
+            }
+        };
+
+        match parse_version(&output) {
+            None => CheckOutcome::warn(format!("can not read the version from `{}`", output)),
+            Some(version) if version < self.minimum => {
+                CheckOutcome::fail(format!("{} is older than {}.{}", output, major, minor))
+                    .with_hint(format!(
+                        "{} need {} {}.{} or newer",
+                        self.reason, self.name, major, minor
+                    ))
+            }
+            Some(_) => CheckOutcome::pass(output),
+        }
+    }
+}
+
+/// The major and minor version in the output of `tmux -V` or `git --version`
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let start = output.find(|x: char| x.is_ascii_digit())?;
+    let mut parts = output[start..].split(|x: char| !x.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Checks that the root of every workspace exists
+pub struct WorkspaceRootsCheck;
+
+impl DoctorCheck for WorkspaceRootsCheck {
+    fn name(&self) -> &str {
+        "workspace roots"
+    }
+
+    fn run(&self, context: &DoctorContext) -> CheckOutcome {
+        let workspaces = context.storage().workspace_storage.read();
+        let problems: Vec<String> = workspaces
+            .iter()
+            .filter_map(|workspace| match workspace.resolved_root() {
+                Ok(root) if Path::new(&expand_path(&root)).exists() => None,
+                Ok(root) => Some(format!("{}: {} does not exist", workspace.id, root)),
+                Err(err) => Some(err.to_string()),
+            })
+            .collect();
+
+        if problems.is_empty() {
+            return CheckOutcome::pass(format!("{} workspaces", workspaces.len()));
+        }
+        CheckOutcome::fail(format!(
+            "{} of {} workspace roots do not exist",
+            problems.len(),
+            workspaces.len()
+        ))
+        .with_problems(problems)
+        .with_hint(
+            "remove the workspace with `rafaeltab workspace remove <id>` or move it with `rafaeltab workspace edit <id> --root <path>`",
+        )
+    }
+}
+
+/// Checks that the `env` and `envFile` of every workspace can be resolved
+pub struct EnvironmentCheck;
+
+impl DoctorCheck for EnvironmentCheck {
+    fn name(&self) -> &str {
+        "environment"
+    }
+
+    fn run(&self, context: &DoctorContext) -> CheckOutcome {
+        let workspaces: Vec<(Workspace, PathBuf)> = existing_roots(context)
+            .into_iter()
+            .filter(|(workspace, _)| workspace.env.is_some() || workspace.env_file.is_some())
+            .collect();
+        let problems: Vec<String> = workspaces
+            .iter()
+            .filter_map(|(workspace, _)| workspace.resolved_env().err())
+            .map(|err| err.to_string())
+            .collect();
+
+        if problems.is_empty() {
+            return CheckOutcome::pass(format!(
+                "{} workspaces with environment variables",
+                workspaces.len()
+            ));
+        }
+        CheckOutcome::fail(format!(
+            "{} workspaces have environment variables that can not be loaded",
+            problems.len()
+        ))
+        .with_problems(problems)
+        .with_hint("create the `envFile` or change it with `rafaeltab config set`")
+    }
+}
+
+/// Checks that every `tmux.sessions` entry refers to an existing workspace
+pub struct SessionsCheck;
+
+impl DoctorCheck for SessionsCheck {
+    fn name(&self) -> &str {
+        "sessions"
+    }
+
+    fn run(&self, context: &DoctorContext) -> CheckOutcome {
+        let storage = context.storage();
+        let ids: HashSet<String> = storage
+            .workspace_storage
+            .read()
+            .into_iter()
+            .map(|x| x.id)
+            .collect();
+        let sessions = storage.tmux_storage.read().sessions.unwrap_or_default();
+        let mut unknown: Vec<String> = sessions
+            .iter()
+            .filter_map(|session| match session {
+                Session::Workspace(session) if !ids.contains(&session.workspace) => {
+                    Some(session.workspace.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        unknown.sort();
+        unknown.dedup();
+
+        if unknown.is_empty() {
+            return CheckOutcome::pass(format!("{} sessions", sessions.len()));
+        }
+
+        if context.fix {
+            let mut tmux = storage.tmux_storage.read();
+            for id in &unknown {
+                remove_session_config(&mut tmux, id);
+            }
+            if let Err(err) = storage.tmux_storage.write(&tmux) {
+                return CheckOutcome::fail("sessions of unknown workspaces could not be removed")
+                    .with_problems(vec![err.to_string()]);
+            }
+            let fixed = unknown
+                .iter()
+                .map(|id| format!("removed the session of unknown workspace `{}`", id))
+                .collect();
+            let remaining = tmux.sessions.map_or(0, |x| x.len());
+            return CheckOutcome::pass(format!("{} sessions", remaining)).with_fixed(fixed);
+        }
+
+        CheckOutcome::warn(format!(
+            "{} sessions refer to unknown workspaces",
+            unknown.len()
+        ))
+        .with_problems(
+            unknown
+                .iter()
+                .map(|id| format!("`{}` is not a workspace id", id))
+                .collect(),
+        )
+        .with_hint("run `rafaeltab doctor --fix` to remove them from `tmux.sessions`")
+    }
+}
+
+/// Checks for worktrees that git still knows about while their directory is gone
+pub struct WorktreesCheck;
+
+impl DoctorCheck for WorktreesCheck {
+    fn name(&self) -> &str {
+        "worktrees"
+    }
+
+    fn run(&self, context: &DoctorContext) -> CheckOutcome {
+        let mut checked = 0;
+        let mut problems = vec![];
+        let mut fixed = vec![];
+        let mut seen = HashSet::new();
+
+        for (workspace, root) in existing_roots(context) {
+            let Ok(worktrees) = list_worktrees(&root) else {
+                continue;
+            };
+            // Workspaces can share a repository, it only needs to be checked once
+            if !worktrees
+                .first()
+                .is_some_and(|x| seen.insert(x.path.clone()))
+            {
+                continue;
+            }
+            checked += 1;
+
+            let dangling: Vec<String> = worktrees
+                .iter()
+                .filter(|x| !x.is_main && !x.path.exists())
+                .map(|x| format!("{}: {} ({})", workspace.id, x.path.display(), x.branch))
+                .collect();
+            if dangling.is_empty() {
+                continue;
+            }
+
+            if context.fix {
+                match prune_worktrees(&root) {
+                    Ok(()) => fixed.push(format!(
+                        "pruned {} worktrees of {}",
+                        dangling.len(),
+                        workspace.id
+                    )),
+                    Err(err) => problems.push(format!("{}: {}", workspace.id, err)),
+                }
+            } else {
+                problems.extend(dangling);
+            }
+        }
+
+        if problems.is_empty() {
+            return CheckOutcome::pass(format!("{} repositories", checked)).with_fixed(fixed);
+        }
+        CheckOutcome::warn(format!("{} worktrees no longer exist", problems.len()))
+            .with_problems(problems)
+            .with_fixed(fixed)
+            .with_hint("run `rafaeltab doctor --fix` or `git worktree prune` in the workspace")
+    }
+}
+
+/// Checks for symlinks to files that no longer exist in linked worktrees, such as the ones made
+/// for `worktree.symlinkFiles`
+pub struct SymlinksCheck;
+
+impl DoctorCheck for SymlinksCheck {
+    fn name(&self) -> &str {
+        "symlinks"
+    }
+
+    fn run(&self, context: &DoctorContext) -> CheckOutcome {
+        let storage = context.storage();
+        let global = storage.worktree_storage.read();
+        let mut problems = vec![];
+        let mut fixed = vec![];
+
+        for (workspace, root) in existing_roots(context) {
+            let Ok(worktrees) = list_worktrees(&root) else {
+                continue;
+            };
+            for worktree in worktrees.iter().filter(|x| !x.is_main && x.path.exists()) {
+                let worktree_context =
+                    InterpolationContext::for_workspace(&workspace.id, &root.to_string_lossy())
+                        .with_worktree(&worktree.branch, &worktree.path.to_string_lossy());
+                let Ok(config) = MergedWorktreeConfig::merge_interpolated(
+                    global.as_ref(),
+                    workspace.worktree.as_ref(),
+                    &workspace.id,
+                    &worktree_context,
+                ) else {
+                    continue;
+                };
+
+                for link in broken_symlinks(&worktree.path, &config.symlink_files) {
+                    if context.fix && fs::remove_file(&link).is_ok() {
+                        fixed.push(format!("removed {}", link.display()));
+                    } else {
+                        problems.push(format!("{}: {}", workspace.id, link.display()));
+                    }
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            return CheckOutcome::pass("no broken symlinks").with_fixed(fixed);
+        }
+        CheckOutcome::warn(format!("{} symlinks are broken", problems.len()))
+            .with_problems(problems)
+            .with_fixed(fixed)
+            .with_hint("run `rafaeltab doctor --fix` to remove them")
+    }
+}
+
+/// Symlinks matching one of `patterns` in `directory` whose target does not exist
+fn broken_symlinks(directory: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut result: Vec<PathBuf> = patterns
+        .iter()
+        .filter_map(|pattern| glob::glob(&directory.join(pattern).to_string_lossy()).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|path| path.is_symlink() && !path.exists())
+        .collect();
+    result.sort();
+    result.dedup();
+    result
+}
+
+/// Checks the configuration files like `config validate`
+pub struct ConfigCheck;
+
+impl DoctorCheck for ConfigCheck {
+    fn name(&self) -> &str {
+        "config"
+    }
+
+    fn needs_config(&self) -> bool {
+        false
+    }
+
+    fn run(&self, context: &DoctorContext) -> CheckOutcome {
+        let problems = validate_files(context.config_paths);
+
+        if problems.is_empty() {
+            return CheckOutcome::pass(format!("{} files", context.config_paths.len()));
+        }
+        CheckOutcome::fail(format!("{} problems", problems.len()))
+            .with_problems(problems.iter().map(|x| x.to_pretty_string()).collect())
+            .with_hint("`rafaeltab config schema` describes the valid configuration")
+    }
+}
+
+/// The workspaces whose root exists, with the expanded root
+fn existing_roots(context: &DoctorContext) -> Vec<(Workspace, PathBuf)> {
+    context
+        .storage()
+        .workspace_storage
+        .read()
+        .into_iter()
+        .filter_map(|workspace| {
+            let root = PathBuf::from(expand_path(&workspace.resolved_root().ok()?));
+            root.exists().then_some((workspace, root))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::doctor::{CheckStatus, DoctorStorage},
+        storage::{
+            storage_interface::Storage,
+            test::mocks::{InMemoryStorage, MockWorktreeStorage},
+            tmux::{Tmux, WorkspaceSession},
+        },
+    };
+
+    use super::*;
+
+    fn workspace(id: &str) -> Workspace {
+        Workspace {
+            root: "/".to_string(),
+            id: id.to_string(),
+            name: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn session(workspace: &str) -> Session {
+        Session::Workspace(WorkspaceSession {
+            windows: vec![],
+            workspace: workspace.to_string(),
+            name: None,
+        })
+    }
+
+    #[test]
+    fn should_parse_versions() {
+        assert_eq!(parse_version("tmux 3.3a"), Some((3, 3)));
+        assert_eq!(parse_version("tmux next-3.4"), Some((3, 4)));
+        assert_eq!(parse_version("git version 2.43.0"), Some((2, 43)));
+        assert_eq!(parse_version("tmux master"), None);
+    }
+
+    #[test]
+    fn should_remove_sessions_of_unknown_workspaces_when_fixing() {
+        let workspace_storage = InMemoryStorage::new(vec![workspace("api")]);
+        let tmux_storage = InMemoryStorage::new(Tmux {
+            sessions: Some(vec![session("api"), session("gone")]),
+            default_windows: vec![],
+        });
+        let worktree_storage = MockWorktreeStorage { data: None };
+        let mut context = DoctorContext {
+            config_paths: &[],
+            storage: Some(DoctorStorage {
+                workspace_storage: &workspace_storage,
+                tmux_storage: &tmux_storage,
+                worktree_storage: &worktree_storage,
+            }),
+            fix: false,
+        };
+
+        let outcome = SessionsCheck.run(&context);
+        assert_eq!(outcome.status, CheckStatus::Warn);
+        assert_eq!(outcome.problems, vec!["`gone` is not a workspace id"]);
+
+        context.fix = true;
+        let outcome = SessionsCheck.run(&context);
+        assert_eq!(outcome.status, CheckStatus::Pass);
+        assert_eq!(tmux_storage.read().sessions, Some(vec![session("api")]));
+    }
+
+    #[test]
+    fn should_find_broken_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("target"), "").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("target"), dir.path().join(".env")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("missing"), dir.path().join(".env.local"))
+            .unwrap();
+
+        let result = broken_symlinks(dir.path(), &[".env*".to_string()]);
+
+        assert_eq!(result, vec![dir.path().join(".env.local")]);
+    }
+}
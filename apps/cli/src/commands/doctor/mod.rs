@@ -0,0 +1,327 @@
+//! Command to diagnose problems with the environment and the configuration.
+//!
+//! Every check is a [`DoctorCheck`] in a [`CheckRegistry`], the default checks are registered by
+//! [`checks::default_registry`]. Checks report whether they passed, found something worth a
+//! warning or failed, with a hint on how to solve it. Checks that can solve their problems safely
+//! do so when `--fix` is given.
+
+pub mod checks;
+
+use std::{fmt, process::exit};
+
+use serde_json::{Value, json};
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    storage::{tmux::TmuxStorage, workspace::WorkspaceStorage, worktree::WorktreeStorage},
+    utils::display::{RafaeltabDisplay, RafaeltabDisplayItem},
+};
+
+#[derive(Default)]
+pub struct DoctorCommand;
+
+pub struct DoctorOptions<'a> {
+    pub registry: &'a CheckRegistry,
+    pub context: DoctorContext<'a>,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+/// Everything the checks can inspect
+pub struct DoctorContext<'a> {
+    /// The configuration files, from lowest to highest precedence
+    pub config_paths: &'a [String],
+    /// The loaded configuration, `None` when it could not be loaded
+    pub storage: Option<DoctorStorage<'a>>,
+    /// Apply the automatic fixes
+    pub fix: bool,
+}
+
+pub struct DoctorStorage<'a> {
+    pub workspace_storage: &'a dyn WorkspaceStorage,
+    pub tmux_storage: &'a dyn TmuxStorage,
+    pub worktree_storage: &'a dyn WorktreeStorage,
+}
+
+impl DoctorContext<'_> {
+    /// The loaded configuration, only available to checks that need it
+    pub fn storage(&self) -> &DoctorStorage<'_> {
+        self.storage
+            .as_ref()
+            .expect("Checks that need the configuration only run when it is loaded")
+    }
+}
+
+/// A single diagnostic
+pub trait DoctorCheck {
+    /// Name of the check in the report, such as `tmux`
+    fn name(&self) -> &str;
+
+    /// Whether the check inspects the loaded configuration, it is skipped when that failed to load
+    fn needs_config(&self) -> bool {
+        true
+    }
+
+    fn run(&self, context: &DoctorContext) -> CheckOutcome;
+}
+
+/// Registry of the checks `doctor` runs, in the order they are reported
+#[derive(Default)]
+pub struct CheckRegistry {
+    checks: Vec<Box<dyn DoctorCheck>>,
+}
+
+impl CheckRegistry {
+    /// Create a new empty check registry.
+    pub fn new() -> Self {
+        Self { checks: Vec::new() }
+    }
+
+    /// Register a check in the registry.
+    ///
+    /// Returns a mutable reference to self for method chaining.
+    pub fn register(&mut self, check: impl DoctorCheck + 'static) -> &mut Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Get all registered checks.
+    pub fn checks(&self) -> &[Box<dyn DoctorCheck>] {
+        &self.checks
+    }
+
+    /// Run every check, skipping the ones that need a configuration that could not be loaded
+    pub fn run(&self, context: &DoctorContext) -> DoctorReport {
+        let checks = self
+            .checks
+            .iter()
+            .map(|check| {
+                let outcome = if check.needs_config() && context.storage.is_none() {
+                    CheckOutcome::skip("the configuration could not be loaded")
+                } else {
+                    check.run(context)
+                };
+                CheckReport {
+                    name: check.name().to_string(),
+                    outcome,
+                }
+            })
+            .collect();
+        DoctorReport { checks }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Skip,
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckStatus::Skip => write!(f, "skip"),
+            CheckStatus::Pass => write!(f, "pass"),
+            CheckStatus::Warn => write!(f, "warn"),
+            CheckStatus::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+impl CheckStatus {
+    fn symbol(&self) -> &str {
+        match self {
+            CheckStatus::Skip => "-",
+            CheckStatus::Pass => "✓",
+            CheckStatus::Warn => "!",
+            CheckStatus::Fail => "✗",
+        }
+    }
+}
+
+/// What a check found
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckOutcome {
+    pub status: CheckStatus,
+    /// One line summary, such as `tmux 3.3a`
+    pub summary: String,
+    /// The individual problems that were found
+    pub problems: Vec<String>,
+    /// How to solve the problems
+    pub hint: Option<String>,
+    /// The fixes that were applied
+    pub fixed: Vec<String>,
+}
+
+impl CheckOutcome {
+    fn new(status: CheckStatus, summary: impl Into<String>) -> Self {
+        CheckOutcome {
+            status,
+            summary: summary.into(),
+            problems: vec![],
+            hint: None,
+            fixed: vec![],
+        }
+    }
+
+    pub fn pass(summary: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Pass, summary)
+    }
+
+    pub fn warn(summary: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Warn, summary)
+    }
+
+    pub fn fail(summary: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Fail, summary)
+    }
+
+    pub fn skip(summary: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Skip, summary)
+    }
+
+    pub fn with_problems(mut self, problems: Vec<String>) -> Self {
+        self.problems = problems;
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn with_fixed(mut self, fixed: Vec<String>) -> Self {
+        self.fixed = fixed;
+        self
+    }
+}
+
+pub struct CheckReport {
+    pub name: String,
+    pub outcome: CheckOutcome,
+}
+
+pub struct DoctorReport {
+    pub checks: Vec<CheckReport>,
+}
+
+impl DoctorReport {
+    /// The worst status of all checks
+    pub fn status(&self) -> CheckStatus {
+        self.checks
+            .iter()
+            .map(|x| x.outcome.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass)
+            .max(CheckStatus::Pass)
+    }
+}
+
+impl RafaeltabDisplayItem for DoctorReport {
+    fn to_json(&self) -> Value {
+        json!({
+            "status": self.status().to_string(),
+            "checks": self.checks.iter().map(|check| json!({
+                "name": check.name,
+                "status": check.outcome.status.to_string(),
+                "summary": check.outcome.summary,
+                "problems": check.outcome.problems,
+                "hint": check.outcome.hint,
+                "fixed": check.outcome.fixed,
+            })).collect::<Vec<Value>>(),
+        })
+    }
+
+    fn to_pretty_string(&self) -> String {
+        let mut lines = vec![];
+        for check in &self.checks {
+            let outcome = &check.outcome;
+            lines.push(format!(
+                "{} {}: {}",
+                outcome.status.symbol(),
+                check.name,
+                outcome.summary
+            ));
+            for fixed in &outcome.fixed {
+                lines.push(format!("    fixed: {}", fixed));
+            }
+            for problem in &outcome.problems {
+                lines.push(format!("    {}", problem));
+            }
+            if let Some(hint) = &outcome.hint {
+                lines.push(format!("    hint: {}", hint));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl RafaeltabCommand<DoctorOptions<'_>> for DoctorCommand {
+    fn execute(&self, options: DoctorOptions) {
+        let report = options.registry.run(&options.context);
+        options.display.display(&report);
+
+        if report.status() == CheckStatus::Fail {
+            exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedCheck(CheckStatus, bool);
+
+    impl DoctorCheck for FixedCheck {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn needs_config(&self) -> bool {
+            self.1
+        }
+
+        fn run(&self, _context: &DoctorContext) -> CheckOutcome {
+            CheckOutcome::new(self.0, "ran")
+        }
+    }
+
+    #[test]
+    fn should_skip_checks_that_need_a_missing_config() {
+        let mut registry = CheckRegistry::new();
+        registry
+            .register(FixedCheck(CheckStatus::Warn, false))
+            .register(FixedCheck(CheckStatus::Fail, true));
+        let context = DoctorContext {
+            config_paths: &[],
+            storage: None,
+            fix: false,
+        };
+
+        let report = registry.run(&context);
+
+        let statuses: Vec<CheckStatus> = report.checks.iter().map(|x| x.outcome.status).collect();
+        assert_eq!(statuses, vec![CheckStatus::Warn, CheckStatus::Skip]);
+        assert_eq!(report.status(), CheckStatus::Warn);
+    }
+
+    #[test]
+    fn should_show_problems_and_hints() {
+        let report = DoctorReport {
+            checks: vec![CheckReport {
+                name: "workspace roots".to_string(),
+                outcome: CheckOutcome::fail("1 of 2 workspace roots do not exist")
+                    .with_problems(vec!["api: /src/api".to_string()])
+                    .with_hint("remove the workspace"),
+            }],
+        };
+
+        assert_eq!(
+            report.to_pretty_string(),
+            "✗ workspace roots: 1 of 2 workspace roots do not exist\n    api: /src/api\n    hint: remove the workspace"
+        );
+        assert_eq!(report.to_json()["status"], "fail");
+    }
+}
@@ -2,6 +2,8 @@ pub mod builtin;
 pub mod command;
 pub mod command_ctx;
 pub mod command_palette;
+pub mod config;
+pub mod doctor;
 pub mod registry;
 pub mod test;
 pub mod tmux;
@@ -0,0 +1,247 @@
+use std::{collections::HashSet, path::Path, process::exit};
+
+use atty::Stream;
+use inquire::MultiSelect;
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    domain::tmux_workspaces::{
+        aggregates::{
+            tmux::{include_fields_builder::IncludeFieldsBuilder, session::TmuxSession},
+            workspaces::workspace::Workspace,
+        },
+        repositories::{
+            tmux::{
+                description_repository::SessionDescriptionRepository,
+                session_repository::TmuxSessionRepository,
+            },
+            workspace::workspace_repository::WorkspaceRepository,
+        },
+    },
+    infrastructure::{
+        git::list_worktrees,
+        tmux_workspaces::repositories::tmux::description_repository::{
+            find_session_id, worktree_session_id,
+        },
+    },
+    utils::path::expand_path,
+};
+
+/// Why a running session is no longer wanted
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrphanReason {
+    /// The directory of the session was removed, such as a worktree removed with git
+    MissingDirectory,
+    /// The session was started for a workspace, session or worktree that is gone
+    UnknownId,
+}
+
+pub struct OrphanedSession {
+    pub session: TmuxSession,
+    pub reason: OrphanReason,
+}
+
+impl OrphanedSession {
+    fn reason_text(&self) -> String {
+        match self.reason {
+            OrphanReason::MissingDirectory => format!("{} no longer exists", self.session.path),
+            OrphanReason::UnknownId => "no longer in the configuration or a worktree".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TmuxGcCommand;
+
+pub struct TmuxGcOptions<'a> {
+    pub session_description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    /// Kill every orphaned session without asking
+    pub yes: bool,
+}
+
+impl RafaeltabCommand<TmuxGcOptions<'_>> for TmuxGcCommand {
+    fn execute(
+        &self,
+        TmuxGcOptions {
+            session_description_repository,
+            session_repository,
+            workspace_repository,
+            yes,
+        }: TmuxGcOptions,
+    ) {
+        let orphans = find_orphaned_sessions(
+            session_description_repository,
+            session_repository,
+            workspace_repository,
+        );
+        if orphans.is_empty() {
+            println!("No orphaned sessions");
+            return;
+        }
+
+        let selected = if yes {
+            orphans
+        } else if atty::is(Stream::Stdout) && atty::is(Stream::Stdin) {
+            review(orphans)
+        } else {
+            eprintln!("Error: Not interactive, pass --yes to kill every orphaned session");
+            exit(1);
+        };
+
+        for orphan in &selected {
+            session_repository.kill_session(Some(&orphan.session));
+            println!("Killed '{}', {}", orphan.session.name, orphan.reason_text());
+        }
+    }
+}
+
+/// Let the user pick the sessions to kill, every session is selected to begin with
+fn review(orphans: Vec<OrphanedSession>) -> Vec<OrphanedSession> {
+    let labels: Vec<String> = orphans
+        .iter()
+        .map(|x| format!("{}: {}", x.session.name, x.reason_text()))
+        .collect();
+
+    let selected = MultiSelect::new("Sessions to kill:", labels)
+        .with_all_selected_by_default()
+        .raw_prompt()
+        .unwrap_or_else(|_| {
+            println!("Operation cancelled.");
+            exit(0);
+        });
+
+    let indices: HashSet<usize> = selected.iter().map(|x| x.index).collect();
+    orphans
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| indices.contains(index))
+        .map(|(_, orphan)| orphan)
+        .collect()
+}
+
+/// The running sessions whose directory is gone, or that were started for a description that no
+/// longer exists. Sessions that were not started by rafaeltab are only orphaned by their directory.
+///
+/// Sessions of workspaces that can not be resolved are reported and kept, including the sessions
+/// of their worktrees.
+pub fn find_orphaned_sessions(
+    description_repository: &dyn SessionDescriptionRepository,
+    session_repository: &dyn TmuxSessionRepository,
+    workspace_repository: &dyn WorkspaceRepository,
+) -> Vec<OrphanedSession> {
+    let mut known_ids: HashSet<String> = HashSet::new();
+    let mut unresolved_names: Vec<String> = vec![];
+    for entry in description_repository.get_session_description_entries() {
+        match entry {
+            Ok(description) => known_ids.insert(description.id),
+            Err(unresolved) => {
+                eprintln!(
+                    "Warning: keeping the sessions of '{}': {}",
+                    unresolved.name, unresolved.error
+                );
+                unresolved_names.push(unresolved.name);
+                known_ids.insert(unresolved.id)
+            }
+        };
+    }
+    let workspaces: Vec<Workspace> = workspace_repository
+        .get_workspace_entries()
+        .into_iter()
+        .flatten()
+        .collect();
+    known_ids.extend(worktree_session_ids(&workspaces));
+
+    session_repository
+        .get_sessions(
+            None,
+            IncludeFieldsBuilder::new()
+                .with_environment(true)
+                .build_session(),
+        )
+        .into_iter()
+        .filter_map(|session| {
+            let id = session.environment.as_deref().and_then(find_session_id);
+            let reason = orphan_reason(&session, id.as_deref(), &known_ids)?;
+            // Worktree sessions are named `{workspace}-{branch}`
+            let unresolved = unresolved_names.iter().any(|name| {
+                session.name == *name || session.name.starts_with(&format!("{}-", name))
+            });
+            if reason == OrphanReason::UnknownId && unresolved {
+                return None;
+            }
+            Some(OrphanedSession { session, reason })
+        })
+        .collect()
+}
+
+/// The ids of the sessions of the worktrees that exist, as `git worktree list` reports them
+fn worktree_session_ids(workspaces: &[Workspace]) -> Vec<String> {
+    workspaces
+        .iter()
+        .flat_map(|workspace| {
+            list_worktrees(Path::new(&expand_path(&workspace.path)))
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|x| !x.is_main)
+                .map(|x| worktree_session_id(&format!("{}-{}", workspace.name, x.branch)))
+        })
+        .collect()
+}
+
+fn orphan_reason(
+    session: &TmuxSession,
+    id: Option<&str>,
+    known_ids: &HashSet<String>,
+) -> Option<OrphanReason> {
+    if !Path::new(&session.path).exists() {
+        return Some(OrphanReason::MissingDirectory);
+    }
+    match id {
+        Some(id) if !known_ids.contains(id) => Some(OrphanReason::UnknownId),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::tmux_workspaces::aggregates::tmux::session::SessionIncludeFields;
+
+    fn session(path: &str) -> TmuxSession {
+        TmuxSession {
+            id: "$1".to_string(),
+            name: "api-feat".to_string(),
+            path: path.to_string(),
+            windows: None,
+            environment: None,
+            include_fields: SessionIncludeFields {
+                windows: None,
+                environment: Some(()),
+            },
+        }
+    }
+
+    #[test]
+    fn should_orphan_sessions_by_directory_and_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().to_string_lossy().to_string();
+        let missing = dir.path().join("removed").to_string_lossy().to_string();
+        let known_ids: HashSet<String> = ["known".to_string()].into();
+
+        assert_eq!(
+            orphan_reason(&session(&missing), Some("known"), &known_ids),
+            Some(OrphanReason::MissingDirectory)
+        );
+        assert_eq!(
+            orphan_reason(&session(&existing), Some("unknown"), &known_ids),
+            Some(OrphanReason::UnknownId)
+        );
+        assert_eq!(
+            orphan_reason(&session(&existing), Some("known"), &known_ids),
+            None
+        );
+        assert_eq!(orphan_reason(&session(&existing), None, &known_ids), None);
+    }
+}
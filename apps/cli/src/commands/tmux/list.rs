@@ -1,6 +1,9 @@
 use crate::{
     commands::command::RafaeltabCommand,
-    domain::tmux_workspaces::repositories::tmux::description_repository::SessionDescriptionRepository,
+    domain::tmux_workspaces::{
+        aggregates::workspaces::tag_query::TagQuery,
+        repositories::tmux::description_repository::SessionDescriptionRepository,
+    },
     utils::display::{RafaeltabDisplay, ToDynVec},
 };
 
@@ -10,6 +13,8 @@ pub struct TmuxListCommand;
 pub struct TmuxListOptions<'a> {
     pub display: &'a dyn RafaeltabDisplay,
     pub session_description_repository: &'a dyn SessionDescriptionRepository,
+    /// Only list the sessions of workspaces whose tags match
+    pub filter: Option<&'a TagQuery>,
 }
 
 impl RafaeltabCommand<TmuxListOptions<'_>> for TmuxListCommand {
@@ -18,9 +23,23 @@ impl RafaeltabCommand<TmuxListOptions<'_>> for TmuxListCommand {
         TmuxListOptions {
             display,
             session_description_repository,
+            filter,
         }: TmuxListOptions,
     ) {
-        let descriptions = session_description_repository.get_session_descriptions();
+        let descriptions: Vec<_> = session_description_repository
+            .get_session_description_entries()
+            .into_iter()
+            .filter_map(|x| {
+                x.inspect_err(|unresolved| {
+                    eprintln!(
+                        "Warning: skipping '{}': {}",
+                        unresolved.name, unresolved.error
+                    )
+                })
+                .ok()
+            })
+            .filter(|description| filter.is_none_or(|filter| filter.matches(description.tags())))
+            .collect();
 
         display.display_list(descriptions.to_dyn_vec());
     }
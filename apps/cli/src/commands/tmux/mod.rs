@@ -1,5 +1,10 @@
+pub mod gc;
 pub mod legacy;
 pub mod list;
+pub mod restore;
+pub mod save;
 pub mod session_utils;
 pub mod start;
+pub mod status;
+pub mod stop;
 pub mod switch;
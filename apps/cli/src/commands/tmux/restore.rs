@@ -0,0 +1,193 @@
+use uuid::Uuid;
+
+use crate::{
+    commands::command::{OrExit, RafaeltabCommand},
+    domain::tmux_workspaces::{
+        aggregates::tmux::{
+            description::{
+                pane::PaneDescription,
+                session::{PathSessionDescription, SessionDescription, SessionKind},
+                window::WindowDescription,
+            },
+            include_fields_builder::IncludeFieldsBuilder,
+        },
+        repositories::tmux::{
+            description_repository::SessionDescriptionRepository,
+            session_repository::TmuxSessionRepository,
+        },
+    },
+    storage::{
+        snapshot::{SessionSnapshot, SnapshotStorage, WindowSnapshot},
+        tmux::PaneSplit,
+    },
+};
+
+#[derive(Default)]
+pub struct TmuxRestoreCommand;
+
+pub struct TmuxRestoreOptions<'a> {
+    pub session_description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub snapshot_storage: &'a dyn SnapshotStorage,
+    /// Name of the snapshot to restore
+    pub name: &'a str,
+}
+
+impl RafaeltabCommand<TmuxRestoreOptions<'_>> for TmuxRestoreCommand {
+    fn execute(
+        &self,
+        TmuxRestoreOptions {
+            session_description_repository,
+            session_repository,
+            snapshot_storage,
+            name,
+        }: TmuxRestoreOptions,
+    ) {
+        let snapshots = snapshot_storage.read();
+        let Some(snapshot) = snapshots.snapshots.get(name) else {
+            eprintln!("Error: there is no snapshot named '{}'", name);
+            std::process::exit(1);
+        };
+
+        let mut descriptions = session_description_repository
+            .get_session_descriptions()
+            .or_exit();
+        let running: Vec<String> = session_repository
+            .get_sessions(None, IncludeFieldsBuilder::new().build_session())
+            .into_iter()
+            .map(|x| x.name)
+            .collect();
+
+        let mut restored = 0;
+        for session in &snapshot.sessions {
+            // Configured sessions keep their workspace and environment, the windows come from the
+            // snapshot
+            let described = descriptions.iter().position(|x| {
+                session.id.as_ref().is_some_and(|id| *id == x.id) || x.name == session.name
+            });
+            let described = described.map(|index| descriptions.swap_remove(index));
+            if running.contains(&session.name)
+                || described.as_ref().is_some_and(|x| x.session.is_some())
+            {
+                println!("Skipped '{}', it is already running", session.name);
+                continue;
+            }
+
+            session_repository.new_session(&restore_description(session, described));
+            restored += 1;
+        }
+
+        println!("Restored {} sessions from snapshot '{}'", restored, name);
+    }
+}
+
+fn restore_description(
+    session: &SessionSnapshot,
+    described: Option<SessionDescription>,
+) -> SessionDescription {
+    let windows = session.windows.iter().map(restore_window).collect();
+    match described {
+        Some(description) => SessionDescription {
+            name: session.name.clone(),
+            windows,
+            ..description
+        },
+        None => SessionDescription {
+            id: session
+                .id
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            name: session.name.clone(),
+            kind: SessionKind::Path(PathSessionDescription {
+                path: session.path.clone(),
+            }),
+            windows,
+            env: Default::default(),
+            session: None,
+        },
+    }
+}
+
+/// Describe a saved window, the panes are created next to each other and put in place by the
+/// saved layout
+fn restore_window(window: &WindowSnapshot) -> WindowDescription {
+    let (command, cwd, panes, layout) = match window.panes.as_slice() {
+        [pane] => (pane.command.clone(), Some(pane.cwd.clone()), None, None),
+        panes => (
+            None,
+            None,
+            Some(PaneDescription {
+                split: PaneSplit::Horizontal,
+                panes: panes
+                    .iter()
+                    .map(|pane| PaneDescription {
+                        command: pane.command.clone(),
+                        cwd: Some(pane.cwd.clone()),
+                        focus: pane.active,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .filter(|x| !x.panes.is_empty()),
+            Some(window.layout.clone()),
+        ),
+    };
+
+    WindowDescription {
+        name: window.name.clone(),
+        command,
+        env: Default::default(),
+        panes,
+        layout,
+        cwd,
+        background: false,
+        focus: window.active,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::snapshot::PaneSnapshot;
+
+    fn window(panes: Vec<PaneSnapshot>) -> WindowSnapshot {
+        WindowSnapshot {
+            name: "dev".to_string(),
+            layout: "layout".to_string(),
+            active: true,
+            panes,
+        }
+    }
+
+    fn pane(command: Option<&str>, active: bool) -> PaneSnapshot {
+        PaneSnapshot {
+            cwd: "/src/api".to_string(),
+            command: command.map(|x| x.to_string()),
+            active,
+        }
+    }
+
+    #[test]
+    fn should_restore_a_single_pane_as_the_window() {
+        let description = restore_window(&window(vec![pane(Some("nvim"), true)]));
+
+        assert_eq!(description.command, Some("nvim".to_string()));
+        assert_eq!(description.cwd, Some("/src/api".to_string()));
+        assert_eq!(description.panes, None);
+        assert_eq!(description.layout, None);
+        assert!(description.focus);
+    }
+
+    #[test]
+    fn should_restore_split_windows_with_their_layout() {
+        let description =
+            restore_window(&window(vec![pane(Some("nvim"), false), pane(None, true)]));
+
+        let panes = description.panes.expect("Expected the window to be split");
+        assert_eq!(panes.panes.len(), 2);
+        assert_eq!(panes.panes[0].command, Some("nvim".to_string()));
+        assert!(panes.panes[1].focus);
+        assert_eq!(description.layout, Some("layout".to_string()));
+    }
+}
@@ -0,0 +1,191 @@
+use std::process::Command;
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    domain::tmux_workspaces::{
+        aggregates::tmux::{
+            include_fields_builder::IncludeFieldsBuilder, pane::TmuxPane, session::TmuxSession,
+            window::TmuxWindow,
+        },
+        repositories::tmux::{
+            description_repository::SessionDescriptionRepository,
+            session_repository::TmuxSessionRepository,
+        },
+    },
+    storage::snapshot::{PaneSnapshot, SessionSnapshot, Snapshot, SnapshotStorage, WindowSnapshot},
+};
+
+#[derive(Default)]
+pub struct TmuxSaveCommand;
+
+pub struct TmuxSaveOptions<'a> {
+    pub session_description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub snapshot_storage: &'a dyn SnapshotStorage,
+    /// Name of the snapshot, an existing snapshot with this name is replaced
+    pub name: &'a str,
+}
+
+impl RafaeltabCommand<TmuxSaveOptions<'_>> for TmuxSaveCommand {
+    fn execute(
+        &self,
+        TmuxSaveOptions {
+            session_description_repository,
+            session_repository,
+            snapshot_storage,
+            name,
+        }: TmuxSaveOptions,
+    ) {
+        // Sessions that can not be resolved are still saved, only without their id
+        let descriptions: Vec<_> = session_description_repository
+            .get_session_description_entries()
+            .into_iter()
+            .flatten()
+            .collect();
+        let sessions = session_repository.get_sessions(
+            None,
+            IncludeFieldsBuilder::new()
+                .with_windows(true)
+                .with_panes(true)
+                .build_session(),
+        );
+
+        let sessions: Vec<SessionSnapshot> = sessions
+            .iter()
+            .map(|session| {
+                let id = descriptions
+                    .iter()
+                    .find(|x| x.session.as_ref().is_some_and(|s| s.id == session.id))
+                    .map(|x| x.id.clone());
+                session_snapshot(session, id, &command_line)
+            })
+            .collect();
+        let count = sessions.len();
+
+        let mut snapshots = snapshot_storage.read();
+        snapshots.snapshots.insert(
+            name.to_string(),
+            Snapshot {
+                saved_at: chrono::Utc::now().timestamp(),
+                sessions,
+            },
+        );
+        if let Err(err) = snapshot_storage.write(&snapshots) {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+
+        println!("Saved {} sessions to snapshot '{}'", count, name);
+    }
+}
+
+fn session_snapshot(
+    session: &TmuxSession,
+    id: Option<String>,
+    command_line: &dyn Fn(&TmuxPane) -> Option<String>,
+) -> SessionSnapshot {
+    SessionSnapshot {
+        name: session.name.clone(),
+        path: session.path.clone(),
+        id,
+        windows: session
+            .windows
+            .iter()
+            .flatten()
+            .map(|window| window_snapshot(window, command_line))
+            .collect(),
+    }
+}
+
+fn window_snapshot(
+    window: &TmuxWindow,
+    command_line: &dyn Fn(&TmuxPane) -> Option<String>,
+) -> WindowSnapshot {
+    WindowSnapshot {
+        name: window.name.clone(),
+        layout: window.layout.clone(),
+        active: window.active,
+        panes: window
+            .panes
+            .iter()
+            .flatten()
+            .map(|pane| PaneSnapshot {
+                cwd: pane.current_path.clone(),
+                command: pane.program().and_then(|_| command_line(pane)),
+                active: pane.active,
+            })
+            .collect(),
+    }
+}
+
+/// The full command line of the program in the foreground of a pane
+///
+/// tmux only knows the name of that program, the arguments come from `ps`. When they can not be
+/// found the pane is saved without a command, restoring `cargo` without `watch -x test` would
+/// run something else entirely.
+fn command_line(pane: &TmuxPane) -> Option<String> {
+    let process_group = ps(&["-o", "tpgid=", "-p", &pane.pid])?;
+    ps(&["-o", "args=", "-p", &process_group])
+}
+
+fn ps(args: &[&str]) -> Option<String> {
+    let output = Command::new("ps").args(args).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(stdout).filter(|x| output.status.success() && !x.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::tmux_workspaces::aggregates::tmux::window::WindowIncludeFields;
+
+    fn pane(command: &str, active: bool) -> TmuxPane {
+        TmuxPane {
+            id: "%1".to_string(),
+            index: "0".to_string(),
+            title: "".to_string(),
+            window_id: "@1".to_string(),
+            pid: "1".to_string(),
+            current_path: "/src/api".to_string(),
+            current_command: command.to_string(),
+            active,
+        }
+    }
+
+    fn window(panes: Vec<TmuxPane>) -> TmuxWindow {
+        TmuxWindow {
+            id: "@1".to_string(),
+            index: "0".to_string(),
+            name: "dev".to_string(),
+            panes: Some(panes),
+            layout: "b25d,80x24,0,0,0".to_string(),
+            active: true,
+            activity: false,
+            include_fields: WindowIncludeFields { panes: Some(()) },
+        }
+    }
+
+    #[test]
+    fn should_not_keep_shells_as_commands() {
+        let window = window(vec![pane("nvim", false), pane("zsh", true)]);
+
+        let snapshot = window_snapshot(&window, &|_| Some("nvim src/main.rs".to_string()));
+
+        assert_eq!(
+            snapshot.panes[0].command,
+            Some("nvim src/main.rs".to_string())
+        );
+        assert_eq!(snapshot.panes[1].command, None);
+        assert!(snapshot.panes[1].active);
+        assert!(snapshot.active);
+    }
+
+    #[test]
+    fn should_not_keep_commands_without_their_arguments() {
+        let window = window(vec![pane("cargo", true)]);
+
+        let snapshot = window_snapshot(&window, &|_| None);
+
+        assert_eq!(snapshot.panes[0].command, None);
+    }
+}
@@ -1,28 +1,41 @@
 //! Utility functions for tmux session management
 
+use std::path::Path;
+
 use crate::{
-    domain::tmux_workspaces::aggregates::tmux::description::window::WindowDescription,
-    domain::tmux_workspaces::repositories::tmux::session_repository::TmuxSessionRepository,
+    domain::tmux_workspaces::{
+        aggregates::{
+            tmux::{
+                description::{
+                    session::{PathSessionDescription, SessionDescription, SessionKind},
+                    window::WindowDescription,
+                },
+                include_fields_builder::IncludeFieldsBuilder,
+            },
+            workspaces::workspace::Workspace,
+        },
+        repositories::tmux::session_repository::TmuxSessionRepository,
+    },
+    infrastructure::{
+        git::{self, WorktreeInfo},
+        tmux_workspaces::repositories::tmux::description_repository::worktree_session_id,
+    },
     storage::tmux::{Session, TmuxStorage},
+    utils::{
+        env::EnvError,
+        interpolation::{InterpolationContext, InterpolationError},
+        path::expand_path,
+    },
 };
 
 /// Create tmux sessions for all worktrees in a workspace.
 /// This runs after the main workspace session has been created.
 /// Errors are silently ignored (TODO: add logging when available).
 pub fn create_worktree_sessions(
-    workspace: &crate::domain::tmux_workspaces::aggregates::workspaces::workspace::Workspace,
+    workspace: &Workspace,
     session_repository: &dyn TmuxSessionRepository,
     tmux_storage: &dyn TmuxStorage,
 ) {
-    use crate::domain::tmux_workspaces::aggregates::tmux::description::session::{
-        PathSessionDescription, SessionDescription, SessionKind,
-    };
-    use crate::domain::tmux_workspaces::aggregates::tmux::include_fields_builder::IncludeFieldsBuilder;
-    use crate::infrastructure::git;
-    use crate::utils::path::expand_path;
-    use std::path::Path;
-    use uuid::{Uuid, uuid};
-
     let workspace_path = expand_path(&workspace.path);
     let workspace_path = Path::new(&workspace_path);
 
@@ -39,12 +52,9 @@ pub fn create_worktree_sessions(
         return; // No worktrees to create sessions for
     }
 
-    // Get window configuration for this workspace
-    let windows = get_windows_for_workspace(&workspace.id, tmux_storage);
-
     // Create session for each worktree
     for worktree_info in worktrees {
-        let session_name = format!("{}-{}", workspace.name, worktree_info.branch);
+        let session_name = worktree_session_name(workspace, &worktree_info);
 
         // Check if session already exists
         let existing_sessions =
@@ -54,18 +64,13 @@ pub fn create_worktree_sessions(
             continue;
         }
 
-        // Create session description
-        let worktree_namespace = uuid!("f47ac10b-58cc-4372-a567-0e02b2c3d479");
-        let id = Uuid::new_v5(&worktree_namespace, session_name.as_bytes());
-
-        let description = SessionDescription {
-            id: id.to_string(),
-            name: session_name,
-            kind: SessionKind::Path(PathSessionDescription {
-                path: worktree_info.path.to_string_lossy().to_string(),
-            }),
-            windows: windows.clone(),
-            session: None,
+        // A worktree whose windows or environment can not be resolved is reported and skipped
+        let description = match describe_worktree_session(workspace, &worktree_info, tmux_storage) {
+            Ok(description) => description,
+            Err(err) => {
+                eprintln!("Warning: skipping session '{}': {}", session_name, err);
+                continue;
+            }
         };
 
         // Create the session (ignore errors silently for now)
@@ -74,12 +79,45 @@ pub fn create_worktree_sessions(
     }
 }
 
+fn worktree_session_name(workspace: &Workspace, worktree_info: &WorktreeInfo) -> String {
+    format!("{}-{}", workspace.name, worktree_info.branch)
+}
+
+/// Describe the session of a worktree, it runs in the worktree with the windows of its workspace.
+///
+/// `${workspace.root}` stays the main checkout, `${worktree.root}` is the directory of the worktree.
+pub fn describe_worktree_session(
+    workspace: &Workspace,
+    worktree_info: &WorktreeInfo,
+    tmux_storage: &dyn TmuxStorage,
+) -> Result<SessionDescription, EnvError> {
+    let worktree_path = worktree_info.path.to_string_lossy().to_string();
+    let context = InterpolationContext::for_workspace(&workspace.id, &expand_path(&workspace.path))
+        .with_worktree(&worktree_info.branch, &worktree_path);
+    let windows = get_windows_for_workspace(&workspace.id, tmux_storage, &context)?;
+    let env = workspace.environment(&context)?;
+    let session_name = worktree_session_name(workspace, worktree_info);
+
+    Ok(SessionDescription {
+        id: worktree_session_id(&session_name),
+        name: session_name,
+        kind: SessionKind::Path(PathSessionDescription {
+            path: worktree_path,
+        }),
+        windows,
+        env,
+        session: None,
+    })
+}
+
 /// Get window configuration for a workspace session.
 /// Returns workspace-specific windows if configured, otherwise returns default windows.
+/// Variables in window commands are expanded with `context`.
 pub fn get_windows_for_workspace(
     workspace_id: &str,
     tmux_storage: &dyn TmuxStorage,
-) -> Vec<WindowDescription> {
+    context: &InterpolationContext,
+) -> Result<Vec<WindowDescription>, InterpolationError> {
     let tmux_config = tmux_storage.read();
 
     // Check if workspace has custom session config
@@ -88,27 +126,17 @@ pub fn get_windows_for_workspace(
             if let Session::Workspace(ws_session) = session
                 && ws_session.workspace == workspace_id
             {
-                return ws_session
-                    .windows
-                    .iter()
-                    .map(|w| WindowDescription {
-                        name: w.name.clone(),
-                        command: w.command.clone(),
-                    })
-                    .collect();
+                return WindowDescription::from_config(
+                    &ws_session.windows,
+                    context,
+                    &format!("tmux.sessions[workspace={}].windows", workspace_id),
+                );
             }
         }
     }
 
     // Fall back to default windows
-    tmux_config
-        .default_windows
-        .iter()
-        .map(|w| WindowDescription {
-            name: w.name.clone(),
-            command: w.command.clone(),
-        })
-        .collect()
+    WindowDescription::from_config(&tmux_config.default_windows, context, "tmux.defaultWindows")
 }
 
 #[cfg(test)]
@@ -128,16 +156,20 @@ mod tests {
                     Window {
                         name: "editor".to_string(),
                         command: Some("vim".to_string()),
+                        ..Default::default()
                     },
                     Window {
                         name: "shell".to_string(),
                         command: None,
+                        ..Default::default()
                     },
                 ],
             },
         };
 
-        let result = get_windows_for_workspace("test-workspace", &storage);
+        let result =
+            get_windows_for_workspace("test-workspace", &storage, &InterpolationContext::default())
+                .unwrap();
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].name, "editor");
@@ -162,21 +194,26 @@ mod tests {
                         Window {
                             name: "nvim".to_string(),
                             command: Some("nvim .".to_string()),
+                            ..Default::default()
                         },
                         Window {
                             name: "build".to_string(),
                             command: Some("npm run dev".to_string()),
+                            ..Default::default()
                         },
                     ],
                 })]),
                 default_windows: vec![Window {
                     name: "default".to_string(),
                     command: None,
+                    ..Default::default()
                 }],
             },
         };
 
-        let result = get_windows_for_workspace("my-workspace", &storage);
+        let result =
+            get_windows_for_workspace("my-workspace", &storage, &InterpolationContext::default())
+                .unwrap();
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].name, "nvim");
@@ -205,16 +242,20 @@ mod tests {
                     windows: vec![Window {
                         name: "custom".to_string(),
                         command: None,
+                        ..Default::default()
                     }],
                 })]),
                 default_windows: vec![Window {
                     name: "default".to_string(),
                     command: None,
+                    ..Default::default()
                 }],
             },
         };
 
-        let result = get_windows_for_workspace("workspace-b", &storage);
+        let result =
+            get_windows_for_workspace("workspace-b", &storage, &InterpolationContext::default())
+                .unwrap();
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].name, "default");
@@ -229,8 +270,72 @@ mod tests {
             },
         };
 
-        let result = get_windows_for_workspace("test-workspace", &storage);
+        let result =
+            get_windows_for_workspace("test-workspace", &storage, &InterpolationContext::default())
+                .unwrap();
 
         assert_eq!(result.len(), 0);
     }
+
+    #[test]
+    fn test_keeps_window_directory_and_flags() {
+        let storage = MockTmuxStorage {
+            data: Tmux {
+                sessions: None,
+                default_windows: vec![Window {
+                    name: "server".to_string(),
+                    command: Some("pnpm dev".to_string()),
+                    cwd: Some("apps/${worktree.branch}".to_string()),
+                    background: Some(true),
+                    focus: Some(true),
+                    ..Default::default()
+                }],
+            },
+        };
+
+        let context = InterpolationContext::for_workspace("test-workspace", "/src/test")
+            .with_worktree("feature", "/src/test-feature");
+        let result = get_windows_for_workspace("test-workspace", &storage, &context).unwrap();
+
+        assert_eq!(result[0].cwd, Some("apps/feature".to_string()));
+        assert!(result[0].background);
+        assert!(result[0].focus);
+    }
+
+    #[test]
+    fn test_renders_worktree_windows_in_the_worktree() {
+        let storage = MockTmuxStorage {
+            data: Tmux {
+                sessions: None,
+                default_windows: vec![Window {
+                    name: "editor".to_string(),
+                    command: Some("cp ${workspace.root}/.env ${worktree.root} && nvim".to_string()),
+                    ..Default::default()
+                }],
+            },
+        };
+        let workspace = Workspace {
+            id: "api".to_string(),
+            name: "Api".to_string(),
+            path: "/src/api".to_string(),
+            ..Default::default()
+        };
+        let worktree_info = WorktreeInfo {
+            path: "/src/api-feature".into(),
+            branch: "feature".to_string(),
+            is_main: false,
+        };
+
+        let description = describe_worktree_session(&workspace, &worktree_info, &storage).unwrap();
+
+        assert_eq!(description.name, "Api-feature");
+        assert_eq!(
+            description.windows[0].command,
+            Some("cp /src/api/.env /src/api-feature && nvim".to_string())
+        );
+        let SessionKind::Path(path) = &description.kind else {
+            panic!("A worktree session should run in the worktree");
+        };
+        assert_eq!(path.path, "/src/api-feature");
+    }
 }
@@ -1,8 +1,11 @@
 use crate::{
     commands::command::RafaeltabCommand,
-    domain::tmux_workspaces::repositories::tmux::{
-        description_repository::SessionDescriptionRepository,
-        session_repository::TmuxSessionRepository,
+    domain::tmux_workspaces::{
+        aggregates::workspaces::tag_query::TagQuery,
+        repositories::tmux::{
+            description_repository::SessionDescriptionRepository,
+            session_repository::TmuxSessionRepository,
+        },
     },
     storage::tmux::TmuxStorage,
 };
@@ -14,6 +17,8 @@ pub struct TmuxStartOptions<'a> {
     pub session_description_repository: &'a dyn SessionDescriptionRepository,
     pub session_repository: &'a dyn TmuxSessionRepository,
     pub tmux_storage: &'a dyn TmuxStorage,
+    /// Only start the sessions of workspaces whose tags match
+    pub filter: Option<&'a TagQuery>,
 }
 
 impl RafaeltabCommand<TmuxStartOptions<'_>> for TmuxStartCommand {
@@ -23,11 +28,27 @@ impl RafaeltabCommand<TmuxStartOptions<'_>> for TmuxStartCommand {
             session_description_repository,
             session_repository,
             tmux_storage,
+            filter,
         }: TmuxStartOptions,
     ) {
-        let descriptions = session_description_repository.get_session_descriptions();
+        // Sessions that can not be resolved are reported, the others are still started
+        let descriptions = session_description_repository
+            .get_session_description_entries()
+            .into_iter()
+            .filter_map(|x| {
+                x.inspect_err(|unresolved| {
+                    eprintln!(
+                        "Warning: skipping '{}': {}",
+                        unresolved.name, unresolved.error
+                    )
+                })
+                .ok()
+            });
 
         for description in descriptions {
+            if filter.is_some_and(|filter| !filter.matches(description.tags())) {
+                continue;
+            }
             if description.session.is_none() {
                 let session = session_repository.new_session(&description);
 
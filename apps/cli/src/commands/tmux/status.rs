@@ -0,0 +1,306 @@
+use std::{env, path::Path};
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    domain::{
+        tmux_workspaces::{
+            aggregates::{
+                tmux::{include_fields_builder::IncludeFieldsBuilder, session::TmuxSession},
+                workspaces::workspace::Workspace,
+            },
+            repositories::{
+                tmux::session_repository::TmuxSessionRepository,
+                workspace::workspace_repository::WorkspaceRepository,
+            },
+        },
+        worktree::config::find_most_specific_workspace,
+    },
+    infrastructure::{
+        git::{get_repository_status, get_root_worktree_path},
+        tmux_workspaces::repositories::tmux::description_repository::{
+            find_session_id, workspace_session_id,
+        },
+    },
+    storage::status_cache::{CachedGitStatus, StatusCacheStorage},
+    utils::path::expand_path,
+};
+
+/// Template `tmux status` prints without `--template`
+pub const DEFAULT_STATUS_TEMPLATE: &str = "#[bold]{workspace}#[nobold] {branch}{dirty}{activity}";
+
+/// Seconds the git state of a directory is reused for without `--max-age`
+pub const DEFAULT_STATUS_MAX_AGE: u64 = 10;
+
+#[derive(Default)]
+pub struct TmuxStatusCommand;
+
+pub struct TmuxStatusOptions<'a> {
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub status_cache_storage: &'a dyn StatusCacheStorage,
+    /// Text to print, `{workspace}`, `{branch}`, `{dirty}` and `{activity}` are replaced
+    pub template: &'a str,
+    /// Directory to show the workspace of, such as `#{pane_current_path}`
+    pub path: Option<&'a str>,
+    /// Session to show the status of, such as `#{session_id}`, the session in `$TMUX` without it
+    pub session: Option<&'a str>,
+    /// Seconds the git state of a directory is reused for
+    pub max_age: u64,
+}
+
+/// The values that are filled into the template
+struct StatusValues<'a> {
+    workspace: &'a str,
+    branch: Option<&'a str>,
+    dirty: bool,
+    /// Number of other sessions with activity
+    activity: usize,
+}
+
+impl RafaeltabCommand<TmuxStatusOptions<'_>> for TmuxStatusCommand {
+    fn execute(
+        &self,
+        TmuxStatusOptions {
+            workspace_repository,
+            session_repository,
+            status_cache_storage,
+            template,
+            path,
+            session,
+            max_age,
+        }: TmuxStatusOptions,
+    ) {
+        // The status line shows nothing for workspaces that can not be resolved
+        let workspaces: Vec<Workspace> = workspace_repository
+            .get_workspace_entries()
+            .into_iter()
+            .flatten()
+            .collect();
+        let sessions = session_repository.get_sessions(
+            None,
+            IncludeFieldsBuilder::new()
+                .with_windows(true)
+                .build_session(),
+        );
+        let current_session_id = session.map(|x| x.to_string()).or_else(tmux_session_id);
+        let current_session = sessions
+            .iter()
+            .find(|x| Some(&x.id) == current_session_id.as_ref());
+        let activity = sessions
+            .iter()
+            .filter(|x| Some(&x.id) != current_session_id.as_ref())
+            .filter(|x| has_activity(x))
+            .count();
+
+        // Sessions of workspaces know their workspace, other sessions are found by their directory
+        let session_workspace = current_session
+            .and_then(|x| find_session_id(&session_repository.get_environment(&x.id)))
+            .and_then(|id| {
+                workspaces
+                    .iter()
+                    .find(|x| workspace_session_id(&x.id) == id)
+            });
+        let path = match path {
+            Some(path) => expand_path(path),
+            None => session_workspace
+                .map(|x| expand_path(&x.path))
+                .or_else(|| current_session.map(|x| x.path.clone()))
+                .unwrap_or_else(|| {
+                    env::current_dir()
+                        .map(|x| x.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                }),
+        };
+
+        let git = cached_git_status(status_cache_storage, &path, max_age);
+        // Worktrees live next to the workspace, so they are found by the root of their repository
+        let workspace = find_workspace(&workspaces, &path)
+            .or_else(|| {
+                git.root
+                    .as_deref()
+                    .and_then(|root| find_workspace(&workspaces, root))
+            })
+            .or(session_workspace);
+
+        // Outside of a workspace the status line stays empty
+        let Some(workspace) = workspace else {
+            return;
+        };
+
+        println!(
+            "{}",
+            render(
+                template,
+                &StatusValues {
+                    workspace: &workspace.name,
+                    branch: git.branch.as_deref(),
+                    dirty: git.dirty,
+                    activity,
+                }
+            )
+        );
+    }
+}
+
+/// The id of the session tmux runs the command for, taken from `$TMUX`
+fn tmux_session_id() -> Option<String> {
+    let tmux = env::var("TMUX").ok()?;
+    let id = tmux.rsplit(',').next()?;
+    Some(format!("${}", id))
+}
+
+fn has_activity(session: &TmuxSession) -> bool {
+    session.windows.iter().flatten().any(|x| x.activity)
+}
+
+fn find_workspace<'a>(workspaces: &'a [Workspace], path: &str) -> Option<&'a Workspace> {
+    let workspace_paths: Vec<(&str, String)> = workspaces
+        .iter()
+        .map(|x| (x.id.as_str(), expand_path(&x.path)))
+        .collect();
+    let id = find_most_specific_workspace(
+        path,
+        workspace_paths
+            .iter()
+            .map(|(id, path)| (*id, path.as_str())),
+    )?;
+    workspaces.iter().find(|x| x.id == id)
+}
+
+/// The git state of a directory, git only runs when the cached state is older than `max_age`
+fn cached_git_status(
+    storage: &dyn StatusCacheStorage,
+    path: &str,
+    max_age: u64,
+) -> CachedGitStatus {
+    let now = chrono::Utc::now().timestamp();
+    let mut cache = storage.read();
+    if let Some(status) = cache.paths.get(path)
+        && status.is_fresh(now, max_age)
+    {
+        return status.clone();
+    }
+
+    let status = read_git_status(Path::new(path), now);
+    cache.paths.retain(|_, x| x.is_fresh(now, max_age));
+    cache.paths.insert(path.to_string(), status.clone());
+    // The cache only saves work, the status line is still right when it can not be written
+    let _ = storage.write(&cache);
+    status
+}
+
+fn read_git_status(path: &Path, now: i64) -> CachedGitStatus {
+    let Ok(status) = get_repository_status(path) else {
+        return CachedGitStatus {
+            checked_at: now,
+            ..Default::default()
+        };
+    };
+
+    CachedGitStatus {
+        checked_at: now,
+        root: get_root_worktree_path(path)
+            .ok()
+            .map(|x| x.to_string_lossy().to_string()),
+        dirty: status.is_dirty(),
+        branch: status.branch,
+    }
+}
+
+/// Fill the values into the template, `{dirty}` and `{activity}` are empty when there is nothing
+/// to show so the template does not need conditionals
+fn render(template: &str, values: &StatusValues) -> String {
+    let activity = match values.activity {
+        0 => "".to_string(),
+        count => format!("+{}", count),
+    };
+    template
+        .replace("{workspace}", values.workspace)
+        .replace("{branch}", values.branch.unwrap_or(""))
+        .replace("{dirty}", if values.dirty { "*" } else { "" })
+        .replace("{activity}", &activity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        kinds::status_cache_storage::JsonStatusCacheStorage, storage_interface::Storage,
+    };
+
+    #[test]
+    fn should_fill_values_into_template() {
+        let values = StatusValues {
+            workspace: "api",
+            branch: Some("feat/login"),
+            dirty: true,
+            activity: 2,
+        };
+
+        let result = render(
+            "#[fg=blue]{workspace}#[default] {branch}{dirty} {activity}",
+            &values,
+        );
+
+        assert_eq!(result, "#[fg=blue]api#[default] feat/login* +2");
+    }
+
+    #[test]
+    fn should_leave_out_values_with_nothing_to_show() {
+        let values = StatusValues {
+            workspace: "api",
+            branch: None,
+            dirty: false,
+            activity: 0,
+        };
+
+        assert_eq!(
+            render(DEFAULT_STATUS_TEMPLATE, &values),
+            "#[bold]api#[nobold] "
+        );
+    }
+
+    #[test]
+    fn should_use_fresh_cached_state_without_running_git() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage =
+            JsonStatusCacheStorage::with_path(dir.path().join("cache.json").to_string_lossy());
+        let cached = CachedGitStatus {
+            checked_at: chrono::Utc::now().timestamp(),
+            root: None,
+            branch: Some("cached".to_string()),
+            dirty: true,
+        };
+        let mut cache = storage.read();
+        cache
+            .paths
+            .insert("/no/repository".to_string(), cached.clone());
+        storage.write(&cache).unwrap();
+
+        assert_eq!(cached_git_status(&storage, "/no/repository", 60), cached);
+    }
+
+    #[test]
+    fn should_refresh_stale_cached_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage =
+            JsonStatusCacheStorage::with_path(dir.path().join("cache.json").to_string_lossy());
+        let mut cache = storage.read();
+        cache.paths.insert(
+            "/no/repository".to_string(),
+            CachedGitStatus {
+                checked_at: 0,
+                root: None,
+                branch: Some("cached".to_string()),
+                dirty: true,
+            },
+        );
+        storage.write(&cache).unwrap();
+
+        let status = cached_git_status(&storage, "/no/repository", 60);
+
+        assert_eq!(status.branch, None);
+        assert!(!status.dirty);
+        assert_eq!(storage.read().paths.get("/no/repository"), Some(&status));
+    }
+}
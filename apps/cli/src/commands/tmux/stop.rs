@@ -0,0 +1,226 @@
+use std::path::Path;
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    domain::tmux_workspaces::{
+        aggregates::{
+            tmux::{
+                description::session::{SessionDescription, SessionKind},
+                include_fields_builder::IncludeFieldsBuilder,
+                session::TmuxSession,
+            },
+            workspaces::tag_query::TagQuery,
+        },
+        repositories::tmux::{
+            description_repository::SessionDescriptionRepository,
+            session_repository::TmuxSessionRepository,
+        },
+    },
+    infrastructure::git,
+    utils::path::expand_path,
+};
+
+/// Which sessions `tmux stop` stops
+pub enum StopTarget<'a> {
+    /// Every session from the configuration
+    All,
+    /// The sessions of workspaces whose tags match
+    Tag(&'a TagQuery),
+    /// A session by name, or the session of a workspace by id
+    Name(&'a str),
+}
+
+/// A running session that is about to be stopped
+pub struct SessionToStop {
+    pub session: TmuxSession,
+    /// The programs other than shells that run in its panes
+    pub programs: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct TmuxStopCommand;
+
+pub struct TmuxStopOptions<'a> {
+    pub session_description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub target: StopTarget<'a>,
+    /// Only list the sessions that would be stopped
+    pub dry_run: bool,
+}
+
+impl RafaeltabCommand<TmuxStopOptions<'_>> for TmuxStopCommand {
+    fn execute(
+        &self,
+        TmuxStopOptions {
+            session_description_repository,
+            session_repository,
+            target,
+            dry_run,
+        }: TmuxStopOptions,
+    ) {
+        let sessions =
+            sessions_to_stop(session_description_repository, session_repository, &target);
+        if sessions.is_empty() {
+            println!("No running sessions to stop");
+            return;
+        }
+
+        for SessionToStop { session, programs } in &sessions {
+            if dry_run {
+                if programs.is_empty() {
+                    println!("Would stop '{}'", session.name);
+                } else {
+                    println!(
+                        "Would stop '{}', running {}",
+                        session.name,
+                        programs.join(", ")
+                    );
+                }
+                continue;
+            }
+
+            if !programs.is_empty() {
+                eprintln!(
+                    "Warning: '{}' is running {}",
+                    session.name,
+                    programs.join(", ")
+                );
+            }
+            session_repository.kill_session(Some(session));
+            println!("Stopped '{}'", session.name);
+        }
+    }
+}
+
+/// The running sessions that match `target`, together with the worktree sessions of the matching
+/// workspaces
+pub fn sessions_to_stop(
+    description_repository: &dyn SessionDescriptionRepository,
+    session_repository: &dyn TmuxSessionRepository,
+    target: &StopTarget,
+) -> Vec<SessionToStop> {
+    let running = session_repository.get_sessions(
+        None,
+        IncludeFieldsBuilder::new()
+            .with_windows(true)
+            .with_panes(true)
+            .build_session(),
+    );
+    // Sessions that can not be resolved are only found by their name
+    let descriptions: Vec<SessionDescription> = description_repository
+        .get_session_description_entries()
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let matches = |description: &SessionDescription| match target {
+        StopTarget::All => true,
+        StopTarget::Tag(query) => query.matches(description.tags()),
+        StopTarget::Name(name) => {
+            description.name == *name
+                || matches!(&description.kind, SessionKind::Workspace(workspace) if workspace.id == *name)
+        }
+    };
+
+    let mut ids: Vec<&str> = vec![];
+    let mut names: Vec<String> = vec![];
+    for description in descriptions.iter().filter(|x| matches(x)) {
+        if let Some(session) = &description.session {
+            ids.push(&session.id);
+        }
+        if let SessionKind::Workspace(workspace) = &description.kind {
+            names.extend(worktree_session_names(&workspace.name, &workspace.path));
+        }
+    }
+    if let StopTarget::Name(name) = target {
+        names.push(name.to_string());
+    }
+
+    running
+        .into_iter()
+        .filter(|x| ids.contains(&x.id.as_str()) || names.contains(&x.name))
+        .map(|session| SessionToStop {
+            programs: programs(&session),
+            session,
+        })
+        .collect()
+}
+
+/// Worktree sessions are named after the workspace and the branch of the worktree
+fn worktree_session_names(workspace_name: &str, workspace_path: &str) -> Vec<String> {
+    git::discover_worktrees_for_workspace(Path::new(&expand_path(workspace_path)))
+        .unwrap_or_default()
+        .iter()
+        .map(|worktree| format!("{}-{}", workspace_name, worktree.branch))
+        .collect()
+}
+
+fn programs(session: &TmuxSession) -> Vec<String> {
+    let mut programs: Vec<String> = session
+        .windows
+        .iter()
+        .flatten()
+        .flat_map(|window| window.panes.iter().flatten())
+        .filter_map(|pane| pane.program())
+        .map(|x| x.to_string())
+        .collect();
+    programs.sort();
+    programs.dedup();
+    programs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::tmux_workspaces::aggregates::tmux::{
+        pane::TmuxPane,
+        session::SessionIncludeFields,
+        window::{TmuxWindow, WindowIncludeFields},
+    };
+
+    fn pane(command: &str) -> TmuxPane {
+        TmuxPane {
+            id: "%1".to_string(),
+            index: "0".to_string(),
+            title: "".to_string(),
+            window_id: "@1".to_string(),
+            pid: "1".to_string(),
+            current_path: "/src/api".to_string(),
+            current_command: command.to_string(),
+            active: false,
+        }
+    }
+
+    fn window(panes: Vec<TmuxPane>) -> TmuxWindow {
+        TmuxWindow {
+            id: "@1".to_string(),
+            index: "0".to_string(),
+            name: "dev".to_string(),
+            panes: Some(panes),
+            layout: "".to_string(),
+            active: false,
+            activity: false,
+            include_fields: WindowIncludeFields { panes: Some(()) },
+        }
+    }
+
+    #[test]
+    fn should_list_every_program_once_without_shells() {
+        let session = TmuxSession {
+            id: "$1".to_string(),
+            name: "api".to_string(),
+            path: "/src/api".to_string(),
+            windows: Some(vec![
+                window(vec![pane("nvim"), pane("zsh")]),
+                window(vec![pane("node"), pane("nvim"), pane("bash")]),
+            ]),
+            environment: None,
+            include_fields: SessionIncludeFields {
+                windows: Some(WindowIncludeFields { panes: Some(()) }),
+                environment: None,
+            },
+        };
+
+        assert_eq!(programs(&session), vec!["node", "nvim"]);
+    }
+}
@@ -14,7 +14,9 @@ use crate::domain::tmux_workspaces::aggregates::tmux::description::session::Sess
 use crate::domain::tmux_workspaces::repositories::tmux::client_repository::{
     SwitchClientTarget, TmuxClientRepository,
 };
+use crate::storage::frecency::FrecencyStorage;
 use crate::storage::tmux::TmuxStorage;
+use crate::utils::ranking::sort_by_rank;
 use crate::utils::with_terminal;
 use crate::{
     commands::command::RafaeltabCommand,
@@ -32,6 +34,7 @@ pub struct TmuxSwitchOptions<'a> {
     pub session_repository: &'a dyn TmuxSessionRepository,
     pub client_repository: &'a dyn TmuxClientRepository,
     pub tmux_storage: &'a dyn TmuxStorage,
+    pub frecency_storage: &'a dyn FrecencyStorage,
 }
 
 impl RafaeltabCommand<TmuxSwitchOptions<'_>> for TmuxSwitchCommand {
@@ -42,9 +45,28 @@ impl RafaeltabCommand<TmuxSwitchOptions<'_>> for TmuxSwitchCommand {
             session_repository,
             client_repository,
             tmux_storage,
+            frecency_storage,
         }: TmuxSwitchOptions,
     ) {
-        let descriptions = session_description_repository.get_session_descriptions();
+        // The picker keeps this order for equal matches, so the most used sessions come first
+        let descriptions = sort_by_rank(
+            session_description_repository
+                .get_session_description_entries()
+                .into_iter()
+                .filter_map(|x| {
+                    x.inspect_err(|unresolved| {
+                        eprintln!(
+                            "Warning: skipping '{}': {}",
+                            unresolved.name, unresolved.error
+                        )
+                    })
+                    .ok()
+                })
+                .collect(),
+            &frecency_storage.read(),
+            chrono::Utc::now().timestamp(),
+            |description| (description.importance(), description.frecency_key()),
+        );
 
         let res = fuzzy_pick(FuzzySearchArgs {
             items: &descriptions,
@@ -60,6 +82,9 @@ impl RafaeltabCommand<TmuxSwitchOptions<'_>> for TmuxSwitchCommand {
             };
 
             client_repository.switch_client(None, SwitchClientTarget::Session(session));
+            if let Err(err) = frecency_storage.visit(&selected_session.frecency_key()) {
+                eprintln!("Could not record the switch: {}", err);
+            }
 
             // Create worktree sessions if this is a workspace session
             use crate::domain::tmux_workspaces::aggregates::tmux::description::session::SessionKind;
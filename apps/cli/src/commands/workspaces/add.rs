@@ -1,3 +1,5 @@
+use std::process::exit;
+
 use atty::Stream;
 use inquire::{Confirm, Text};
 
@@ -35,20 +37,30 @@ impl RafaeltabCommand<WorkspaceAddOptions<'_>> for WorkspaceAddCommand {
 
         let prompt_data = prompt_data(&options);
 
-        // Build an id
-        let id = prompt_data.name.to_lowercase().replace(' ', "_");
+        let id = workspace_id(&prompt_data.name);
 
-        let workspace = &options.workspace_repository.create_workspace(
+        let workspace = match options.workspace_repository.create_workspace(
             prompt_data.name,
             prompt_data.tags,
             path,
             id,
-        );
+        ) {
+            Ok(workspace) => workspace,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        };
 
-        let _ = &options.display.display(workspace);
+        let _ = &options.display.display(&workspace);
     }
 }
 
+/// Build the id of a workspace from its name
+pub fn workspace_id(name: &str) -> String {
+    name.to_lowercase().replace(' ', "_")
+}
+
 fn prompt_data(options: &WorkspaceAddOptions) -> PromptData {
     let interactive = match options.interactive {
         Some(i) => i,
@@ -0,0 +1,422 @@
+//! Command to clone a repository and add it as a workspace
+
+use std::{collections::HashSet, fmt, fs, path::Path, process::exit};
+
+use duct::cmd;
+
+use crate::{
+    commands::{
+        builtin::add_workspace::{slugify, unique_id},
+        command::RafaeltabCommand,
+    },
+    domain::{
+        tmux_workspaces::{
+            aggregates::{tmux::session::TmuxSession, workspaces::workspace::Workspace},
+            repositories::{
+                tmux::{
+                    description_repository::SessionDescriptionRepository,
+                    session_repository::TmuxSessionRepository,
+                },
+                workspace::workspace_repository::WorkspaceRepository,
+            },
+        },
+        worktree::config::MergedWorktreeConfig,
+    },
+    infrastructure::{
+        git::{GitError, clone_repository, get_current_branch},
+        tmux_workspaces::repositories::tmux::description_repository::workspace_session_id,
+    },
+    storage::{
+        clone::{CloneStorage, DEFAULT_CLONE_PATH},
+        error::StorageError,
+        worktree::{WorktreeConfig, WorktreeStorage},
+    },
+    utils::{
+        display::RafaeltabDisplay,
+        env::{EnvError, with_env},
+        interpolation::{InterpolationContext, InterpolationError, interpolate},
+        path::expand_path,
+    },
+};
+
+#[derive(Default)]
+pub struct WorkspaceCloneCommand;
+
+pub struct WorkspaceCloneOptions<'a> {
+    /// Url or path of the repository
+    pub url: String,
+    /// Name of the workspace, the repository name when omitted
+    pub name: Option<String>,
+    /// Tags added to the tags inferred from the url
+    pub tags: Vec<String>,
+    /// Directory to clone into instead of the one from `clone.path`
+    pub path: Option<String>,
+    /// Run the worktree `onCreate` commands in the clone
+    pub run_on_create: bool,
+    /// Start the tmux session of the new workspace
+    pub start: bool,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub clone_storage: &'a dyn CloneStorage,
+    pub worktree_storage: &'a dyn WorktreeStorage,
+    pub description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+/// The parts of a repository url that are used to pick the directory to clone into
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepositoryUrl {
+    /// Host of the remote, `local` for `file://` urls and paths
+    pub host: String,
+    /// Everything between the host and the repository, e.g. `rafaeltab` or `group/subgroup`
+    pub owner: String,
+    /// Name of the repository without `.git`
+    pub repo: String,
+}
+
+/// What to clone and how to register it
+pub struct CloneRequest {
+    pub url: String,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum CloneError {
+    /// The url does not contain a repository name
+    InvalidUrl(String),
+    /// The directory to clone into already has content
+    DestinationExists(String),
+    Interpolation(InterpolationError),
+    Environment(EnvError),
+    Git(GitError),
+    Storage(StorageError),
+    /// An `onCreate` command failed
+    OnCreateFailed {
+        command: String,
+        error: String,
+    },
+}
+
+impl fmt::Display for CloneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloneError::InvalidUrl(url) => write!(f, "'{}' is not a repository url", url),
+            CloneError::DestinationExists(path) => write!(f, "'{}' already exists", path),
+            CloneError::Interpolation(err) => write!(f, "{}", err),
+            CloneError::Environment(err) => write!(f, "{}", err),
+            CloneError::Git(err) => write!(f, "{}", err),
+            CloneError::Storage(err) => write!(f, "{}", err),
+            CloneError::OnCreateFailed { command, error } => {
+                write!(f, "onCreate command '{}' failed: {}", command, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CloneError {}
+
+impl RafaeltabCommand<WorkspaceCloneOptions<'_>> for WorkspaceCloneCommand {
+    fn execute(&self, options: WorkspaceCloneOptions) {
+        let request = CloneRequest {
+            url: options.url,
+            name: options.name,
+            tags: options.tags,
+            path: options.path,
+        };
+        let clone_path = options.clone_storage.read().and_then(|x| x.path);
+        let workspace =
+            match clone_workspace(options.workspace_repository, clone_path.as_deref(), request) {
+                Ok(workspace) => workspace,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    exit(1);
+                }
+            };
+
+        if options.run_on_create
+            && let Err(err) = run_on_create(&workspace, options.worktree_storage.read().as_ref())
+        {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+
+        if options.start
+            && let Err(err) = start_session(
+                options.description_repository,
+                options.session_repository,
+                &workspace.id,
+            )
+        {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+
+        options.display.display(&workspace);
+    }
+}
+
+/// Clone a repository and register it as a workspace.
+///
+/// The repository is cloned into `request.path` or the `clone_path` layout, the workspace is
+/// named after the repository and tagged with its owner.
+pub fn clone_workspace(
+    workspace_repository: &dyn WorkspaceRepository,
+    clone_path: Option<&str>,
+    request: CloneRequest,
+) -> Result<Workspace, CloneError> {
+    let url = parse_repository_url(&request.url)
+        .ok_or_else(|| CloneError::InvalidUrl(request.url.clone()))?;
+    let destination = match &request.path {
+        Some(path) => expand_path(path),
+        None => clone_destination(clone_path.unwrap_or(DEFAULT_CLONE_PATH), &url)
+            .map_err(|err| CloneError::Interpolation(err.at("clone.path")))?,
+    };
+
+    let is_empty_dir = Path::new(&destination)
+        .read_dir()
+        .is_ok_and(|mut x| x.next().is_none());
+    if Path::new(&destination).exists() && !is_empty_dir {
+        return Err(CloneError::DestinationExists(destination));
+    }
+
+    clone_repository(&request.url, Path::new(&destination)).map_err(CloneError::Git)?;
+
+    let ids: HashSet<String> = workspace_repository
+        .get_workspace_ids()
+        .into_iter()
+        .collect();
+    let name = request.name.unwrap_or_else(|| url.repo.clone());
+    let id = unique_id(&slugify(&name), &ids);
+    let mut tags: Vec<String> = url
+        .owner
+        .split('/')
+        .map(slugify)
+        .filter(|x| !x.is_empty())
+        .collect();
+    for tag in request.tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    workspace_repository
+        .create_workspace(name, tags, destination.clone(), id)
+        .map_err(|err| {
+            // A clone without a workspace would make the next attempt fail on the destination
+            let _ = fs::remove_dir_all(&destination);
+            if is_empty_dir {
+                let _ = fs::create_dir(&destination);
+            }
+            CloneError::Storage(err)
+        })
+}
+
+/// Run the merged worktree `onCreate` commands in the root of a workspace.
+///
+/// Commands run one after another and stop at the first that fails.
+pub fn run_on_create(
+    workspace: &Workspace,
+    global: Option<&WorktreeConfig>,
+) -> Result<(), CloneError> {
+    let root = expand_path(&workspace.path);
+    let mut context = InterpolationContext::for_workspace(&workspace.id, &root);
+    // The clone is the main checkout, so it is the root of the worktree as well
+    if let Ok(branch) = get_current_branch(Path::new(&root)) {
+        context = context.with_worktree(&branch, &root);
+    }
+    let config = MergedWorktreeConfig::merge_interpolated(
+        global,
+        workspace.worktree.as_ref(),
+        &workspace.id,
+        &context,
+    )
+    .map_err(CloneError::Interpolation)?;
+    let env = workspace
+        .environment(&context)
+        .map_err(CloneError::Environment)?;
+
+    for command in &config.on_create {
+        eprintln!("  Running: {}", command);
+        let output = with_env(cmd!("sh", "-c", command), &env)
+            .dir(&root)
+            .stderr_to_stdout()
+            .read()
+            .map_err(|err| CloneError::OnCreateFailed {
+                command: command.clone(),
+                error: err.to_string(),
+            })?;
+        for line in output.lines() {
+            eprintln!("    {}", line);
+        }
+        eprintln!("  ✓ Completed: {}", command);
+    }
+    Ok(())
+}
+
+/// Start the tmux session of a workspace, unless it is running already
+pub fn start_session(
+    description_repository: &dyn SessionDescriptionRepository,
+    session_repository: &dyn TmuxSessionRepository,
+    workspace_id: &str,
+) -> Result<Option<TmuxSession>, EnvError> {
+    let session_id = workspace_session_id(workspace_id);
+    let Some(description) = description_repository
+        .get_session_description_entries()
+        .into_iter()
+        .find(|x| match x {
+            Ok(description) => description.id == session_id,
+            Err(unresolved) => unresolved.id == session_id,
+        })
+    else {
+        return Ok(None);
+    };
+    let description = description.map_err(|x| x.error)?;
+
+    match description.session {
+        Some(session) => Ok(Some(session)),
+        None => Ok(Some(session_repository.new_session(&description))),
+    }
+}
+
+/// Split a repository url into host, owner and name.
+///
+/// Supports `https://`, `ssh://` and `file://` urls, scp-like `git@host:owner/repo` urls and
+/// local paths. Returns `None` when there is no repository name, or when the host, owner or name
+/// of a remote url is `.` or `..`, since they become directories of the clone destination.
+pub fn parse_repository_url(url: &str) -> Option<RepositoryUrl> {
+    let url = url.trim().trim_end_matches('/');
+
+    let (host, path) = if let Some((scheme, rest)) = url.split_once("://") {
+        if scheme == "file" {
+            ("local".to_string(), rest)
+        } else {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host = authority.rsplit('@').next().unwrap_or(authority);
+            let host = host.split(':').next().unwrap_or(host);
+            (host.to_string(), path)
+        }
+    } else if let Some((authority, path)) = url.split_once(':')
+        && !authority.contains('/')
+        && !url.starts_with('.')
+    {
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        (host.to_string(), path)
+    } else {
+        ("local".to_string(), url)
+    };
+
+    let is_relative = |x: &str| x == "." || x == "..";
+    let mut segments: Vec<&str> = path.split('/').filter(|x| !x.is_empty()).collect();
+    let repo = segments.pop()?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    if repo.is_empty() || host.is_empty() || is_relative(repo) || is_relative(&host) {
+        return None;
+    }
+
+    // Local repositories are usually deep in the file system, only their directory is relevant
+    let owner = if host == "local" {
+        segments
+            .iter()
+            .rfind(|x| !is_relative(x))
+            .map(|x| x.to_string())
+            .unwrap_or_default()
+    } else if segments.iter().any(|x| is_relative(x)) {
+        return None;
+    } else {
+        segments.join("/")
+    };
+
+    Some(RepositoryUrl {
+        host,
+        owner,
+        repo: repo.to_string(),
+    })
+}
+
+/// Fill in the `{host}`, `{owner}` and `{repo}` placeholders of a `clone.path` layout.
+///
+/// Variables in the layout are expanded first, empty placeholders do not leave empty directories.
+pub fn clone_destination(layout: &str, url: &RepositoryUrl) -> Result<String, InterpolationError> {
+    let layout = interpolate(layout, &InterpolationContext::default())?;
+    let path = layout
+        .replace("{host}", &url.host)
+        .replace("{owner}", &url.owner)
+        .replace("{repo}", &url.repo);
+
+    let absolute = path.starts_with('/');
+    let path = path
+        .split('/')
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<&str>>()
+        .join("/");
+    Ok(if absolute { format!("/{}", path) } else { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(host: &str, owner: &str, repo: &str) -> RepositoryUrl {
+        RepositoryUrl {
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+
+    #[test]
+    fn should_parse_remote_urls() {
+        assert_eq!(
+            parse_repository_url("https://github.com/rafaeltab/devenv.git"),
+            Some(url("github.com", "rafaeltab", "devenv"))
+        );
+        assert_eq!(
+            parse_repository_url("git@github.com:rafaeltab/devenv.git"),
+            Some(url("github.com", "rafaeltab", "devenv"))
+        );
+        assert_eq!(
+            parse_repository_url("ssh://git@gitlab.com:2222/group/sub/api/"),
+            Some(url("gitlab.com", "group/sub", "api"))
+        );
+    }
+
+    #[test]
+    fn should_parse_local_urls() {
+        assert_eq!(
+            parse_repository_url("file:///tmp/remotes/acme/api.git"),
+            Some(url("local", "acme", "api"))
+        );
+        assert_eq!(
+            parse_repository_url("/tmp/remotes/api.git"),
+            Some(url("local", "remotes", "api"))
+        );
+        assert_eq!(parse_repository_url("https://github.com/"), None);
+        assert_eq!(
+            parse_repository_url("../remotes/api.git"),
+            Some(url("local", "remotes", "api"))
+        );
+        assert_eq!(parse_repository_url("./api"), Some(url("local", "", "api")));
+    }
+
+    #[test]
+    fn should_reject_relative_segments_in_remote_urls() {
+        assert_eq!(parse_repository_url("https://github.com/../../etc"), None);
+        assert_eq!(parse_repository_url("git@github.com:owner/.."), None);
+        assert_eq!(parse_repository_url("git@..:owner/repo"), None);
+        assert_eq!(parse_repository_url("/tmp/remotes/.."), None);
+    }
+
+    #[test]
+    fn should_fill_in_the_layout() {
+        let result = clone_destination(
+            "/src/{host}/{owner}/{repo}",
+            &url("github.com", "rafaeltab", "devenv"),
+        );
+        assert_eq!(result.unwrap(), "/src/github.com/rafaeltab/devenv");
+
+        let result = clone_destination("/src/{owner}/{repo}", &url("local", "", "api"));
+        assert_eq!(result.unwrap(), "/src/api");
+    }
+}
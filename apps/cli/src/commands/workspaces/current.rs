@@ -14,7 +14,10 @@ pub fn get_current_workspace<TWorkspaceStorage: WorkspaceStorage>(
     workspace_storage: &TWorkspaceStorage,
     CurrentWorkspaceOptions { display }: CurrentWorkspaceOptions,
 ) {
-    let workspaces = get_workspace_paths(workspace_storage);
+    let workspaces: Vec<_> = get_workspace_paths(workspace_storage)
+        .into_iter()
+        .filter_map(|x| x.inspect_err(|err| eprintln!("Warning: {}", err)).ok())
+        .collect();
     let cwd = match env::current_dir() {
         Ok(cwd_path) => cwd_path.to_string_lossy().to_string(),
         Err(_) => panic!("Failed to read cwd"),
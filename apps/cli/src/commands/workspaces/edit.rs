@@ -0,0 +1,181 @@
+//! Command to change the name, root, tags or worktree configuration of a workspace
+
+use std::process::exit;
+
+use atty::Stream;
+use inquire::Text;
+
+use crate::{
+    commands::command::{OrExit, RafaeltabCommand},
+    domain::tmux_workspaces::repositories::workspace::workspace_repository::{
+        WorkspaceRepository, WorkspaceUpdate,
+    },
+    storage::worktree::WorkspaceWorktreeConfig,
+    utils::display::RafaeltabDisplay,
+};
+
+#[derive(Default)]
+pub struct WorkspaceEditCommand;
+
+pub struct WorkspaceEditOptions<'a> {
+    /// Id of the workspace to edit
+    pub id: String,
+    pub name: Option<String>,
+    pub root: Option<String>,
+    /// Replaces all tags
+    pub tags: Option<Vec<String>>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    /// Replaces the worktree configuration, as JSON
+    pub worktree: Option<String>,
+    /// Removes the worktree configuration
+    pub clear_worktree: bool,
+    /// Prompt for the name, root and tags when nothing else is changed
+    pub interactive: Option<bool>,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+impl RafaeltabCommand<WorkspaceEditOptions<'_>> for WorkspaceEditCommand {
+    fn execute(&self, options: WorkspaceEditOptions) {
+        let Some(workspace) = options
+            .workspace_repository
+            .get_workspaces()
+            .or_exit()
+            .into_iter()
+            .find(|x| x.id == options.id)
+        else {
+            eprintln!("Error: workspace '{}' does not exist", options.id);
+            exit(1);
+        };
+        let current_tags: Vec<String> = workspace.tags.iter().map(|x| x.name.clone()).collect();
+
+        let worktree = match (&options.worktree, options.clear_worktree) {
+            (Some(_), true) => {
+                eprintln!("Error: --worktree and --clear-worktree can not be used together");
+                exit(1);
+            }
+            (Some(json), false) => match serde_json::from_str::<WorkspaceWorktreeConfig>(json) {
+                Ok(config) => Some(Some(config)),
+                Err(err) => {
+                    eprintln!("Error: invalid worktree configuration: {}", err);
+                    exit(1);
+                }
+            },
+            (None, true) => Some(None),
+            (None, false) => None,
+        };
+
+        let mut update = WorkspaceUpdate {
+            name: options.name.clone(),
+            root: options.root.clone(),
+            tags: edit_tags(
+                &current_tags,
+                options.tags.clone(),
+                &options.add_tags,
+                &options.remove_tags,
+            ),
+            worktree,
+            ..Default::default()
+        };
+
+        if update.name.is_none()
+            && update.root.is_none()
+            && update.tags.is_none()
+            && update.worktree.is_none()
+        {
+            let interactive = options
+                .interactive
+                .unwrap_or_else(|| atty::is(Stream::Stdout));
+            if !interactive {
+                eprintln!("Error: Not interactive, and nothing to change was provided");
+                exit(1);
+            }
+            // Only values that were changed are written, so variables in the root are kept
+            let name = prompt("Name:", &workspace.name);
+            update.name = (name != workspace.name).then_some(name);
+            let root = prompt("Root:", &workspace.path);
+            update.root = (root != workspace.path).then_some(root);
+            let tags: Vec<String> = prompt("Tags (comma separated):", &current_tags.join(", "))
+                .split(',')
+                .map(|x| x.trim().to_string())
+                .filter(|x| !x.is_empty())
+                .collect();
+            update.tags = (tags != current_tags).then_some(tags);
+        }
+
+        match options
+            .workspace_repository
+            .update_workspace(&workspace.id, update)
+        {
+            Ok(Some(workspace)) => options.display.display(&workspace),
+            Ok(None) => {
+                eprintln!("Error: workspace '{}' does not exist", options.id);
+                exit(1);
+            }
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        }
+    }
+}
+
+/// The new tags of a workspace, `None` when they do not change
+pub fn edit_tags(
+    current: &[String],
+    replace: Option<Vec<String>>,
+    add: &[String],
+    remove: &[String],
+) -> Option<Vec<String>> {
+    if replace.is_none() && add.is_empty() && remove.is_empty() {
+        return None;
+    }
+
+    let mut tags = replace.unwrap_or_else(|| current.to_vec());
+    for tag in add {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags.retain(|x| !remove.contains(x));
+    Some(tags)
+}
+
+fn prompt(message: &str, initial: &str) -> String {
+    Text::new(message)
+        .with_initial_value(initial)
+        .prompt()
+        .unwrap_or_else(|_| {
+            println!("Operation cancelled.");
+            exit(0);
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn should_not_change_tags_without_options() {
+        assert_eq!(edit_tags(&tags(&["a"]), None, &[], &[]), None);
+    }
+
+    #[test]
+    fn should_add_and_remove_tags() {
+        let result = edit_tags(&tags(&["a", "b"]), None, &tags(&["b", "c"]), &tags(&["a"]));
+
+        assert_eq!(result, Some(tags(&["b", "c"])));
+    }
+
+    #[test]
+    fn should_replace_tags_before_adding() {
+        let result = edit_tags(&tags(&["a"]), Some(tags(&["x"])), &tags(&["y"]), &[]);
+
+        assert_eq!(result, Some(tags(&["x", "y"])));
+    }
+}
@@ -0,0 +1,345 @@
+//! Command to run a shell command in many workspaces at once
+
+use std::{
+    io::{self, Write},
+    process::{Command, exit},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    commands::command::OrExit,
+    domain::tmux_workspaces::aggregates::workspaces::tag_query::TagQuery,
+    storage::workspace::WorkspaceStorage,
+    utils::{
+        display::{RafaeltabDisplay, RafaeltabDisplayItem},
+        parallel::map_bounded,
+        table::format_table,
+        workspace::get_workspace_paths,
+    },
+};
+
+pub struct WorkspaceExecOptions<'a> {
+    /// The program and its arguments, a single argument is run by `sh -c`
+    pub command: Vec<String>,
+    /// Only run in the workspaces whose tags match
+    pub filter: Option<&'a TagQuery>,
+    /// How many workspaces run the command at the same time
+    pub parallel: usize,
+    /// Do not start the command in more workspaces once it failed in one
+    pub fail_fast: bool,
+    /// Print the output of every workspace as it finishes, the output is part of the summary
+    /// otherwise
+    pub print_output: bool,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecStatus {
+    /// The command exited, with its exit code when it was not killed by a signal
+    Exited(Option<i32>),
+    /// The command could not be started
+    Failed(String),
+    /// The command was not started because of `--fail-fast`
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecResult {
+    pub id: String,
+    pub root: String,
+    pub status: ExecStatus,
+    pub duration: Duration,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ExecResult {
+    pub fn success(&self) -> bool {
+        self.status == ExecStatus::Exited(Some(0))
+    }
+
+    fn status_text(&self) -> String {
+        match &self.status {
+            ExecStatus::Exited(Some(code)) => format!("exit {}", code),
+            ExecStatus::Exited(None) => "killed".to_string(),
+            ExecStatus::Failed(_) => "failed to start".to_string(),
+            ExecStatus::Skipped => "skipped".to_string(),
+        }
+    }
+}
+
+struct ExecTarget {
+    id: String,
+    root: String,
+}
+
+pub fn workspace_exec<TWorkspaceStorage: WorkspaceStorage>(
+    workspace_storage: &TWorkspaceStorage,
+    options: WorkspaceExecOptions,
+) {
+    if options.command.is_empty() {
+        eprintln!("Error: no command given, pass it after `--`");
+        exit(1);
+    }
+
+    let workspaces: Vec<_> = get_workspace_paths(workspace_storage)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .or_exit();
+    let targets: Vec<ExecTarget> = workspaces
+        .into_iter()
+        .filter(|x| {
+            options
+                .filter
+                .is_none_or(|filter| filter.matches(x.data.tags.as_deref().unwrap_or_default()))
+        })
+        .map(|x| ExecTarget {
+            id: x.data.id,
+            root: x.path,
+        })
+        .collect();
+
+    let print_output = options.print_output;
+    let results = run_all(
+        targets,
+        &options.command,
+        options.parallel,
+        options.fail_fast,
+        |result| {
+            if print_output {
+                print_grouped(result);
+            }
+        },
+    );
+
+    let success = results.iter().all(|x| x.success());
+    options.display.display(&ExecSummary { results });
+    if !success {
+        exit(1);
+    }
+}
+
+/// Run the command in every target with at most `parallel` running at once.
+///
+/// `on_finish` is called for every target as soon as it finishes, the results are returned in
+/// the order of the targets.
+fn run_all(
+    targets: Vec<ExecTarget>,
+    command: &[String],
+    parallel: usize,
+    fail_fast: bool,
+    on_finish: impl Fn(&ExecResult) + Sync,
+) -> Vec<ExecResult> {
+    let failed = AtomicBool::new(false);
+
+    map_bounded(targets, parallel, |target| {
+        if fail_fast && failed.load(Ordering::SeqCst) {
+            return skipped(target);
+        }
+        let result = run_one(target, command);
+        if !result.success() {
+            failed.store(true, Ordering::SeqCst);
+        }
+        on_finish(&result);
+        result
+    })
+}
+
+fn run_one(target: ExecTarget, command: &[String]) -> ExecResult {
+    let mut process = if command.len() == 1 {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(&command[0]);
+        process
+    } else {
+        let mut process = Command::new(&command[0]);
+        process.args(&command[1..]);
+        process
+    };
+
+    let start = Instant::now();
+    let output = process.current_dir(&target.root).output();
+    let duration = start.elapsed();
+
+    match output {
+        Ok(output) => ExecResult {
+            id: target.id,
+            root: target.root,
+            status: ExecStatus::Exited(output.status.code()),
+            duration,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        },
+        Err(err) => ExecResult {
+            id: target.id,
+            root: target.root,
+            status: ExecStatus::Failed(err.to_string()),
+            duration,
+            stdout: String::new(),
+            stderr: String::new(),
+        },
+    }
+}
+
+fn skipped(target: ExecTarget) -> ExecResult {
+    ExecResult {
+        id: target.id,
+        root: target.root,
+        status: ExecStatus::Skipped,
+        duration: Duration::ZERO,
+        stdout: String::new(),
+        stderr: String::new(),
+    }
+}
+
+/// Print the output of a workspace at once, every line prefixed with the workspace id
+fn print_grouped(result: &ExecResult) {
+    let mut stdout = io::stdout().lock();
+    for line in result.stdout.lines() {
+        let _ = writeln!(stdout, "[{}] {}", result.id, line);
+    }
+    drop(stdout);
+
+    let mut stderr = io::stderr().lock();
+    for line in result.stderr.lines() {
+        let _ = writeln!(stderr, "[{}] {}", result.id, line);
+    }
+    if let ExecStatus::Failed(message) = &result.status {
+        let _ = writeln!(stderr, "[{}] {}", result.id, message);
+    }
+}
+
+/// The results of all workspaces, shown as a table
+struct ExecSummary {
+    results: Vec<ExecResult>,
+}
+
+impl RafaeltabDisplayItem for ExecResult {
+    fn to_json(&self) -> Value {
+        let (exit_code, error) = match &self.status {
+            ExecStatus::Exited(code) => (*code, None),
+            ExecStatus::Failed(message) => (None, Some(message.clone())),
+            ExecStatus::Skipped => (None, None),
+        };
+        json!({
+            "id": self.id,
+            "root": self.root,
+            "status": match self.status {
+                ExecStatus::Skipped => "skipped",
+                _ if self.success() => "success",
+                _ => "failed",
+            },
+            "exitCode": exit_code,
+            "error": error,
+            "durationMs": self.duration.as_millis() as u64,
+            "stdout": self.stdout,
+            "stderr": self.stderr,
+        })
+    }
+
+    fn to_pretty_string(&self) -> String {
+        format!(
+            "{} {} {:.1}s",
+            self.id,
+            self.status_text(),
+            self.duration.as_secs_f64()
+        )
+    }
+}
+
+impl RafaeltabDisplayItem for ExecSummary {
+    fn to_json(&self) -> Value {
+        Value::Array(self.results.iter().map(|x| x.to_json()).collect())
+    }
+
+    fn to_pretty_string(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .results
+            .iter()
+            .map(|x| {
+                vec![
+                    x.id.clone(),
+                    x.status_text(),
+                    format!("{:.1}s", x.duration.as_secs_f64()),
+                ]
+            })
+            .collect();
+        format_table(&["WORKSPACE", "STATUS", "DURATION"], &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(ids: &[&str]) -> Vec<ExecTarget> {
+        ids.iter()
+            .map(|id| ExecTarget {
+                id: id.to_string(),
+                root: std::env::temp_dir().to_string_lossy().to_string(),
+            })
+            .collect()
+    }
+
+    fn command(command: &str) -> Vec<String> {
+        vec![command.to_string()]
+    }
+
+    #[test]
+    fn should_run_in_every_workspace_and_keep_order() {
+        let results = run_all(
+            targets(&["a", "b", "c"]),
+            &command("echo hi"),
+            2,
+            false,
+            |_| {},
+        );
+
+        let ids: Vec<&str> = results.iter().map(|x| x.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert!(results.iter().all(|x| x.success() && x.stdout == "hi\n"));
+    }
+
+    #[test]
+    fn should_report_exit_codes() {
+        let results = run_all(targets(&["a"]), &command("exit 3"), 1, false, |_| {});
+
+        assert_eq!(results[0].status, ExecStatus::Exited(Some(3)));
+    }
+
+    #[test]
+    fn should_skip_remaining_workspaces_when_failing_fast() {
+        let results = run_all(
+            targets(&["a", "b", "c"]),
+            &command("false"),
+            1,
+            true,
+            |_| {},
+        );
+
+        let statuses: Vec<ExecStatus> = results.into_iter().map(|x| x.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ExecStatus::Exited(Some(1)),
+                ExecStatus::Skipped,
+                ExecStatus::Skipped
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_commands_that_can_not_start() {
+        let results = run_all(
+            targets(&["a"]),
+            &["rafaeltab-missing-program".to_string(), "arg".to_string()],
+            1,
+            false,
+            |_| {},
+        );
+
+        assert!(matches!(results[0].status, ExecStatus::Failed(_)));
+    }
+}
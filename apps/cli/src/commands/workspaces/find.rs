@@ -1,22 +1,97 @@
+use std::process::exit;
+
+use atty::Stream;
+use inquire::Select;
+use serde_json::{Value, json};
+
 use crate::{
-    storage::workspace::WorkspaceStorage,
+    commands::command::OrExit,
+    storage::workspace::{Workspace, WorkspaceStorage},
     utils::{
-        data_with_path::DataWithPath, display::RafaeltabDisplay, path::expand_path,
-        workspace::find_workspace,
+        data_with_path::DataWithPath,
+        display::{RafaeltabDisplay, RafaeltabDisplayItem},
+        env::{Environment, display_value},
+        workspace::{WorkspaceLookup, lookup_workspace},
     },
 };
 
 pub struct FindWorkspaceOptions<'a> {
     pub display: &'a dyn RafaeltabDisplay,
+    /// Only accept the exact id or an alias
+    pub exact: bool,
 }
 
 pub fn find_workspace_cmd<TWorkspaceStorage: WorkspaceStorage>(
     workspace_storage: &TWorkspaceStorage,
-    id: &str,
-    FindWorkspaceOptions { display }: FindWorkspaceOptions,
+    query: &str,
+    FindWorkspaceOptions { display, exact }: FindWorkspaceOptions,
 ) {
-    let workspace = find_workspace(workspace_storage, id);
-    if let Some(space) = workspace {
-        display.display(&DataWithPath::new(space.clone(), expand_path(&space.root)))
+    let workspace = match lookup_workspace(&workspace_storage.read(), query, exact) {
+        WorkspaceLookup::Found(workspace) => *workspace,
+        // The picker needs a terminal to read from as well as to draw on
+        WorkspaceLookup::Ambiguous(candidates)
+            if atty::is(Stream::Stdout) && atty::is(Stream::Stdin) =>
+        {
+            match pick_candidate(query, candidates) {
+                Some(workspace) => workspace,
+                None => return,
+            }
+        }
+        WorkspaceLookup::Ambiguous(candidates) => {
+            eprintln!("Error: '{}' matches more than one workspace:", query);
+            for candidate in candidates {
+                eprintln!("  {} ({})", candidate.name, candidate.id);
+            }
+            exit(1);
+        }
+        // Scripts that only pass ids get nothing back, like before lookups by name existed
+        WorkspaceLookup::NotFound if exact => return,
+        WorkspaceLookup::NotFound => {
+            eprintln!("Error: no workspace matches '{}'", query);
+            exit(1);
+        }
+    };
+
+    let env = workspace.resolved_env().or_exit();
+    display.display(&FoundWorkspace {
+        workspace: workspace.load_path().or_exit(),
+        env,
+    })
+}
+
+/// A workspace together with its environment variables
+struct FoundWorkspace {
+    workspace: DataWithPath<Workspace>,
+    env: Environment,
+}
+
+impl RafaeltabDisplayItem for FoundWorkspace {
+    fn to_json(&self) -> Value {
+        let mut json = self.workspace.to_json();
+        json["env"] = json!(self.env);
+        json
+    }
+
+    fn to_pretty_string(&self) -> String {
+        let mut result = self.workspace.to_pretty_string();
+        for (key, value) in &self.env {
+            result.push_str(&format!("\n  {}={}", key, display_value(key, value)));
+        }
+        result
     }
 }
+
+fn pick_candidate(query: &str, candidates: Vec<Workspace>) -> Option<Workspace> {
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|x| format!("{} ({})", x.name, x.id))
+        .collect();
+    let selected = Select::new(
+        &format!("'{}' matches more than one workspace:", query),
+        labels,
+    )
+    .raw_prompt()
+    .ok()?;
+
+    candidates.into_iter().nth(selected.index)
+}
@@ -1,4 +1,6 @@
 use crate::{
+    commands::command::OrExit,
+    domain::tmux_workspaces::aggregates::workspaces::tag_query::TagQuery,
     storage::workspace::{Workspace, WorkspaceStorage},
     utils::{
         data_with_path::DataWithPath,
@@ -12,18 +14,16 @@ pub struct FindTagWorkspaceOptions<'a> {
 
 pub fn find_tag_workspace<TWorkspaceStorage: WorkspaceStorage>(
     workspace_storage: &TWorkspaceStorage,
-    tag: &str,
+    query: &TagQuery,
     FindTagWorkspaceOptions { display }: FindTagWorkspaceOptions,
 ) {
     let workspaces: Vec<DataWithPath<Workspace>> = workspace_storage
         .read()
         .iter()
-        .filter(|x| match &x.tags {
-            Some(tags) => tags.contains(&tag.to_string()),
-            None => false,
-        })
+        .filter(|x| query.matches(x.tags.as_deref().unwrap_or_default()))
         .map(|x| x.load_path())
-        .collect();
+        .collect::<Result<_, _>>()
+        .or_exit();
 
     display.display_list(workspaces.to_dyn_vec());
 }
@@ -1,8 +1,16 @@
+use serde_json::{Value, json};
+
 use crate::{
     commands::command::RafaeltabCommand,
-    storage::workspace::WorkspaceStorage,
+    domain::tmux_workspaces::aggregates::workspaces::tag_query::TagQuery,
+    storage::{
+        frecency::{Frecency, FrecencyStorage},
+        workspace::{Workspace, WorkspaceStorage},
+    },
     utils::{
-        display::{RafaeltabDisplay, ToDynVec},
+        data_with_path::DataWithPath,
+        display::{RafaeltabDisplay, RafaeltabDisplayItem, ToDynVec},
+        ranking::sort_by_rank,
         workspace::get_workspace_paths,
     },
 };
@@ -11,6 +19,9 @@ use crate::{
 pub struct ListWorkspacesCommand;
 pub struct ListWorkspacesCommandArgs<'a, TWorkspaceStorage: WorkspaceStorage> {
     pub workspace_storage: &'a TWorkspaceStorage,
+    pub frecency_storage: &'a dyn FrecencyStorage,
+    /// Only list the workspaces whose tags match
+    pub filter: Option<&'a TagQuery>,
     pub display: &'a dyn RafaeltabDisplay,
 }
 
@@ -22,8 +33,56 @@ impl<'a, TWorkspaceStorage: WorkspaceStorage>
         ListWorkspacesCommandArgs {
             display,
             workspace_storage,
+            frecency_storage,
+            filter,
         }: ListWorkspacesCommandArgs<'a, TWorkspaceStorage>,
     ) {
-        display.display_list(get_workspace_paths(workspace_storage).to_dyn_vec())
+        let workspaces = get_workspace_paths(workspace_storage)
+            .into_iter()
+            .filter_map(|x| x.inspect_err(|err| eprintln!("Warning: {}", err)).ok())
+            .filter(|workspace| {
+                filter.is_none_or(|filter| {
+                    filter.matches(workspace.data.tags.as_deref().unwrap_or_default())
+                })
+            })
+            .collect();
+        let workspaces = sort_by_rank(
+            workspaces,
+            &frecency_storage.read(),
+            chrono::Utc::now().timestamp(),
+            |workspace| {
+                (
+                    workspace.data.importance.unwrap_or(0),
+                    Frecency::workspace_key(&workspace.data.id),
+                )
+            },
+        );
+        let workspaces: Vec<ListedWorkspace> = workspaces
+            .into_iter()
+            .map(|workspace| ListedWorkspace {
+                source: workspace_storage.workspace_source(&workspace.data.id),
+                workspace,
+            })
+            .collect();
+        display.display_list(workspaces.to_dyn_vec())
+    }
+}
+
+/// A workspace together with the configuration file it is defined in
+struct ListedWorkspace {
+    workspace: DataWithPath<Workspace>,
+    source: Option<String>,
+}
+
+impl RafaeltabDisplayItem for ListedWorkspace {
+    fn to_json(&self) -> Value {
+        let mut json = self.workspace.to_json();
+        json["source"] = json!(self.source);
+        json["importance"] = json!(self.workspace.data.importance.unwrap_or(0));
+        json
+    }
+
+    fn to_pretty_string(&self) -> String {
+        self.workspace.to_pretty_string()
     }
 }
@@ -1,6 +1,15 @@
 pub mod add;
+pub mod clone;
 pub mod current;
+pub mod edit;
+pub mod exec;
 pub mod find;
 pub mod find_tag;
 pub mod list;
+pub mod new;
+pub mod references;
+pub mod remove;
+pub mod rename;
+pub mod scan;
+pub mod status;
 pub mod tmux;
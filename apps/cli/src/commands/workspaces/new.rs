@@ -0,0 +1,644 @@
+//! Command to create a new project from a template and add it as a workspace
+
+use std::{
+    collections::HashSet,
+    env::current_dir,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    process::{self, exit},
+};
+
+use crate::{
+    commands::{
+        builtin::add_workspace::{slugify, unique_id},
+        command::RafaeltabCommand,
+        workspaces::clone::start_session,
+    },
+    domain::tmux_workspaces::{
+        aggregates::workspaces::workspace::Workspace,
+        repositories::{
+            tmux::{
+                description_repository::SessionDescriptionRepository,
+                session_repository::TmuxSessionRepository,
+            },
+            workspace::workspace_repository::{WorkspaceRepository, WorkspaceUpdate},
+        },
+    },
+    infrastructure::git::{GitError, clone_repository, init_repository},
+    storage::{
+        error::StorageError,
+        template::{TEMPLATE_MANIFEST, Template, TemplateManifest, TemplateStorage},
+        tmux::{Session, TmuxStorage, WorkspaceSession},
+    },
+    utils::{
+        display::RafaeltabDisplay,
+        interpolation::{InterpolationContext, InterpolationError, interpolate},
+        path::expand_path,
+    },
+};
+
+#[derive(Default)]
+pub struct WorkspaceNewCommand;
+
+pub struct WorkspaceNewOptions<'a> {
+    /// Name of the workspace
+    pub name: String,
+    /// Name of the template to create the workspace from
+    pub template: String,
+    /// Tags added to the tags of the template
+    pub tags: Vec<String>,
+    /// Directory to create the workspace in, a directory named after the id in the current
+    /// directory when omitted
+    pub path: Option<String>,
+    /// Start the tmux session of the new workspace
+    pub start: bool,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub template_storage: &'a dyn TemplateStorage,
+    pub tmux_storage: &'a dyn TmuxStorage,
+    pub description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+/// What to create and from which template
+pub struct NewWorkspaceRequest {
+    pub name: String,
+    pub template: String,
+    pub tags: Vec<String>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    /// No template has the requested name
+    UnknownTemplate {
+        name: String,
+        available: Vec<String>,
+    },
+    /// The directory to create the workspace in already has content
+    DestinationExists(String),
+    Interpolation(InterpolationError),
+    /// The template manifest is not valid
+    Manifest {
+        path: String,
+        error: String,
+    },
+    /// The template files could not be copied
+    Io(String),
+    Git(GitError),
+    Storage(StorageError),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownTemplate { name, available } if available.is_empty() => write!(
+                f,
+                "no template named '{}', there are no templates configured",
+                name
+            ),
+            TemplateError::UnknownTemplate { name, available } => write!(
+                f,
+                "no template named '{}', available templates: {}",
+                name,
+                available.join(", ")
+            ),
+            TemplateError::DestinationExists(path) => write!(f, "'{}' already exists", path),
+            TemplateError::Interpolation(err) => write!(f, "{}", err),
+            TemplateError::Manifest { path, error } => write!(f, "{}: {}", path, error),
+            TemplateError::Io(err) => write!(f, "can not copy the template: {}", err),
+            TemplateError::Git(err) => write!(f, "{}", err),
+            TemplateError::Storage(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl RafaeltabCommand<WorkspaceNewOptions<'_>> for WorkspaceNewCommand {
+    fn execute(&self, options: WorkspaceNewOptions) {
+        let request = NewWorkspaceRequest {
+            name: options.name,
+            template: options.template,
+            tags: options.tags,
+            path: options.path,
+        };
+        let workspace = match new_workspace(
+            options.workspace_repository,
+            options.tmux_storage,
+            &options.template_storage.read(),
+            request,
+        ) {
+            Ok(workspace) => workspace,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        };
+
+        if options.start
+            && let Err(err) = start_session(
+                options.description_repository,
+                options.session_repository,
+                &workspace.id,
+            )
+        {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+
+        options.display.display(&workspace);
+    }
+}
+
+/// Create a new project from a template and register it as a workspace.
+///
+/// The template files are rendered into `request.path` or a directory named after the id in the
+/// current directory, a git repository is created there and the workspace is added with the
+/// worktree configuration and tmux windows of the template. When anything fails the destination
+/// is removed again, or emptied again when it was an empty directory.
+pub fn new_workspace(
+    workspace_repository: &dyn WorkspaceRepository,
+    tmux_storage: &dyn TmuxStorage,
+    templates: &[Template],
+    request: NewWorkspaceRequest,
+) -> Result<Workspace, TemplateError> {
+    let template = templates
+        .iter()
+        .find(|x| x.name == request.template)
+        .ok_or_else(|| TemplateError::UnknownTemplate {
+            name: request.template.clone(),
+            available: templates.iter().map(|x| x.name.clone()).collect(),
+        })?;
+
+    let ids: HashSet<String> = workspace_repository
+        .get_workspace_ids()
+        .into_iter()
+        .collect();
+    let id = unique_id(&slugify(&request.name), &ids);
+    let destination = match &request.path {
+        Some(path) => PathBuf::from(expand_path(path)),
+        None => current_dir()
+            .map_err(|err| TemplateError::Io(err.to_string()))?
+            .join(&id),
+    };
+
+    let is_empty_dir = destination.read_dir().is_ok_and(|mut x| x.next().is_none());
+    if destination.exists() && !is_empty_dir {
+        return Err(TemplateError::DestinationExists(
+            destination.to_string_lossy().to_string(),
+        ));
+    }
+
+    let result = create_workspace(
+        workspace_repository,
+        tmux_storage,
+        template,
+        request,
+        id,
+        &destination,
+    );
+    if result.is_err() {
+        remove_created(&destination, is_empty_dir);
+    }
+    result
+}
+
+fn create_workspace(
+    workspace_repository: &dyn WorkspaceRepository,
+    tmux_storage: &dyn TmuxStorage,
+    template: &Template,
+    request: NewWorkspaceRequest,
+    id: String,
+    destination: &Path,
+) -> Result<Workspace, TemplateError> {
+    let variables = [("name", request.name.as_str()), ("id", id.as_str())];
+    let manifest = copy_template(template, destination, &variables)?;
+    init_repository(destination).map_err(TemplateError::Git)?;
+
+    let mut tags: Vec<String> = template
+        .tags
+        .clone()
+        .or_else(|| manifest.tags.clone())
+        .unwrap_or_default();
+    for tag in request.tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    let workspace = workspace_repository
+        .create_workspace(
+            request.name,
+            tags,
+            destination.to_string_lossy().to_string(),
+            id.clone(),
+        )
+        .map_err(TemplateError::Storage)?;
+
+    let result = apply_template_settings(
+        workspace_repository,
+        tmux_storage,
+        template,
+        manifest,
+        workspace,
+    );
+    if result.is_err() {
+        let _ = workspace_repository.remove_workspace(&id);
+    }
+    result
+}
+
+/// Add the worktree configuration and tmux windows of a template to a new workspace
+fn apply_template_settings(
+    workspace_repository: &dyn WorkspaceRepository,
+    tmux_storage: &dyn TmuxStorage,
+    template: &Template,
+    manifest: TemplateManifest,
+    mut workspace: Workspace,
+) -> Result<Workspace, TemplateError> {
+    if let Some(worktree) = template.worktree.clone().or(manifest.worktree) {
+        let update = WorkspaceUpdate {
+            worktree: Some(Some(worktree)),
+            ..Default::default()
+        };
+        if let Some(updated) = workspace_repository
+            .update_workspace(&workspace.id, update)
+            .map_err(TemplateError::Storage)?
+        {
+            workspace = updated;
+        }
+    }
+
+    if let Some(windows) = template.windows.clone().or(manifest.windows) {
+        let mut tmux = tmux_storage.read();
+        tmux.sessions
+            .get_or_insert_with(Vec::new)
+            .push(Session::Workspace(WorkspaceSession {
+                windows,
+                workspace: workspace.id.clone(),
+                name: None,
+            }));
+        tmux_storage.write(&tmux).map_err(TemplateError::Storage)?;
+    }
+
+    Ok(workspace)
+}
+
+/// Remove what a failed `new_workspace` created, a directory that was empty before is kept empty
+fn remove_created(destination: &Path, was_empty_dir: bool) {
+    if !was_empty_dir {
+        let _ = fs::remove_dir_all(destination);
+        return;
+    }
+    let Ok(entries) = fs::read_dir(destination) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let _ = match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => fs::remove_dir_all(&path),
+            _ => fs::remove_file(&path),
+        };
+    }
+}
+
+/// Render the files of a template into `destination`, returning the manifest of the template.
+///
+/// Git sources are cloned into a temporary directory first.
+fn copy_template(
+    template: &Template,
+    destination: &Path,
+    variables: &[(&str, &str)],
+) -> Result<TemplateManifest, TemplateError> {
+    let source = interpolate(&template.source, &InterpolationContext::default())
+        .map_err(TemplateError::Interpolation)?;
+    let source = expand_path(&source);
+
+    if Path::new(&source).is_dir() {
+        return render_template(Path::new(&source), destination, variables);
+    }
+
+    let checkout = std::env::temp_dir().join(format!(
+        "rafaeltab-template-{}-{}",
+        process::id(),
+        slugify(&template.name)
+    ));
+    let _ = fs::remove_dir_all(&checkout);
+    clone_repository(&source, &checkout).map_err(TemplateError::Git)?;
+    let result = render_template(&checkout, destination, variables);
+    let _ = fs::remove_dir_all(&checkout);
+    result
+}
+
+/// Render a template directory into `destination`.
+///
+/// The manifest and `.git` directories are not copied.
+pub fn render_template(
+    source: &Path,
+    destination: &Path,
+    variables: &[(&str, &str)],
+) -> Result<TemplateManifest, TemplateError> {
+    let manifest_path = source.join(TEMPLATE_MANIFEST);
+    let manifest = match fs::read_to_string(&manifest_path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|err| TemplateError::Manifest {
+            path: manifest_path.to_string_lossy().to_string(),
+            error: err.to_string(),
+        })?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => TemplateManifest::default(),
+        Err(err) => return Err(TemplateError::Io(err.to_string())),
+    };
+
+    render_dir(source, destination, variables, true)
+        .map_err(|err| TemplateError::Io(err.to_string()))?;
+    Ok(manifest)
+}
+
+fn render_dir(
+    source: &Path,
+    destination: &Path,
+    variables: &[(&str, &str)],
+    is_root: bool,
+) -> io::Result<()> {
+    fs::create_dir_all(destination)?;
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name == ".git" || (is_root && file_name == TEMPLATE_MANIFEST) {
+            continue;
+        }
+        let target = destination.join(render_file_name(&file_name, variables)?);
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            render_dir(&entry.path(), &target, variables, false)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+        } else {
+            let content = fs::read(entry.path())?;
+            match String::from_utf8(content) {
+                Ok(text) => fs::write(&target, render(&text, variables))?,
+                // Binary files are copied as they are
+                Err(err) => fs::write(&target, err.into_bytes())?,
+            }
+            fs::set_permissions(&target, entry.metadata()?.permissions())?;
+        }
+    }
+    Ok(())
+}
+
+/// Render the name of a file or directory, names that would leave their directory are refused
+fn render_file_name(file_name: &str, variables: &[(&str, &str)]) -> io::Result<String> {
+    let rendered = render(file_name, variables);
+    if rendered.is_empty() || rendered == "." || rendered == ".." || rendered.contains('/') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "'{}' renders to the invalid file name '{}'",
+                file_name, rendered
+            ),
+        ));
+    }
+    Ok(rendered)
+}
+
+/// Replace the `{{key}}` placeholders in a text, unknown placeholders are left as they are
+pub fn render(text: &str, variables: &[(&str, &str)]) -> String {
+    variables
+        .iter()
+        .fold(text.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{{{}}}}}", key), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        infrastructure::tmux_workspaces::repositories::workspace::workspace_repository::ImplWorkspaceRepository,
+        storage::{
+            storage_interface::Storage, test::mocks::InMemoryStorage, tmux::Tmux, tmux::Window,
+            worktree::WorkspaceWorktreeConfig,
+        },
+    };
+
+    use super::*;
+
+    fn template(name: &str, source: &Path) -> Template {
+        Template {
+            name: name.to_string(),
+            source: source.to_string_lossy().to_string(),
+            description: None,
+            tags: Some(vec!["rust".to_string()]),
+            worktree: None,
+            windows: None,
+        }
+    }
+
+    #[test]
+    fn should_replace_placeholders() {
+        let variables = [("name", "My App"), ("id", "my-app")];
+
+        assert_eq!(
+            render("# {{name}} ({{id}}) {{unknown}}", &variables),
+            "# My App (my-app) {{unknown}}"
+        );
+    }
+
+    #[test]
+    fn should_render_files_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("template");
+        fs::create_dir_all(source.join("src/{{id}}")).unwrap();
+        fs::create_dir_all(source.join(".git")).unwrap();
+        fs::write(source.join("src/{{id}}/README.md"), "# {{name}}\n").unwrap();
+        fs::write(source.join("logo.bin"), [0xff, 0xfe, b'{']).unwrap();
+        fs::write(
+            source.join(TEMPLATE_MANIFEST),
+            r#"{ "windows": [{ "name": "editor" }] }"#,
+        )
+        .unwrap();
+        let destination = dir.path().join("app");
+
+        let manifest =
+            render_template(&source, &destination, &[("name", "App"), ("id", "app")]).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.join("src/app/README.md")).unwrap(),
+            "# App\n"
+        );
+        assert_eq!(
+            fs::read(destination.join("logo.bin")).unwrap(),
+            vec![0xff, 0xfe, b'{']
+        );
+        assert!(!destination.join(".git").exists());
+        assert!(!destination.join(TEMPLATE_MANIFEST).exists());
+        assert_eq!(manifest.windows.unwrap()[0].name, "editor");
+    }
+
+    #[test]
+    fn should_register_the_workspace_with_the_template_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("template");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("Cargo.toml"), "name = \"{{id}}\"\n").unwrap();
+        fs::write(
+            source.join(TEMPLATE_MANIFEST),
+            r#"{ "worktree": { "onCreate": ["cargo build"] }, "windows": [{ "name": "editor" }] }"#,
+        )
+        .unwrap();
+        let mut rust = template("rust", &source);
+        rust.windows = Some(vec![Window {
+            name: "shell".to_string(),
+            command: None,
+            ..Default::default()
+        }]);
+        let workspace_storage = InMemoryStorage::new(vec![]);
+        let tmux_storage = InMemoryStorage::new(Tmux {
+            sessions: None,
+            default_windows: vec![],
+        });
+        let destination = dir.path().join("my-app");
+
+        let workspace = new_workspace(
+            &ImplWorkspaceRepository {
+                workspace_storage: &workspace_storage,
+            },
+            &tmux_storage,
+            &[rust],
+            NewWorkspaceRequest {
+                name: "My App".to_string(),
+                template: "rust".to_string(),
+                tags: vec!["work".to_string()],
+                path: Some(destination.to_string_lossy().to_string()),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(workspace.id, "my-app");
+        assert_eq!(
+            fs::read_to_string(destination.join("Cargo.toml")).unwrap(),
+            "name = \"my-app\"\n"
+        );
+        assert!(destination.join(".git").is_dir());
+
+        let stored = &workspace_storage.read()[0];
+        assert_eq!(
+            stored.tags,
+            Some(vec!["rust".to_string(), "work".to_string()])
+        );
+        assert_eq!(
+            stored.worktree,
+            Some(WorkspaceWorktreeConfig {
+                on_create: vec!["cargo build".to_string()],
+                ..Default::default()
+            })
+        );
+        let Some(sessions) = tmux_storage.read().sessions else {
+            panic!("A session should be added");
+        };
+        let Session::Workspace(session) = &sessions[0] else {
+            panic!("The session should belong to the workspace");
+        };
+        assert_eq!(session.workspace, "my-app");
+        assert_eq!(session.windows[0].name, "shell", "The config wins");
+    }
+
+    #[test]
+    fn should_refuse_names_that_leave_the_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("template");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("{{name}}.md"), "# {{name}}\n").unwrap();
+        let workspace_storage = InMemoryStorage::new(vec![]);
+        let tmux_storage = InMemoryStorage::new(Tmux {
+            sessions: None,
+            default_windows: vec![],
+        });
+        let destination = dir.path().join("app");
+
+        let result = new_workspace(
+            &ImplWorkspaceRepository {
+                workspace_storage: &workspace_storage,
+            },
+            &tmux_storage,
+            &[template("rust", &source)],
+            NewWorkspaceRequest {
+                name: "../escape".to_string(),
+                template: "rust".to_string(),
+                tags: vec![],
+                path: Some(destination.to_string_lossy().to_string()),
+            },
+        );
+
+        assert!(result.is_err(), "A name with a '/' should be refused");
+        assert!(!dir.path().join("escape.md").exists());
+        assert!(!destination.exists(), "The destination should be removed");
+        assert!(workspace_storage.read().is_empty());
+    }
+
+    #[test]
+    fn should_keep_an_empty_destination_empty_when_failing() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("template");
+        fs::create_dir_all(source.join("docs")).unwrap();
+        fs::write(source.join("docs/README.md"), "# {{name}}\n").unwrap();
+        fs::write(source.join("{{name}}"), "").unwrap();
+        let workspace_storage = InMemoryStorage::new(vec![]);
+        let tmux_storage = InMemoryStorage::new(Tmux {
+            sessions: None,
+            default_windows: vec![],
+        });
+        let destination = dir.path().join("app");
+        fs::create_dir_all(&destination).unwrap();
+
+        let result = new_workspace(
+            &ImplWorkspaceRepository {
+                workspace_storage: &workspace_storage,
+            },
+            &tmux_storage,
+            &[template("rust", &source)],
+            NewWorkspaceRequest {
+                name: "..".to_string(),
+                template: "rust".to_string(),
+                tags: vec![],
+                path: Some(destination.to_string_lossy().to_string()),
+            },
+        );
+
+        assert!(result.is_err(), "A name of '..' should be refused");
+        assert!(destination.is_dir(), "The destination should be kept");
+        assert_eq!(fs::read_dir(&destination).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn should_list_available_templates_for_unknown_ones() {
+        let workspace_storage = InMemoryStorage::new(vec![]);
+        let tmux_storage = InMemoryStorage::new(Tmux {
+            sessions: None,
+            default_windows: vec![],
+        });
+
+        let result = new_workspace(
+            &ImplWorkspaceRepository {
+                workspace_storage: &workspace_storage,
+            },
+            &tmux_storage,
+            &[template("rust", Path::new("/templates/rust"))],
+            NewWorkspaceRequest {
+                name: "App".to_string(),
+                template: "go".to_string(),
+                tags: vec![],
+                path: None,
+            },
+        );
+
+        let Err(err) = result else {
+            panic!("An unknown template should fail");
+        };
+        assert_eq!(
+            err.to_string(),
+            "no template named 'go', available templates: rust"
+        );
+    }
+}
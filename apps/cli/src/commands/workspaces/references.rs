@@ -0,0 +1,108 @@
+//! Tmux sessions and session configuration that refer to a workspace
+
+use crate::{
+    domain::tmux_workspaces::{
+        aggregates::tmux::{description::session::SessionKind, session::TmuxSession},
+        repositories::tmux::description_repository::SessionDescriptionRepository,
+    },
+    storage::tmux::{Session, Tmux, TmuxStorage},
+};
+
+/// The running tmux sessions of a workspace, sessions that can not be resolved are left out
+pub fn running_sessions(
+    description_repository: &dyn SessionDescriptionRepository,
+    workspace_id: &str,
+) -> Vec<TmuxSession> {
+    description_repository
+        .get_session_description_entries()
+        .into_iter()
+        .flatten()
+        .filter(|x| matches!(&x.kind, SessionKind::Workspace(workspace) if workspace.id == workspace_id))
+        .filter_map(|x| x.session)
+        .collect()
+}
+
+/// The number of `tmux.sessions` entries that refer to a workspace
+pub fn count_session_config(tmux_storage: &dyn TmuxStorage, workspace_id: &str) -> usize {
+    tmux_storage
+        .read()
+        .sessions
+        .unwrap_or_default()
+        .iter()
+        .filter(|x| refers_to(x, workspace_id))
+        .count()
+}
+
+/// Remove the `tmux.sessions` entries that refer to a workspace, returning how many were removed
+pub fn remove_session_config(tmux: &mut Tmux, workspace_id: &str) -> usize {
+    let Some(sessions) = &mut tmux.sessions else {
+        return 0;
+    };
+    let before = sessions.len();
+    sessions.retain(|x| !refers_to(x, workspace_id));
+    before - sessions.len()
+}
+
+/// Point the `tmux.sessions` entries of a workspace to its new id, returning how many were changed
+pub fn rename_session_config(tmux: &mut Tmux, old_id: &str, new_id: &str) -> usize {
+    let mut renamed = 0;
+    for session in tmux.sessions.iter_mut().flatten() {
+        if let Session::Workspace(workspace) = session
+            && workspace.workspace == old_id
+        {
+            workspace.workspace = new_id.to_string();
+            renamed += 1;
+        }
+    }
+    renamed
+}
+
+fn refers_to(session: &Session, workspace_id: &str) -> bool {
+    matches!(session, Session::Workspace(workspace) if workspace.workspace == workspace_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tmux::{PathSession, WorkspaceSession};
+
+    fn tmux_factory() -> Tmux {
+        Tmux {
+            sessions: Some(vec![
+                Session::Workspace(WorkspaceSession {
+                    windows: vec![],
+                    workspace: "api".to_string(),
+                    name: None,
+                }),
+                Session::Path(PathSession {
+                    path: "~/api".to_string(),
+                    name: "api".to_string(),
+                    ..Default::default()
+                }),
+            ]),
+            default_windows: vec![],
+        }
+    }
+
+    #[test]
+    fn should_remove_only_workspace_sessions() {
+        let mut tmux = tmux_factory();
+
+        assert_eq!(remove_session_config(&mut tmux, "api"), 1);
+
+        let sessions = tmux.sessions.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(matches!(sessions[0], Session::Path(_)));
+    }
+
+    #[test]
+    fn should_rename_workspace_references() {
+        let mut tmux = tmux_factory();
+
+        assert_eq!(rename_session_config(&mut tmux, "api", "backend"), 1);
+
+        let sessions = tmux.sessions.unwrap();
+        assert!(refers_to(&sessions[0], "backend"));
+        assert!(!sessions.iter().any(|x| refers_to(x, "api")));
+    }
+}
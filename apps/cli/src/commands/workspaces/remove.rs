@@ -0,0 +1,137 @@
+//! Command to remove a workspace
+
+use std::process::exit;
+
+use atty::Stream;
+
+use crate::{
+    commands::{
+        command::RafaeltabCommand,
+        workspaces::references::{count_session_config, remove_session_config, running_sessions},
+    },
+    domain::tmux_workspaces::{
+        aggregates::tmux::session::TmuxSession,
+        repositories::{
+            tmux::{
+                description_repository::SessionDescriptionRepository,
+                session_repository::TmuxSessionRepository,
+            },
+            workspace::workspace_repository::WorkspaceRepository,
+        },
+    },
+    storage::{
+        config::{ConfigStorage, ConfigUpdate},
+        error::StorageError,
+        storage_interface::Storage,
+        tmux::Tmux,
+        workspace::Workspace,
+    },
+    utils::prompt::confirm,
+};
+
+#[derive(Default)]
+pub struct WorkspaceRemoveCommand;
+
+pub struct WorkspaceRemoveOptions<'a> {
+    /// Id of the workspace to remove
+    pub id: String,
+    /// Remove without asking, also killing its sessions unless `keep_sessions` is set
+    pub yes: bool,
+    /// Keep the running tmux sessions and the `tmux.sessions` entries of the workspace
+    pub keep_sessions: bool,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub config_storage: &'a dyn ConfigStorage,
+    pub description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+}
+
+impl RafaeltabCommand<WorkspaceRemoveOptions<'_>> for WorkspaceRemoveCommand {
+    fn execute(&self, options: WorkspaceRemoveOptions) {
+        // Workspaces whose root can not be resolved can still be removed
+        let Some((id, name)) = options
+            .workspace_repository
+            .get_workspace_entries()
+            .into_iter()
+            .map(|x| match x {
+                Ok(workspace) => (workspace.id, workspace.name),
+                Err(unresolved) => (unresolved.id, unresolved.name),
+            })
+            .find(|(id, _)| *id == options.id)
+        else {
+            eprintln!("Error: workspace '{}' does not exist", options.id);
+            exit(1);
+        };
+
+        let sessions = running_sessions(options.description_repository, &id);
+        let session_config = count_session_config(options.config_storage, &id);
+
+        let (kill_sessions, remove_config) = if options.yes {
+            (!options.keep_sessions, !options.keep_sessions)
+        } else if atty::is(Stream::Stdout) && atty::is(Stream::Stdin) {
+            if !confirm(&format!("Remove workspace '{}' ({})?", name, id), false) {
+                println!("Operation cancelled.");
+                return;
+            }
+            let kill_sessions = !options.keep_sessions
+                && !sessions.is_empty()
+                && confirm(
+                    &format!("Kill {} running tmux session(s)?", sessions.len()),
+                    true,
+                );
+            let remove_config = !options.keep_sessions
+                && session_config > 0
+                && confirm("Remove its configuration from tmux.sessions?", true);
+            (kill_sessions, remove_config)
+        } else {
+            eprintln!("Error: Not interactive, pass --yes to remove the workspace");
+            exit(1);
+        };
+
+        let kill: &[TmuxSession] = if kill_sessions { &sessions } else { &[] };
+        if let Err(err) = remove_workspace(
+            options.config_storage,
+            options.session_repository,
+            &id,
+            kill,
+            remove_config,
+        ) {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+
+        println!("Removed workspace '{}' ({})", name, id);
+        if !kill.is_empty() {
+            println!("Killed {} tmux session(s)", kill.len());
+        }
+        if remove_config && session_config > 0 {
+            println!("Removed its configuration from tmux.sessions");
+        }
+    }
+}
+
+/// Remove a workspace, and optionally its `tmux.sessions` configuration, in a single write.
+///
+/// The given sessions are killed once the write succeeded, so a failed write leaves them running.
+pub fn remove_workspace(
+    config_storage: &dyn ConfigStorage,
+    session_repository: &dyn TmuxSessionRepository,
+    id: &str,
+    kill: &[TmuxSession],
+    remove_config: bool,
+) -> Result<(), StorageError> {
+    let mut workspaces = Storage::<Vec<Workspace>>::read(config_storage);
+    workspaces.retain(|x| x.id != id);
+    let mut tmux = Storage::<Tmux>::read(config_storage);
+    let tmux = (remove_config && remove_session_config(&mut tmux, id) > 0).then_some(tmux);
+
+    config_storage.write_config(&ConfigUpdate {
+        workspaces: Some(workspaces),
+        tmux,
+        ..Default::default()
+    })?;
+
+    for session in kill {
+        session_repository.kill_session(Some(session));
+    }
+    Ok(())
+}
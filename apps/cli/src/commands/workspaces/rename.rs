@@ -0,0 +1,181 @@
+//! Command to rename a workspace
+
+use std::process::exit;
+
+use atty::Stream;
+
+use crate::{
+    commands::{
+        command::RafaeltabCommand,
+        workspaces::{
+            add::workspace_id,
+            references::{rename_session_config, running_sessions},
+        },
+    },
+    domain::tmux_workspaces::{
+        aggregates::{tmux::session::TmuxSession, workspaces::workspace::Workspace},
+        repositories::{
+            tmux::{
+                description_repository::SessionDescriptionRepository,
+                session_repository::TmuxSessionRepository,
+            },
+            workspace::workspace_repository::WorkspaceRepository,
+        },
+    },
+    infrastructure::tmux_workspaces::repositories::tmux::description_repository::workspace_session_id,
+    storage::{
+        self,
+        config::{ConfigStorage, ConfigUpdate},
+        storage_interface::Storage,
+        tmux::Tmux,
+    },
+    utils::{display::RafaeltabDisplay, prompt::confirm},
+};
+
+#[derive(Default)]
+pub struct WorkspaceRenameCommand;
+
+pub struct WorkspaceRenameOptions<'a> {
+    /// Id of the workspace to rename
+    pub id: String,
+    /// The new name of the workspace
+    pub name: String,
+    /// Derive a new id from the new name instead of keeping the current id
+    pub reslug: bool,
+    /// Kill the running tmux session when the id changes instead of moving it to the new id
+    pub kill_sessions: bool,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub config_storage: &'a dyn ConfigStorage,
+    pub description_repository: &'a dyn SessionDescriptionRepository,
+    pub session_repository: &'a dyn TmuxSessionRepository,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+impl RafaeltabCommand<WorkspaceRenameOptions<'_>> for WorkspaceRenameCommand {
+    fn execute(&self, options: WorkspaceRenameOptions) {
+        let new_id = options.reslug.then(|| workspace_id(&options.name));
+        // Running sessions are found by the id of their workspace, so they are looked up before it
+        // changes
+        let sessions = match &new_id {
+            Some(new_id) if *new_id != options.id => {
+                running_sessions(options.description_repository, &options.id)
+            }
+            _ => vec![],
+        };
+        let action = if sessions.is_empty() {
+            SessionAction::Keep
+        } else if options.kill_sessions {
+            SessionAction::Kill
+        } else if atty::is(Stream::Stdout) && atty::is(Stream::Stdin) {
+            let message = format!(
+                "Move {} running tmux session(s) to the new id?",
+                sessions.len()
+            );
+            if confirm(&message, true) {
+                SessionAction::Move
+            } else if confirm("Kill them instead?", false) {
+                SessionAction::Kill
+            } else {
+                SessionAction::Keep
+            }
+        } else {
+            SessionAction::Move
+        };
+
+        let workspace = match rename_workspace(
+            options.workspace_repository,
+            options.config_storage,
+            &options.id,
+            options.name,
+            new_id,
+        ) {
+            Ok(workspace) => workspace,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                exit(1);
+            }
+        };
+        apply_session_action(options.session_repository, &sessions, &workspace, action);
+        options.display.display(&workspace);
+    }
+}
+
+/// What happens to the running tmux sessions of a workspace whose id changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionAction {
+    /// Give the sessions the name and session id of the renamed workspace
+    Move,
+    /// Kill the sessions
+    Kill,
+    /// Leave the sessions alone, they are no longer recognized as sessions of the workspace
+    Keep,
+}
+
+/// Move or kill the sessions that were running for a workspace before its id changed
+pub fn apply_session_action(
+    session_repository: &dyn TmuxSessionRepository,
+    sessions: &[TmuxSession],
+    workspace: &Workspace,
+    action: SessionAction,
+) {
+    for session in sessions {
+        match action {
+            SessionAction::Move => session_repository.rename_session(
+                session,
+                &workspace.name,
+                &workspace_session_id(&workspace.id),
+            ),
+            SessionAction::Kill => session_repository.kill_session(Some(session)),
+            SessionAction::Keep => {}
+        }
+    }
+}
+
+/// Rename a workspace, moving its `tmux.sessions` configuration along in the same write when the
+/// id changes
+pub fn rename_workspace(
+    workspace_repository: &dyn WorkspaceRepository,
+    config_storage: &dyn ConfigStorage,
+    id: &str,
+    name: String,
+    new_id: Option<String>,
+) -> Result<Workspace, String> {
+    let mut workspaces = Storage::<Vec<storage::workspace::Workspace>>::read(config_storage);
+    let new_id = new_id.filter(|new_id| new_id != id);
+    if let Some(new_id) = &new_id
+        && workspaces.iter().any(|x| x.id == *new_id)
+    {
+        return Err(format!("a workspace with id '{}' already exists", new_id));
+    }
+    let Some(workspace) = workspaces.iter_mut().find(|x| x.id == id) else {
+        return Err(format!("workspace '{}' does not exist", id));
+    };
+
+    workspace.name = name;
+    let tmux = match &new_id {
+        Some(new_id) => {
+            workspace.id = new_id.clone();
+            let mut tmux = Storage::<Tmux>::read(config_storage);
+            (rename_session_config(&mut tmux, id, new_id) > 0).then_some(tmux)
+        }
+        None => None,
+    };
+    config_storage
+        .write_config(&ConfigUpdate {
+            workspaces: Some(workspaces),
+            tmux,
+            ..Default::default()
+        })
+        .map_err(|err| err.to_string())?;
+
+    let id = new_id.as_deref().unwrap_or(id);
+    workspace_repository
+        .get_workspace_entries()
+        .into_iter()
+        .find_map(|x| match x {
+            Ok(workspace) if workspace.id == id => Some(Ok(workspace)),
+            Err(unresolved) if unresolved.id == id => Some(Err(unresolved.error.to_string())),
+            _ => None,
+        })
+        .unwrap_or_else(|| Err(format!("workspace '{}' does not exist", id)))
+}
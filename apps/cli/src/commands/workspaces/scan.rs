@@ -0,0 +1,238 @@
+//! Command to add the git repositories in a directory tree as workspaces
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use atty::Stream;
+use inquire::MultiSelect;
+
+use crate::{
+    commands::{
+        builtin::add_workspace::{slugify, unique_id},
+        command::{OrExit, RafaeltabCommand},
+    },
+    domain::tmux_workspaces::{
+        aggregates::workspaces::workspace::Workspace,
+        repositories::workspace::workspace_repository::WorkspaceRepository,
+    },
+    infrastructure::git::find_repositories,
+    utils::{
+        display::{RafaeltabDisplay, ToDynVec},
+        path::expand_path,
+    },
+};
+
+#[derive(Default)]
+pub struct WorkspaceScanCommand;
+
+pub struct WorkspaceScanOptions<'a> {
+    /// Directory to search for git repositories
+    pub dir: String,
+    /// How many directory levels below `dir` to search
+    pub depth: usize,
+    /// Add every repository that was found without asking
+    pub yes: bool,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+/// A workspace proposed for a repository that was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScannedWorkspace {
+    pub id: String,
+    pub name: String,
+    pub root: String,
+    pub tags: Vec<String>,
+}
+
+impl RafaeltabCommand<WorkspaceScanOptions<'_>> for WorkspaceScanCommand {
+    fn execute(&self, options: WorkspaceScanOptions) {
+        let dir = PathBuf::from(expand_path(&options.dir));
+        let Ok(dir) = fs::canonicalize(&dir) else {
+            eprintln!("Error: directory '{}' does not exist", options.dir);
+            exit(1);
+        };
+
+        let repositories = find_repositories(&dir, options.depth);
+        let existing = options.workspace_repository.get_workspaces().or_exit();
+        let proposals = propose_workspaces(&dir, &repositories, &existing);
+        if proposals.is_empty() {
+            println!("No new git repositories found in {}", dir.display());
+            return;
+        }
+
+        let selected = if options.yes {
+            proposals
+        } else if atty::is(Stream::Stdout) && atty::is(Stream::Stdin) {
+            review(proposals)
+        } else {
+            eprintln!("Error: Not interactive, pass --yes to add every repository that was found");
+            exit(1);
+        };
+
+        let mut added: Vec<Workspace> = vec![];
+        for proposal in selected {
+            match options.workspace_repository.create_workspace(
+                proposal.name,
+                proposal.tags,
+                proposal.root,
+                proposal.id,
+            ) {
+                Ok(workspace) => added.push(workspace),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    exit(1);
+                }
+            }
+        }
+
+        options.display.display_list(added.to_dyn_vec());
+    }
+}
+
+/// Let the user pick the proposals to add, every proposal is selected to begin with
+fn review(proposals: Vec<ScannedWorkspace>) -> Vec<ScannedWorkspace> {
+    let labels: Vec<String> = proposals
+        .iter()
+        .map(|x| {
+            if x.tags.is_empty() {
+                format!("{} ({}): {}", x.name, x.id, x.root)
+            } else {
+                format!("{} ({}): {} {:?}", x.name, x.id, x.root, x.tags)
+            }
+        })
+        .collect();
+
+    let selected = MultiSelect::new("Workspaces to add:", labels)
+        .with_all_selected_by_default()
+        .raw_prompt()
+        .unwrap_or_else(|_| {
+            println!("Operation cancelled.");
+            exit(0);
+        });
+
+    let indices: HashSet<usize> = selected.iter().map(|x| x.index).collect();
+    proposals
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| indices.contains(index))
+        .map(|(_, proposal)| proposal)
+        .collect()
+}
+
+/// Propose a workspace for every repository whose root is not a workspace yet.
+///
+/// The name is the directory name of the repository and the tags are the directories between
+/// `dir` and the repository. Ids are made unique by adding a number.
+pub fn propose_workspaces(
+    dir: &Path,
+    repositories: &[PathBuf],
+    existing: &[Workspace],
+) -> Vec<ScannedWorkspace> {
+    let registered: HashSet<PathBuf> = existing
+        .iter()
+        .map(|x| canonical(Path::new(&expand_path(&x.path))))
+        .collect();
+    let mut ids: HashSet<String> = existing.iter().map(|x| x.id.clone()).collect();
+
+    let mut result = vec![];
+    for repository in repositories {
+        if registered.contains(&canonical(repository)) {
+            continue;
+        }
+
+        let name = repository
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| repository.to_string_lossy().to_string());
+        let tags: Vec<String> = repository
+            .parent()
+            .and_then(|parent| parent.strip_prefix(dir).ok())
+            .map(|relative| {
+                relative
+                    .components()
+                    .map(|x| slugify(&x.as_os_str().to_string_lossy()))
+                    .filter(|x| !x.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let id = unique_id(&slugify(&name), &ids);
+        ids.insert(id.clone());
+        result.push(ScannedWorkspace {
+            id,
+            name,
+            root: repository.to_string_lossy().to_string(),
+            tags,
+        });
+    }
+    result
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(id: &str, path: &str) -> Workspace {
+        Workspace {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_tag_workspaces_with_their_parent_directories() {
+        let dir = Path::new("/src");
+        let repositories = vec![
+            PathBuf::from("/src/work/Backend Api"),
+            PathBuf::from("/src/dotfiles"),
+        ];
+
+        let result = propose_workspaces(dir, &repositories, &[]);
+
+        assert_eq!(
+            result,
+            vec![
+                ScannedWorkspace {
+                    id: "backend-api".to_string(),
+                    name: "Backend Api".to_string(),
+                    root: "/src/work/Backend Api".to_string(),
+                    tags: vec!["work".to_string()],
+                },
+                ScannedWorkspace {
+                    id: "dotfiles".to_string(),
+                    name: "dotfiles".to_string(),
+                    root: "/src/dotfiles".to_string(),
+                    tags: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_registered_roots_and_avoid_taken_ids() {
+        let dir = Path::new("/src");
+        let repositories = vec![
+            PathBuf::from("/src/work/api"),
+            PathBuf::from("/src/personal/api"),
+        ];
+        let existing = vec![workspace("api", "/src/work/api")];
+
+        let result = propose_workspaces(dir, &repositories, &existing);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].root, "/src/personal/api");
+        assert_eq!(result[0].id, "api-2");
+        assert_eq!(result[0].tags, vec!["personal"]);
+    }
+}
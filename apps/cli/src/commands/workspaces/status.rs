@@ -0,0 +1,345 @@
+//! Command to show the git status of every workspace
+
+use std::{collections::HashSet, path::Path};
+
+use serde_json::{Value, json};
+
+use crate::{
+    commands::command::RafaeltabCommand,
+    domain::tmux_workspaces::{
+        aggregates::{
+            tmux::description::session::SessionKind,
+            workspaces::{tag_query::TagQuery, workspace::Workspace},
+        },
+        repositories::{
+            tmux::description_repository::SessionDescriptionRepository,
+            workspace::workspace_repository::WorkspaceRepository,
+        },
+    },
+    infrastructure::git::{
+        RepositoryStatus, discover_worktrees_for_workspace, get_repository_status,
+    },
+    utils::{
+        display::{RafaeltabDisplay, RafaeltabDisplayItem, ToDynVec},
+        parallel::map_bounded,
+        path::expand_path,
+        table::format_table,
+    },
+};
+
+#[derive(Default)]
+pub struct WorkspaceStatusCommand;
+
+pub struct WorkspaceStatusOptions<'a> {
+    /// Only show the workspaces whose tags match
+    pub filter: Option<&'a TagQuery>,
+    /// Show the workspaces as one table instead of a line per workspace
+    pub table: bool,
+    /// How many workspaces are checked at the same time
+    pub parallel: usize,
+    pub workspace_repository: &'a dyn WorkspaceRepository,
+    pub session_description_repository: &'a dyn SessionDescriptionRepository,
+    pub display: &'a dyn RafaeltabDisplay,
+}
+
+/// The git and tmux state of a workspace
+pub struct WorkspaceStatus {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// `None` when the root is not a git repository
+    pub git: Option<RepositoryStatus>,
+    /// Number of linked worktrees, the main worktree is not counted
+    pub worktrees: usize,
+    /// Whether the tmux session of the workspace is running
+    pub session: bool,
+}
+
+impl RafaeltabCommand<WorkspaceStatusOptions<'_>> for WorkspaceStatusCommand {
+    fn execute(&self, options: WorkspaceStatusOptions) {
+        let workspaces: Vec<Workspace> = options
+            .workspace_repository
+            .get_workspace_entries()
+            .into_iter()
+            .filter_map(|x| {
+                x.inspect_err(|unresolved| {
+                    eprintln!(
+                        "Warning: skipping '{}': {}",
+                        unresolved.id, unresolved.error
+                    )
+                })
+                .ok()
+            })
+            .filter(|x| options.filter.is_none_or(|filter| filter.matches(&x.tags)))
+            .collect();
+
+        let running: HashSet<String> = options
+            .session_description_repository
+            .get_session_description_entries()
+            .into_iter()
+            .flatten()
+            .filter(|x| x.session.is_some())
+            .filter_map(|x| match x.kind {
+                SessionKind::Workspace(workspace) => Some(workspace.id),
+                SessionKind::Path(_) => None,
+            })
+            .collect();
+
+        let statuses = get_workspace_statuses(&workspaces, &running, options.parallel);
+
+        if options.table {
+            options.display.display(&WorkspaceStatusTable { statuses });
+        } else {
+            options.display.display_list(statuses.to_dyn_vec());
+        }
+    }
+}
+
+/// Get the status of every workspace with at most `parallel` being checked at once.
+///
+/// Every workspace runs a few git commands, doing them side by side keeps large setups fast. The
+/// statuses are returned in the order of the workspaces.
+fn get_workspace_statuses(
+    workspaces: &[Workspace],
+    running: &HashSet<String>,
+    parallel: usize,
+) -> Vec<WorkspaceStatus> {
+    map_bounded(workspaces.iter().collect(), parallel, |workspace| {
+        get_workspace_status(workspace, running.contains(&workspace.id))
+    })
+}
+
+fn get_workspace_status(workspace: &Workspace, session: bool) -> WorkspaceStatus {
+    let path = expand_path(&workspace.path);
+    let git = get_repository_status(Path::new(&path)).ok();
+    let worktrees = match git {
+        Some(_) => discover_worktrees_for_workspace(Path::new(&path))
+            .map(|x| x.len())
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    WorkspaceStatus {
+        id: workspace.id.clone(),
+        name: workspace.name.clone(),
+        path,
+        git,
+        worktrees,
+        session,
+    }
+}
+
+impl WorkspaceStatus {
+    fn branch_text(&self) -> String {
+        match &self.git {
+            None => "not a git repository".to_string(),
+            Some(RepositoryStatus { branch: None, .. }) => "(detached)".to_string(),
+            Some(RepositoryStatus {
+                branch: Some(branch),
+                ..
+            }) => branch.clone(),
+        }
+    }
+
+    fn session_text(&self) -> &'static str {
+        if self.session { "running" } else { "-" }
+    }
+}
+
+impl RafaeltabDisplayItem for WorkspaceStatus {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "path": self.path,
+            "git": self.git.as_ref().map(|git| json!({
+                "branch": git.branch,
+                "upstream": git.upstream,
+                "ahead": git.ahead,
+                "behind": git.behind,
+                "dirty": git.is_dirty(),
+                "staged": git.staged,
+                "modified": git.modified,
+                "untracked": git.untracked,
+                "conflicted": git.conflicted,
+                "stashes": git.stashes,
+            })),
+            "worktrees": self.worktrees,
+            "session": self.session,
+        })
+    }
+
+    fn to_pretty_string(&self) -> String {
+        let Some(git) = &self.git else {
+            return format!("{} ({}): {}", self.name, self.id, self.branch_text());
+        };
+
+        let mut details = vec![];
+        if git.upstream.is_some() {
+            if git.ahead > 0 {
+                details.push(format!("{} ahead", git.ahead));
+            }
+            if git.behind > 0 {
+                details.push(format!("{} behind", git.behind));
+            }
+        } else {
+            details.push("no upstream".to_string());
+        }
+        for (count, text) in [
+            (git.staged, "staged"),
+            (git.modified, "modified"),
+            (git.untracked, "untracked"),
+            (git.conflicted, "conflicted"),
+        ] {
+            if count > 0 {
+                details.push(format!("{} {}", count, text));
+            }
+        }
+        if !git.is_dirty() {
+            details.push("clean".to_string());
+        }
+        if git.stashes > 0 {
+            details.push(format!("{} stashed", git.stashes));
+        }
+        if self.worktrees > 0 {
+            details.push(format!("{} worktrees", self.worktrees));
+        }
+        if self.session {
+            details.push("session running".to_string());
+        }
+
+        format!(
+            "{} ({}) on {}: {}",
+            self.name,
+            self.id,
+            self.branch_text(),
+            details.join(", ")
+        )
+    }
+}
+
+/// The status of all workspaces, shown as a table
+struct WorkspaceStatusTable {
+    statuses: Vec<WorkspaceStatus>,
+}
+
+impl RafaeltabDisplayItem for WorkspaceStatusTable {
+    fn to_json(&self) -> Value {
+        Value::Array(self.statuses.iter().map(|x| x.to_json()).collect())
+    }
+
+    fn to_pretty_string(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .statuses
+            .iter()
+            .map(|x| {
+                let counts = match &x.git {
+                    Some(git) => [
+                        git.staged,
+                        git.modified,
+                        git.untracked,
+                        git.conflicted,
+                        git.stashes,
+                    ]
+                    .map(|x| x.to_string()),
+                    None => ["-", "-", "-", "-", "-"].map(|x| x.to_string()),
+                };
+                let (ahead, behind) = match &x.git {
+                    Some(git) if git.upstream.is_some() => {
+                        (git.ahead.to_string(), git.behind.to_string())
+                    }
+                    _ => ("-".to_string(), "-".to_string()),
+                };
+                let [staged, modified, untracked, conflicted, stashes] = counts;
+                let branch = match &x.git {
+                    Some(_) => x.branch_text(),
+                    None => "-".to_string(),
+                };
+                vec![
+                    x.id.clone(),
+                    branch,
+                    staged,
+                    modified,
+                    untracked,
+                    conflicted,
+                    ahead,
+                    behind,
+                    stashes,
+                    x.worktrees.to_string(),
+                    x.session_text().to_string(),
+                ]
+            })
+            .collect();
+
+        format_table(
+            &[
+                "WORKSPACE",
+                "BRANCH",
+                "STAGED",
+                "MODIFIED",
+                "UNTRACKED",
+                "CONFLICTS",
+                "AHEAD",
+                "BEHIND",
+                "STASHES",
+                "WORKTREES",
+                "SESSION",
+            ],
+            &rows,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(git: Option<RepositoryStatus>) -> WorkspaceStatus {
+        WorkspaceStatus {
+            id: "api".to_string(),
+            name: "Api".to_string(),
+            path: "/src/api".to_string(),
+            git,
+            worktrees: 0,
+            session: false,
+        }
+    }
+
+    #[test]
+    fn should_describe_a_clean_workspace() {
+        let result = status(Some(RepositoryStatus {
+            branch: Some("main".to_string()),
+            upstream: Some("origin/main".to_string()),
+            ..Default::default()
+        }));
+
+        assert_eq!(result.to_pretty_string(), "Api (api) on main: clean");
+    }
+
+    #[test]
+    fn should_describe_a_dirty_workspace() {
+        let mut result = status(Some(RepositoryStatus {
+            branch: Some("feature".to_string()),
+            upstream: Some("origin/feature".to_string()),
+            ahead: 2,
+            modified: 3,
+            stashes: 1,
+            ..Default::default()
+        }));
+        result.worktrees = 2;
+        result.session = true;
+
+        assert_eq!(
+            result.to_pretty_string(),
+            "Api (api) on feature: 2 ahead, 3 modified, 1 stashed, 2 worktrees, session running"
+        );
+    }
+
+    #[test]
+    fn should_describe_workspaces_without_repository() {
+        let result = status(None);
+
+        assert_eq!(result.to_pretty_string(), "Api (api): not a git repository");
+        assert_eq!(result.to_json()["git"], Value::Null);
+    }
+}
@@ -28,7 +28,11 @@ use crate::{
     },
     infrastructure::{git, tmux_workspaces::tmux::session_detection::get_current_tmux_session},
     storage::worktree::WorktreeStorage,
-    utils::path::expand_path,
+    utils::{
+        env::{Environment, with_env},
+        interpolation::InterpolationContext,
+        path::expand_path,
+    },
 };
 
 #[derive(Default)]
@@ -207,13 +211,32 @@ impl WorktreeCompleteCommand {
         }
 
         // 6. Find the workspace this worktree belongs to and load config
-        let workspaces = options.workspace_repository.get_workspaces();
+        let workspaces = match options.workspace_repository.get_workspaces() {
+            Ok(workspaces) => workspaces,
+            Err(err) => return WorktreeCompleteResult::Failed(err.into()),
+        };
         let workspace = find_workspace_for_path(&main_repo_path, &workspaces);
 
         let global_config = options.worktree_storage.read();
         let workspace_config = workspace.and_then(|ws| ws.worktree.clone());
-        let merged_config =
-            MergedWorktreeConfig::merge(global_config.as_ref(), workspace_config.as_ref());
+        let context = match workspace {
+            Some(ws) => InterpolationContext::for_workspace(&ws.id, &expand_path(&ws.path)),
+            None => InterpolationContext::default(),
+        }
+        .with_worktree(&branch_name, &worktree_path.to_string_lossy());
+        let merged_config = match MergedWorktreeConfig::merge_interpolated(
+            global_config.as_ref(),
+            workspace_config.as_ref(),
+            workspace.map(|ws| ws.id.as_str()).unwrap_or_default(),
+            &context,
+        ) {
+            Ok(config) => config,
+            Err(err) => return WorktreeCompleteResult::Failed(err.into()),
+        };
+        let env = match workspace.map(|ws| ws.environment(&context)).transpose() {
+            Ok(env) => env.unwrap_or_default(),
+            Err(err) => return WorktreeCompleteResult::Failed(err.into()),
+        };
 
         // ===== PHASE 2: DETERMINE EXECUTION FLOW =====
 
@@ -251,6 +274,7 @@ impl WorktreeCompleteCommand {
                 options.yes,
                 &current_dir,
                 &merged_config,
+                &env,
                 options.session_repository,
                 options.client_repository,
             )
@@ -389,6 +413,7 @@ fn execute_cleanup_directly(
     yes: bool,
     current_dir: &Path,
     merged_config: &MergedWorktreeConfig,
+    env: &Environment,
     session_repository: &dyn TmuxSessionRepository,
     client_repository: &dyn TmuxClientRepository,
 ) -> WorktreeCompleteResult {
@@ -432,7 +457,7 @@ fn execute_cleanup_directly(
     if !skip_destroy {
         for command in &merged_config.on_destroy {
             println!("  Running onDestroy: {}", command);
-            let result = cmd!("sh", "-c", command)
+            let result = with_env(cmd!("sh", "-c", command), env)
                 .dir(worktree_path)
                 .stderr_to_stdout()
                 .read();
@@ -553,7 +578,7 @@ fn ensure_workspace_session_exists(
     }
 
     // Create the session using description repository
-    let descriptions = description_repository.get_session_descriptions();
+    let descriptions = description_repository.get_session_descriptions()?;
     let workspace_description = descriptions
         .iter()
         .find(|d| d.name == workspace.name)
@@ -6,10 +6,12 @@ use duct::cmd;
 use inquire::Confirm;
 
 use crate::{
-    commands::command::RafaeltabCommand,
+    commands::{command::RafaeltabCommand, tmux::session_utils::get_windows_for_workspace},
     domain::{
         tmux_workspaces::{
-            aggregates::workspaces::workspace::Workspace,
+            aggregates::{
+                tmux::description::window::WindowDescription, workspaces::workspace::Workspace,
+            },
             repositories::{
                 tmux::{
                     client_repository::{SwitchClientTarget, TmuxClientRepository},
@@ -28,7 +30,11 @@ use crate::{
     },
     infrastructure::git::{self, BranchLocation, GitError, symlink::create_symlinks},
     storage::{tmux::TmuxStorage, worktree::WorktreeStorage},
-    utils::path::expand_path,
+    utils::{
+        env::{Environment, with_env},
+        interpolation::InterpolationContext,
+        path::expand_path,
+    },
 };
 
 #[derive(Default)]
@@ -146,7 +152,10 @@ impl WorktreeStartCommand {
         };
 
         // 2. Find the workspace for the current directory
-        let workspaces = options.workspace_repository.get_workspaces();
+        let workspaces = match options.workspace_repository.get_workspaces() {
+            Ok(workspaces) => workspaces,
+            Err(err) => return WorktreeStartResult::Failed(err.into()),
+        };
         let workspace = match find_workspace_for_path(&current_dir, &workspaces) {
             Some(ws) => ws,
             None => {
@@ -182,12 +191,32 @@ impl WorktreeStartCommand {
             });
         }
 
-        // 6. Merge configurations
-        let mut merged_config =
-            MergedWorktreeConfig::merge(global_config.as_ref(), workspace_config.as_ref());
+        // 6. Merge configurations, the path of the worktree is known before it exists
+        let worktree_path = calculate_worktree_path(&git_root, &options.branch_name);
+        let context = InterpolationContext::for_workspace(&workspace.id, &workspace_root)
+            .with_worktree(&options.branch_name, &worktree_path.to_string_lossy());
+        let mut merged_config = match MergedWorktreeConfig::merge_interpolated(
+            global_config.as_ref(),
+            workspace_config.as_ref(),
+            &workspace.id,
+            &context,
+        ) {
+            Ok(config) => config,
+            Err(err) => return WorktreeStartResult::Failed(err.into()),
+        };
         if options.no_tmux {
             merged_config.tmux = false;
         }
+        let env = match workspace.environment(&context) {
+            Ok(env) => env,
+            Err(err) => return WorktreeStartResult::Failed(err.into()),
+        };
+        // Windows are resolved before anything is created, so a broken window creates nothing
+        let windows = match get_windows_for_workspace(&workspace.id, options.tmux_storage, &context)
+        {
+            Ok(windows) => windows,
+            Err(err) => return WorktreeStartResult::Failed(err.into()),
+        };
 
         // 7. Get current branch (base branch)
         let base_branch = match git::get_current_branch(&git_root) {
@@ -210,15 +239,12 @@ impl WorktreeStartCommand {
             BranchLocation::None => BranchStatus::New,
         };
 
-        // 9. Calculate worktree path
-        let worktree_path = calculate_worktree_path(&git_root, &options.branch_name);
-
-        // 10. Check if path already exists
+        // 9. Check if path already exists
         if worktree_path.exists() {
             return WorktreeStartResult::Failed(WorktreeError::PathConflict(worktree_path));
         }
 
-        // 11. Build creation info for confirmation
+        // 10. Build creation info for confirmation
         let creation_info = WorktreeCreationInfo {
             branch_name: options.branch_name.clone(),
             base_branch: base_branch.clone(),
@@ -228,7 +254,7 @@ impl WorktreeStartCommand {
             workspace_name: workspace.name.clone(),
         };
 
-        // 12. Show confirmation prompt (unless --yes)
+        // 11. Show confirmation prompt (unless --yes)
         if !options.yes {
             println!();
             println!("Creating worktree:");
@@ -266,7 +292,7 @@ impl WorktreeStartCommand {
             }
         }
 
-        // 13. Create the worktree
+        // 12. Create the worktree
         if let Err(e) = git::create_worktree(
             &git_root,
             &options.branch_name,
@@ -278,7 +304,7 @@ impl WorktreeStartCommand {
         }
         println!("✓ Created git worktree");
 
-        // 14. Create symlinks
+        // 13. Create symlinks
         if !merged_config.symlink_files.is_empty() {
             match create_symlinks(&git_root, &worktree_path, &merged_config.symlink_files) {
                 Ok(result) => {
@@ -308,11 +334,11 @@ impl WorktreeStartCommand {
             }
         }
 
-        // 15. Run onCreate commands
+        // 14. Run onCreate commands
         let mut on_create_failed: Option<(String, String)> = None;
         for command in &merged_config.on_create {
             println!("  Running: {}", command);
-            let result = cmd!("sh", "-c", command)
+            let result = with_env(cmd!("sh", "-c", command), &env)
                 .dir(&worktree_path)
                 .stderr_to_stdout()
                 .read();
@@ -334,7 +360,7 @@ impl WorktreeStartCommand {
             }
         }
 
-        // 16. Optionally create tmux session
+        // 15. Optionally create tmux session
         let session_name = format!("{}-{}", workspace.name, options.branch_name);
         let session = if merged_config.tmux {
             // Create a session description for the worktree
@@ -342,14 +368,14 @@ impl WorktreeStartCommand {
                 options.session_repository,
                 &session_name,
                 &worktree_path,
-                &workspace.id,
-                options.tmux_storage,
+                windows,
+                &env,
             )
         } else {
             None
         };
 
-        // 17. If onCreate failed, don't switch to session
+        // 16. If onCreate failed, don't switch to session
         if let Some((failed_cmd, error)) = on_create_failed {
             return WorktreeStartResult::PartialSuccess {
                 worktree_path: worktree_path.display().to_string(),
@@ -364,7 +390,7 @@ impl WorktreeStartCommand {
             };
         }
 
-        // 18. Switch to the new tmux session
+        // 17. Switch to the new tmux session
         if let Some(ref sess) = session {
             options
                 .client_repository
@@ -420,8 +446,9 @@ fn find_workspace_worktree_config(
     workspace_repository: &dyn WorkspaceRepository,
 ) -> Option<crate::storage::worktree::WorkspaceWorktreeConfig> {
     workspace_repository
-        .get_workspaces()
+        .get_workspace_entries()
         .iter()
+        .flatten()
         .find(|ws| ws.id == workspace_id)
         .and_then(|ws| ws.worktree.clone())
 }
@@ -431,25 +458,22 @@ fn create_tmux_session(
     session_repository: &dyn TmuxSessionRepository,
     session_name: &str,
     worktree_path: &Path,
-    workspace_id: &str,
-    tmux_storage: &dyn TmuxStorage,
+    windows: Vec<WindowDescription>,
+    env: &Environment,
 ) -> Option<crate::domain::tmux_workspaces::aggregates::tmux::session::TmuxSession> {
-    use crate::commands::tmux::session_utils::get_windows_for_workspace;
     use crate::domain::tmux_workspaces::aggregates::tmux::description::session::{
         PathSessionDescription, SessionDescription, SessionKind,
     };
-    use uuid::{Uuid, uuid};
-
-    let worktree_namespace = uuid!("f47ac10b-58cc-4372-a567-0e02b2c3d479");
-    let id = Uuid::new_v5(&worktree_namespace, session_name.as_bytes());
+    use crate::infrastructure::tmux_workspaces::repositories::tmux::description_repository::worktree_session_id;
 
     let description = SessionDescription {
-        id: id.to_string(),
+        id: worktree_session_id(session_name),
         name: session_name.to_string(),
         kind: SessionKind::Path(PathSessionDescription {
             path: worktree_path.to_string_lossy().to_string(),
         }),
-        windows: get_windows_for_workspace(workspace_id, tmux_storage),
+        windows,
+        env: env.clone(),
         session: None,
     };
 
@@ -482,15 +506,14 @@ mod tests {
                     id: "workspace-1".to_string(),
                     root: "~/test1".to_string(),
                     name: "Test 1".to_string(),
-                    tags: None,
-                    worktree: None,
+                    ..Default::default()
                 },
                 Workspace {
                     id: "workspace-with-config".to_string(),
                     root: "~/test2".to_string(),
                     name: "Test 2".to_string(),
-                    tags: None,
                     worktree: Some(worktree_config.clone()),
+                    ..Default::default()
                 },
             ],
         };
@@ -516,8 +539,7 @@ mod tests {
                 id: "workspace-no-config".to_string(),
                 root: "~/test".to_string(),
                 name: "Test".to_string(),
-                tags: None,
-                worktree: None,
+                ..Default::default()
             }],
         };
 
@@ -537,8 +559,7 @@ mod tests {
                 id: "workspace-1".to_string(),
                 root: "~/test".to_string(),
                 name: "Test".to_string(),
-                tags: None,
-                worktree: None,
+                ..Default::default()
             }],
         };
 
@@ -566,8 +587,8 @@ mod tests {
                 id: "test-workspace".to_string(),
                 root: "~/test".to_string(),
                 name: "Test Workspace".to_string(),
-                tags: None,
                 worktree: Some(workspace_config),
+                ..Default::default()
             }],
         };
 
@@ -606,8 +627,7 @@ mod tests {
                 id: "no-config-workspace".to_string(),
                 root: "~/test".to_string(),
                 name: "No Config Workspace".to_string(),
-                tags: None,
-                worktree: None,
+                ..Default::default()
             }],
         };
 
@@ -649,8 +669,8 @@ mod tests {
                 id: "workspace-only".to_string(),
                 root: "~/test".to_string(),
                 name: "Workspace Only".to_string(),
-                tags: None,
                 worktree: Some(workspace_config),
+                ..Default::default()
             }],
         };
 
@@ -1,2 +1,3 @@
+pub mod pane;
 pub mod session;
 pub mod window;
@@ -0,0 +1,144 @@
+use crate::{
+    storage::tmux::{Pane, PaneSplit},
+    utils::interpolation::{InterpolationContext, InterpolationError, interpolate},
+};
+
+/// A pane of a window, or a pane that is split into more panes
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PaneDescription {
+    pub command: Option<String>,
+    /// Directory relative to the directory of the window
+    pub cwd: Option<String>,
+    /// Percentage of the parent split
+    pub size: Option<u32>,
+    pub focus: bool,
+    pub split: PaneSplit,
+    /// The panes this pane is split into, empty for a single pane
+    pub panes: Vec<PaneDescription>,
+}
+
+impl PaneDescription {
+    /// Describe configured panes, expanding the variables in their commands and directories.
+    ///
+    /// The error points at `location` when a variable can not be resolved.
+    pub fn from_config(
+        panes: &[Pane],
+        context: &InterpolationContext,
+        location: &str,
+    ) -> Result<Vec<PaneDescription>, InterpolationError> {
+        panes
+            .iter()
+            .enumerate()
+            .map(|(index, pane)| {
+                let location = format!("{}[{}]", location, index);
+                let expand = |value: &Option<String>, key: &str| {
+                    value
+                        .as_ref()
+                        .map(|value| {
+                            interpolate(value, context)
+                                .map_err(|err| err.at(format!("{}.{}", location, key)))
+                        })
+                        .transpose()
+                };
+                Ok(PaneDescription {
+                    command: expand(&pane.command, "command")?,
+                    cwd: expand(&pane.cwd, "cwd")?,
+                    size: pane.size,
+                    focus: pane.focus.unwrap_or(false),
+                    split: pane.split.unwrap_or_default(),
+                    panes: PaneDescription::from_config(
+                        pane.panes.as_deref().unwrap_or_default(),
+                        context,
+                        &format!("{}.panes", location),
+                    )?,
+                })
+            })
+            .collect()
+    }
+
+    /// The pane that takes the top left of this pane once it is split, tmux keeps the pane that is
+    /// split there
+    pub fn first_leaf(&self) -> &PaneDescription {
+        match self.panes.first() {
+            Some(pane) => pane.first_leaf(),
+            None => self,
+        }
+    }
+
+    /// The size of every pane after the first, in percent of the pane it is split from.
+    ///
+    /// The panes are created from the first, so every split divides what is left between the
+    /// previous pane and the ones that follow.
+    pub fn split_sizes(&self) -> Vec<u32> {
+        let fixed: u32 = self.panes.iter().filter_map(|x| x.size).sum();
+        let flexible = self.panes.iter().filter(|x| x.size.is_none()).count() as u32;
+        let share = 100u32
+            .saturating_sub(fixed)
+            .checked_div(flexible)
+            .unwrap_or(0);
+        let sizes: Vec<u32> = self
+            .panes
+            .iter()
+            .map(|x| x.size.unwrap_or(share).max(1))
+            .collect();
+
+        (1..sizes.len())
+            .map(|index| {
+                let rest: u32 = sizes[index..].iter().sum();
+                let total = rest + sizes[index - 1];
+                ((rest * 100 + total / 2) / total).clamp(1, 99)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(size: Option<u32>) -> PaneDescription {
+        PaneDescription {
+            size,
+            ..Default::default()
+        }
+    }
+
+    fn split(panes: Vec<PaneDescription>) -> PaneDescription {
+        PaneDescription {
+            panes,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_split_equally_without_sizes() {
+        assert_eq!(split(vec![pane(None), pane(None)]).split_sizes(), vec![50]);
+        assert_eq!(
+            split(vec![pane(None), pane(None), pane(None)]).split_sizes(),
+            vec![67, 50]
+        );
+    }
+
+    #[test]
+    fn should_share_what_is_left_between_panes_without_size() {
+        // 60% | 20% | 20%
+        assert_eq!(
+            split(vec![pane(Some(60)), pane(None), pane(None)]).split_sizes(),
+            vec![40, 50]
+        );
+        // 30% | 70%
+        assert_eq!(
+            split(vec![pane(None), pane(Some(70))]).split_sizes(),
+            vec![70]
+        );
+    }
+
+    #[test]
+    fn should_find_the_first_leaf() {
+        let mut editor = pane(None);
+        editor.command = Some("nvim".to_string());
+        let tree = split(vec![split(vec![editor.clone(), pane(None)]), pane(None)]);
+
+        assert_eq!(tree.first_leaf(), &editor);
+    }
+}
@@ -2,9 +2,11 @@ use serde_json::{Value, json};
 
 use crate::{
     domain::tmux_workspaces::aggregates::{
-        tmux::session::TmuxSession, workspaces::workspace::Workspace,
+        tmux::session::TmuxSession,
+        workspaces::workspace::{Workspace, WorkspaceTag},
     },
-    utils::display::RafaeltabDisplayItem,
+    storage::frecency::Frecency,
+    utils::{display::RafaeltabDisplayItem, env::Environment},
 };
 
 use super::window::WindowDescription;
@@ -14,18 +16,47 @@ pub struct SessionDescription {
     pub name: String,
     pub kind: SessionKind,
     pub windows: Vec<WindowDescription>,
+    /// Environment variables of every window in the session
+    pub env: Environment,
     pub session: Option<TmuxSession>,
 }
 
 pub enum SessionKind {
     Path(PathSessionDescription),
-    Workspace(Workspace),
+    Workspace(Box<Workspace>),
 }
 
 pub struct PathSessionDescription {
     pub path: String,
 }
 
+impl SessionDescription {
+    /// How important the session is, path sessions are never more important than others
+    pub fn importance(&self) -> i32 {
+        match &self.kind {
+            SessionKind::Path(_) => 0,
+            SessionKind::Workspace(workspace) => workspace.importance,
+        }
+    }
+
+    /// Tags of the workspace of the session, path sessions have no tags
+    pub fn tags(&self) -> &[WorkspaceTag] {
+        match &self.kind {
+            SessionKind::Path(_) => &[],
+            SessionKind::Workspace(workspace) => &workspace.tags,
+        }
+    }
+
+    /// The key under which switching to this session is recorded, workspace sessions share it
+    /// with their workspace
+    pub fn frecency_key(&self) -> String {
+        match &self.kind {
+            SessionKind::Path(_) => Frecency::session_key(&self.name),
+            SessionKind::Workspace(workspace) => Frecency::workspace_key(&workspace.id),
+        }
+    }
+}
+
 impl RafaeltabDisplayItem for SessionDescription {
     fn to_json(&self) -> serde_json::Value {
         json!({
@@ -1,5 +1,90 @@
+use super::pane::PaneDescription;
+use crate::{
+    storage::tmux::Window,
+    utils::{
+        env::{Environment, interpolate_env},
+        interpolation::{InterpolationContext, InterpolationError, interpolate},
+    },
+};
+
 #[derive(Clone)]
 pub struct WindowDescription {
     pub name: String,
     pub command: Option<String>,
+    /// Environment variables of only this window, on top of the ones of the session
+    pub env: Environment,
+    /// The panes the window is split into, `None` for a window with a single pane
+    pub panes: Option<PaneDescription>,
+    /// Tmux layout applied once the panes exist
+    pub layout: Option<String>,
+    /// Directory relative to the directory of the session
+    pub cwd: Option<String>,
+    pub background: bool,
+    pub focus: bool,
+}
+
+impl WindowDescription {
+    /// Describe configured windows, expanding the variables in their commands and environment.
+    ///
+    /// The error points at `location` when a variable can not be resolved.
+    pub fn from_config(
+        windows: &[Window],
+        context: &InterpolationContext,
+        location: &str,
+    ) -> Result<Vec<WindowDescription>, InterpolationError> {
+        windows
+            .iter()
+            .enumerate()
+            .map(|(index, x)| {
+                let location = format!("{}[{}]", location, index);
+                let expand = |value: &Option<String>, key: &str| {
+                    value
+                        .as_ref()
+                        .map(|value| {
+                            interpolate(value, context)
+                                .map_err(|err| err.at(format!("{}.{}", location, key)))
+                        })
+                        .transpose()
+                };
+                let panes = match x.panes.as_deref().filter(|panes| !panes.is_empty()) {
+                    Some(panes) => Some(PaneDescription {
+                        split: x.split.unwrap_or_default(),
+                        panes: PaneDescription::from_config(
+                            panes,
+                            context,
+                            &format!("{}.panes", location),
+                        )?,
+                        ..Default::default()
+                    }),
+                    None => None,
+                };
+                Ok(WindowDescription {
+                    name: x.name.clone(),
+                    command: expand(&x.command, "command")?,
+                    env: interpolate_env(x.env.as_ref(), context, &format!("{}.env", location))?,
+                    panes,
+                    layout: x.layout.clone(),
+                    cwd: expand(&x.cwd, "cwd")?,
+                    background: x.background.unwrap_or(false),
+                    focus: x.focus.unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+
+    /// The command of the pane the window is created with, the first pane falls back to the
+    /// command of the window
+    pub fn first_command(&self) -> Option<String> {
+        self.panes
+            .as_ref()
+            .and_then(|x| x.first_leaf().command.clone())
+            .or_else(|| self.command.clone())
+    }
+
+    /// The directory of the pane the window is created with, relative to the window
+    pub fn first_cwd(&self) -> Option<&str> {
+        self.panes
+            .as_ref()
+            .and_then(|x| x.first_leaf().cwd.as_deref())
+    }
 }
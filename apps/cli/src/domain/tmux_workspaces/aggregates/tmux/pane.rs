@@ -2,12 +2,30 @@ use serde_json::json;
 
 use crate::utils::display::RafaeltabDisplayItem;
 
+/// Programs that are the shell of a pane rather than something that was started in it
+const SHELLS: [&str; 8] = ["bash", "zsh", "fish", "sh", "dash", "ksh", "tcsh", "nu"];
+
 #[derive(Debug, Clone)]
 pub struct TmuxPane {
     pub id: String,
     pub index: String,
     pub title: String,
     pub window_id: String,
+    /// Process id of the program tmux started in the pane, usually its shell
+    pub pid: String,
+    /// Directory of the process running in the pane
+    pub current_path: String,
+    /// Name of the process running in the pane
+    pub current_command: String,
+    /// Whether this is the active pane of its window
+    pub active: bool,
+}
+
+impl TmuxPane {
+    /// The program running in the pane, `None` when that is just its shell
+    pub fn program(&self) -> Option<&str> {
+        Some(self.current_command.as_str()).filter(|x| !x.is_empty() && !SHELLS.contains(x))
+    }
 }
 
 impl RafaeltabDisplayItem for TmuxPane {
@@ -17,6 +35,10 @@ impl RafaeltabDisplayItem for TmuxPane {
             index,
             title,
             window_id,
+            pid: _,
+            current_path: _,
+            current_command: _,
+            active: _,
         } = self;
         json!({
             "id": id,
@@ -32,6 +54,10 @@ impl RafaeltabDisplayItem for TmuxPane {
             index,
             title,
             window_id,
+            pid: _,
+            current_path: _,
+            current_command: _,
+            active: _,
         } = self;
         format!("{} {} {} {}", id, index, title, window_id)
     }
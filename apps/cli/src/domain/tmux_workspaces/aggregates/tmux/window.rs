@@ -10,6 +10,12 @@ pub struct TmuxWindow {
     pub index: String,
     pub name: String,
     pub panes: Option<Vec<TmuxPane>>,
+    /// Layout string of the panes, as printed by `list-windows`
+    pub layout: String,
+    /// Whether this is the current window of its session
+    pub active: bool,
+    /// Whether tmux flagged activity in the window, only set with the `monitor-activity` option
+    pub activity: bool,
     pub include_fields: WindowIncludeFields,
 }
 
@@ -25,6 +31,9 @@ impl RafaeltabDisplayItem for TmuxWindow {
             index,
             name,
             panes: _,
+            layout: _,
+            active: _,
+            activity: _,
             include_fields: _,
         } = self;
         let mut value = json!({
@@ -45,6 +54,9 @@ impl RafaeltabDisplayItem for TmuxWindow {
             index,
             name,
             panes: _,
+            layout: _,
+            active: _,
+            activity: _,
             include_fields: _,
         } = self;
         if let Some(panes) = &self.panes {
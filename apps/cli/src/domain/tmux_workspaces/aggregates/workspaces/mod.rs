@@ -1 +1,2 @@
+pub mod tag_query;
 pub mod workspace;
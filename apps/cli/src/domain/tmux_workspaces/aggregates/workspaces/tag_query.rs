@@ -0,0 +1,275 @@
+//! Boolean expressions over the tags of a workspace.
+//!
+//! A query combines tag patterns with `&&`, `||`, `!` and parentheses, for example
+//! `(backend || infra) && team:payments && !archived`. `!` binds tightest, then `&&`, then `||`.
+//! A pattern matches a tag by name and may use the glob wildcards `*`, `?` and `[...]`. Patterns can
+//! be written with a `tag:` prefix, so `tag:*api*` matches every tag that contains `api`.
+
+use std::{fmt, str::FromStr};
+
+use glob::Pattern;
+
+use super::workspace::WorkspaceTag;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagQuery {
+    /// Some tag matches the pattern
+    Tag(Pattern),
+    Not(Box<TagQuery>),
+    And(Box<TagQuery>, Box<TagQuery>),
+    Or(Box<TagQuery>, Box<TagQuery>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagQueryError {
+    pub query: String,
+    /// Character offset of the problem in the query
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TagQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid filter `{}`: {} at column {}",
+            self.query,
+            self.message,
+            self.position + 1
+        )
+    }
+}
+
+impl std::error::Error for TagQueryError {}
+
+impl TagQuery {
+    pub fn matches<T: AsRef<str>>(&self, tags: &[T]) -> bool {
+        match self {
+            TagQuery::Tag(pattern) => tags.iter().any(|tag| pattern.matches(tag.as_ref())),
+            TagQuery::Not(query) => !query.matches(tags),
+            TagQuery::And(left, right) => left.matches(tags) && right.matches(tags),
+            TagQuery::Or(left, right) => left.matches(tags) || right.matches(tags),
+        }
+    }
+}
+
+impl AsRef<str> for WorkspaceTag {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Term(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl FromStr for TagQuery {
+    type Err = TagQueryError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            query,
+            tokens: tokenize(query)?,
+            index: 0,
+        };
+        let result = parser.parse_or()?;
+        match parser.tokens.get(parser.index) {
+            None => Ok(result),
+            Some((position, Token::Close)) => Err(parser.error(*position, "unmatched `)`")),
+            Some((position, _)) => Err(parser.error(*position, "expected `&&` or `||`")),
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<(usize, Token)>, TagQueryError> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = vec![];
+    let mut index = 0;
+    while index < chars.len() {
+        let start = index;
+        let token = match chars[index] {
+            x if x.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '!' => Token::Not,
+            '&' | '|' => {
+                let operator = chars[index];
+                if chars.get(index + 1) != Some(&operator) {
+                    return Err(TagQueryError {
+                        query: query.to_string(),
+                        position: index,
+                        message: format!("expected `{}{}`", operator, operator),
+                    });
+                }
+                index += 1;
+                if operator == '&' {
+                    Token::And
+                } else {
+                    Token::Or
+                }
+            }
+            _ => {
+                while index < chars.len() && !is_special(chars[index]) {
+                    index += 1;
+                }
+                let term: String = chars[start..index].iter().collect();
+                tokens.push((start, Token::Term(term)));
+                continue;
+            }
+        };
+        tokens.push((start, token));
+        index += 1;
+    }
+    Ok(tokens)
+}
+
+fn is_special(x: char) -> bool {
+    x.is_whitespace() || matches!(x, '(' | ')' | '!' | '&' | '|')
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, position: usize, message: &str) -> TagQueryError {
+        TagQueryError {
+            query: self.query.to_string(),
+            position,
+            message: message.to_string(),
+        }
+    }
+
+    fn next_if(&mut self, token: &Token) -> bool {
+        let matches = self.tokens.get(self.index).map(|(_, x)| x) == Some(token);
+        if matches {
+            self.index += 1;
+        }
+        matches
+    }
+
+    fn parse_or(&mut self) -> Result<TagQuery, TagQueryError> {
+        let mut result = self.parse_and()?;
+        while self.next_if(&Token::Or) {
+            result = TagQuery::Or(Box::new(result), Box::new(self.parse_and()?));
+        }
+        Ok(result)
+    }
+
+    fn parse_and(&mut self) -> Result<TagQuery, TagQueryError> {
+        let mut result = self.parse_not()?;
+        while self.next_if(&Token::And) {
+            result = TagQuery::And(Box::new(result), Box::new(self.parse_not()?));
+        }
+        Ok(result)
+    }
+
+    fn parse_not(&mut self) -> Result<TagQuery, TagQueryError> {
+        if self.next_if(&Token::Not) {
+            return Ok(TagQuery::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<TagQuery, TagQueryError> {
+        let Some((position, token)) = self.tokens.get(self.index).cloned() else {
+            return Err(self.error(self.query.chars().count(), "expected a tag"));
+        };
+        self.index += 1;
+
+        match token {
+            Token::Open => {
+                let result = self.parse_or()?;
+                if !self.next_if(&Token::Close) {
+                    return Err(self.error(position, "unmatched `(`"));
+                }
+                Ok(result)
+            }
+            Token::Term(term) => {
+                let pattern = term.strip_prefix("tag:").unwrap_or(&term);
+                if pattern.is_empty() {
+                    return Err(self.error(position, "expected a tag after `tag:`"));
+                }
+                Pattern::new(pattern)
+                    .map(TagQuery::Tag)
+                    .map_err(|err| self.error(position, err.msg))
+            }
+            _ => Err(self.error(position, "expected a tag")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(pattern: &str) -> Box<TagQuery> {
+        Box::new(TagQuery::Tag(Pattern::new(pattern).unwrap()))
+    }
+
+    fn matches(query: &str, tags: &[&str]) -> bool {
+        query.parse::<TagQuery>().unwrap().matches(tags)
+    }
+
+    #[test]
+    fn should_parse_with_precedence() {
+        let query: TagQuery = "a || !b && c".parse().unwrap();
+
+        assert_eq!(
+            query,
+            TagQuery::Or(
+                tag("a"),
+                Box::new(TagQuery::And(Box::new(TagQuery::Not(tag("b"))), tag("c")))
+            )
+        );
+    }
+
+    #[test]
+    fn should_match_plain_tags_exactly() {
+        assert!(matches("rust", &["lua", "rust"]));
+        assert!(!matches("rust", &["rustacean"]));
+    }
+
+    #[test]
+    fn should_evaluate_expressions() {
+        let tags = ["backend", "team:payments"];
+
+        assert!(matches("(backend || infra) && team:payments", &tags));
+        assert!(matches("rust || !archived", &tags));
+        assert!(!matches("backend && !team:payments", &tags));
+    }
+
+    #[test]
+    fn should_match_globs() {
+        assert!(matches("tag:*api*", &["public-api-v2"]));
+        assert!(matches("team:*", &["team:payments"]));
+        assert!(!matches("tag:*api*", &["web"]));
+    }
+
+    #[test]
+    fn should_report_errors_with_position() {
+        let err = "rust && (lua".parse::<TagQuery>().unwrap_err();
+        assert_eq!(err.position, 8);
+        assert_eq!(err.message, "unmatched `(`");
+
+        let err = "rust & lua".parse::<TagQuery>().unwrap_err();
+        assert_eq!(err.message, "expected `&&`");
+
+        let err = "rust lua".parse::<TagQuery>().unwrap_err();
+        assert_eq!(err.position, 5);
+
+        assert!("".parse::<TagQuery>().is_err());
+        assert!("rust)".parse::<TagQuery>().is_err());
+    }
+}
@@ -1,9 +1,17 @@
 use serde::Serialize;
 use serde_json::json;
 
-use crate::{storage::worktree::WorkspaceWorktreeConfig, utils::display::RafaeltabDisplayItem};
+use crate::{
+    storage::worktree::WorkspaceWorktreeConfig,
+    utils::{
+        display::RafaeltabDisplayItem,
+        env::{EnvError, Environment, resolve_env},
+        interpolation::InterpolationContext,
+        path::expand_path,
+    },
+};
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Default, Serialize)]
 pub struct Workspace {
     /// A plaintext string that represents the unique identifier of this workspace
     pub id: String,
@@ -17,6 +25,25 @@ pub struct Workspace {
     pub importance: i32,
     /// Optional worktree configuration for this workspace
     pub worktree: Option<WorkspaceWorktreeConfig>,
+    /// Environment variables as configured, see [`Workspace::environment`]
+    pub env: Option<Environment>,
+    /// Dotenv file as configured, relative to the path
+    pub env_file: Option<String>,
+}
+
+impl Workspace {
+    /// The environment variables of this workspace, from its `envFile` and `env`.
+    ///
+    /// Values are interpolated with `context`, so worktrees can use `${worktree.branch}`.
+    pub fn environment(&self, context: &InterpolationContext) -> Result<Environment, EnvError> {
+        resolve_env(
+            self.env.as_ref(),
+            self.env_file.as_deref(),
+            &expand_path(&self.path),
+            context,
+            &format!("workspaces[id={}]", self.id),
+        )
+    }
 }
 
 #[derive(Clone, Serialize)]
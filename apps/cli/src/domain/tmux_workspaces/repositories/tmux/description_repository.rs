@@ -1,5 +1,25 @@
-use crate::domain::tmux_workspaces::aggregates::tmux::description::session::SessionDescription;
+use crate::{
+    domain::tmux_workspaces::aggregates::tmux::description::session::SessionDescription,
+    utils::env::EnvError,
+};
+
+/// A session whose windows or environment can not be resolved
+#[derive(Debug, Clone)]
+pub struct UnresolvedSession {
+    pub id: String,
+    pub name: String,
+    pub error: EnvError,
+}
 
 pub trait SessionDescriptionRepository {
-    fn get_session_descriptions(&self) -> Vec<SessionDescription>;
+    /// Every session, fails on the first session that can not be resolved
+    fn get_session_descriptions(&self) -> Result<Vec<SessionDescription>, EnvError> {
+        self.get_session_description_entries()
+            .into_iter()
+            .map(|x| x.map_err(|x| x.error))
+            .collect()
+    }
+    /// Every session on its own, for lists that skip or report the ones that can not be resolved
+    fn get_session_description_entries(&self)
+    -> Vec<Result<SessionDescription, UnresolvedSession>>;
 }
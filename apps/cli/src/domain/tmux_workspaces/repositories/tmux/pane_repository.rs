@@ -4,6 +4,7 @@ use crate::{
 };
 
 #[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
 pub enum SplitDirection {
     Horizontal,
     Vertical,
@@ -20,10 +21,72 @@ pub enum GetPanesTarget<'a> {
 pub trait TmuxPaneRepository {
     fn get_panes(&self, filter: Option<TmuxFilterNode>, target: GetPanesTarget) -> Vec<TmuxPane>;
     fn kill_pane(&self, pane: Option<&TmuxPane>);
-    fn split_window(
-        &self,
-        pane: Option<&TmuxPane>,
-        cwd: Option<&str>,
-        direction: SplitDirection,
-    ) -> TmuxPane;
+    /// Split a pane, the new pane is created without selecting it
+    fn split_window(&self, split: &SplitWindowBuilder) -> TmuxPane;
+    /// Make a pane the active pane of its window
+    fn select_pane(&self, pane: &TmuxPane);
+}
+
+#[allow(dead_code)]
+#[derive(Clone)]
+pub struct SplitWindowBuilder {
+    /// The pane to split, the current pane when omitted
+    pub target: Option<TmuxPane>,
+    pub direction: SplitDirection,
+    /// Size of the new pane, in cells or as a percentage such as `30%`
+    pub size: Option<String>,
+    pub dir: Option<String>,
+    pub environment: Vec<(String, String)>,
+    pub command: Option<String>,
+}
+
+#[allow(dead_code)]
+impl SplitWindowBuilder {
+    pub fn new(direction: SplitDirection) -> Self {
+        SplitWindowBuilder {
+            target: None,
+            direction,
+            size: None,
+            dir: None,
+            environment: vec![],
+            command: None,
+        }
+    }
+
+    pub fn with_target(&self, target: TmuxPane) -> Self {
+        SplitWindowBuilder {
+            target: Some(target),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_size(&self, size: impl Into<String>) -> Self {
+        SplitWindowBuilder {
+            size: Some(size.into()),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_dir(&self, dir: impl Into<String>) -> Self {
+        SplitWindowBuilder {
+            dir: Some(dir.into()),
+            ..self.clone()
+        }
+    }
+
+    pub fn add_env(&self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let mut environment = self.environment.clone();
+        environment.push((key.into(), value.into()));
+        SplitWindowBuilder {
+            environment,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_command(&self, command: impl Into<String>) -> Self {
+        SplitWindowBuilder {
+            command: Some(command.into()),
+            ..self.clone()
+        }
+    }
 }
@@ -9,6 +9,8 @@ use crate::{
 pub trait TmuxSessionRepository {
     fn new_session(&self, description: &SessionDescription) -> TmuxSession;
     fn kill_session(&self, session: Option<&TmuxSession>);
+    /// Give a running session a new name and `RAFAELTAB_SESSION_ID`
+    fn rename_session(&self, session: &TmuxSession, name: &str, session_id: &str);
     fn get_environment(&self, session_id: &str) -> String;
     fn get_sessions(
         &self,
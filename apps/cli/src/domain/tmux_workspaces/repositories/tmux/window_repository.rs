@@ -16,6 +16,10 @@ pub enum GetWindowsTarget<'a> {
 pub trait TmuxWindowRepository {
     fn new_window(&self, new_widow: &NewWindowBuilder) -> TmuxWindow;
     fn delete_window(&self, session: Option<&TmuxWindow>);
+    /// Make a window the current window of its session
+    fn select_window(&self, window: &TmuxWindow);
+    /// Arrange the panes of a window with a named layout or a layout string
+    fn select_layout(&self, window: &TmuxWindow, layout: &str) -> Result<(), String>;
     fn get_windows(
         &self,
         filter: Option<TmuxFilterNode>,
@@ -31,6 +35,8 @@ pub struct NewWindowBuilder {
     pub name: Option<String>,
     pub target: Option<TmuxSession>,
     pub command: Option<String>,
+    /// Create the window without making it the current window
+    pub background: bool,
 }
 
 #[allow(dead_code)]
@@ -42,6 +48,7 @@ impl NewWindowBuilder {
             name: None,
             target: None,
             command: None,
+            background: false,
         }
     }
 
@@ -52,6 +59,7 @@ impl NewWindowBuilder {
             name: self.name.clone(),
             target: self.target.clone(),
             command: self.command.clone(),
+            background: self.background,
         }
     }
 
@@ -64,6 +72,7 @@ impl NewWindowBuilder {
             name: self.name.clone(),
             target: self.target.clone(),
             command: self.command.clone(),
+            background: self.background,
         }
     }
 
@@ -74,6 +83,7 @@ impl NewWindowBuilder {
             name: Some(name.into()),
             target: self.target.clone(),
             command: self.command.clone(),
+            background: self.background,
         }
     }
 
@@ -84,6 +94,7 @@ impl NewWindowBuilder {
             name: self.name.clone(),
             target: Some(target),
             command: self.command.clone(),
+            background: self.background,
         }
     }
 
@@ -94,6 +105,18 @@ impl NewWindowBuilder {
             name: self.name.clone(),
             target: self.target.clone(),
             command: Some(cmd.into()),
+            background: self.background,
+        }
+    }
+
+    pub fn with_background(&self, background: bool) -> Self {
+        NewWindowBuilder {
+            dir: self.dir.clone(),
+            environment: self.environment.clone(),
+            name: self.name.clone(),
+            target: self.target.clone(),
+            command: self.command.clone(),
+            background,
         }
     }
 }
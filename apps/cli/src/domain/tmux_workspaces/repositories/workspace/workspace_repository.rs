@@ -1,12 +1,61 @@
-use crate::domain::tmux_workspaces::aggregates::workspaces::workspace::Workspace;
+use crate::{
+    domain::tmux_workspaces::aggregates::workspaces::workspace::Workspace,
+    storage::{error::StorageError, worktree::WorkspaceWorktreeConfig},
+    utils::interpolation::InterpolationError,
+};
+
+/// A workspace whose root can not be resolved, such as a root with a variable that is not set
+#[derive(Debug, Clone)]
+pub struct UnresolvedWorkspace {
+    pub id: String,
+    pub name: String,
+    pub error: InterpolationError,
+}
+
+/// Changes to apply to a workspace, fields that are `None` are left as they are
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceUpdate {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub root: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// `Some(None)` removes the worktree configuration
+    pub worktree: Option<Option<WorkspaceWorktreeConfig>>,
+}
 
 pub trait WorkspaceRepository {
-    fn get_workspaces(&self) -> Vec<Workspace>;
+    /// Every workspace, fails on the first workspace that can not be resolved
+    fn get_workspaces(&self) -> Result<Vec<Workspace>, InterpolationError> {
+        self.get_workspace_entries()
+            .into_iter()
+            .map(|x| x.map_err(|x| x.error))
+            .collect()
+    }
+    /// Every workspace on its own, for lists that skip or report the ones that can not be resolved
+    fn get_workspace_entries(&self) -> Vec<Result<Workspace, UnresolvedWorkspace>>;
+    /// The ids of every workspace, including the ones that can not be resolved
+    fn get_workspace_ids(&self) -> Vec<String> {
+        self.get_workspace_entries()
+            .into_iter()
+            .map(|x| match x {
+                Ok(workspace) => workspace.id,
+                Err(unresolved) => unresolved.id,
+            })
+            .collect()
+    }
     fn create_workspace(
         &self,
         name: String,
         tags: Vec<String>,
         root: String,
         id: String,
-    ) -> Workspace;
+    ) -> Result<Workspace, StorageError>;
+    /// Apply an update to the workspace with the given id, `None` when there is no such workspace
+    fn update_workspace(
+        &self,
+        id: &str,
+        update: WorkspaceUpdate,
+    ) -> Result<Option<Workspace>, StorageError>;
+    /// Remove the workspace with the given id, `None` when there is no such workspace
+    fn remove_workspace(&self, id: &str) -> Result<Option<Workspace>, StorageError>;
 }
@@ -2,7 +2,10 @@
 
 use std::path::{Path, PathBuf};
 
-use crate::storage::worktree::{WorkspaceWorktreeConfig, WorktreeConfig};
+use crate::{
+    storage::worktree::{WorkspaceWorktreeConfig, WorktreeConfig},
+    utils::interpolation::{InterpolationContext, InterpolationError, interpolate},
+};
 
 /// Merged worktree configuration from global and workspace-specific settings
 #[derive(Debug, Clone)]
@@ -82,12 +85,70 @@ impl MergedWorktreeConfig {
         }
     }
 
+    /// Merge the configs like [`Self::merge`], after expanding the variables in every entry.
+    ///
+    /// `workspace_id` is used to point errors in the workspace config at the right workspace.
+    pub fn merge_interpolated(
+        global: Option<&WorktreeConfig>,
+        workspace: Option<&WorkspaceWorktreeConfig>,
+        workspace_id: &str,
+        context: &InterpolationContext,
+    ) -> Result<Self, InterpolationError> {
+        let global = match global {
+            Some(config) => {
+                let mut config = config.clone();
+                interpolate_entries(&mut config.symlink_files, context, "worktree.symlinkFiles")?;
+                interpolate_entries(&mut config.on_create, context, "worktree.onCreate")?;
+                interpolate_entries(&mut config.on_destroy, context, "worktree.onDestroy")?;
+                Some(config)
+            }
+            None => None,
+        };
+        let workspace = match workspace {
+            Some(config) => {
+                let mut config = config.clone();
+                let location = format!("workspaces[id={}].worktree", workspace_id);
+                interpolate_entries(
+                    &mut config.symlink_files,
+                    context,
+                    &format!("{}.symlinkFiles", location),
+                )?;
+                interpolate_entries(
+                    &mut config.on_create,
+                    context,
+                    &format!("{}.onCreate", location),
+                )?;
+                interpolate_entries(
+                    &mut config.on_destroy,
+                    context,
+                    &format!("{}.onDestroy", location),
+                )?;
+                Some(config)
+            }
+            None => None,
+        };
+
+        Ok(Self::merge(global.as_ref(), workspace.as_ref()))
+    }
+
     /// Check if this config is empty (no symlink files, no onCreate, and no onDestroy commands)
     pub fn is_empty(&self) -> bool {
         self.symlink_files.is_empty() && self.on_create.is_empty() && self.on_destroy.is_empty()
     }
 }
 
+fn interpolate_entries(
+    entries: &mut [String],
+    context: &InterpolationContext,
+    location: &str,
+) -> Result<(), InterpolationError> {
+    for (index, entry) in entries.iter_mut().enumerate() {
+        *entry = interpolate(entry, context)
+            .map_err(|err| err.at(format!("{}[{}]", location, index)))?;
+    }
+    Ok(())
+}
+
 /// Calculate the worktree path for a given branch name.
 ///
 /// The worktree is created as a sibling of the main workspace root.
@@ -549,4 +610,44 @@ mod tests {
 
         assert_eq!(result, Some("devenv"));
     }
+
+    #[test]
+    fn test_merge_interpolated_expands_built_ins() {
+        let global = WorktreeConfig {
+            symlink_files: vec!["${workspace.root}/.env".to_string()],
+            on_create: vec!["echo ${worktree.branch}".to_string()],
+            on_destroy: vec![],
+            tmux: None,
+        };
+        let context = InterpolationContext::for_workspace("api", "/src/api")
+            .with_worktree("feat", "/src/api-feat");
+
+        let result =
+            MergedWorktreeConfig::merge_interpolated(Some(&global), None, "api", &context).unwrap();
+
+        assert_eq!(result.symlink_files, vec!["/src/api/.env"]);
+        assert_eq!(result.on_create, vec!["echo feat"]);
+    }
+
+    #[test]
+    fn test_merge_interpolated_reports_location() {
+        let workspace = WorkspaceWorktreeConfig {
+            symlink_files: vec![],
+            on_create: vec!["pnpm install".to_string(), "${workspace.name}".to_string()],
+            on_destroy: vec![],
+            tmux: None,
+        };
+
+        let result = MergedWorktreeConfig::merge_interpolated(
+            None,
+            Some(&workspace),
+            "api",
+            &InterpolationContext::default(),
+        );
+
+        assert_eq!(
+            result.unwrap_err().location.as_deref(),
+            Some("workspaces[id=api].worktree.onCreate[1]")
+        );
+    }
 }
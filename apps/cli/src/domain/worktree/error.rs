@@ -2,6 +2,8 @@
 
 use std::path::PathBuf;
 
+use crate::utils::{env::EnvError, interpolation::InterpolationError};
+
 /// Errors that can occur during worktree domain operations
 #[derive(Debug, Clone)]
 pub enum WorktreeError {
@@ -31,6 +33,10 @@ pub enum WorktreeError {
     GitError(String),
     /// User cancelled the operation
     UserCancelled,
+    /// A variable in the worktree configuration could not be resolved
+    Interpolation(InterpolationError),
+    /// The environment variables of the workspace could not be resolved
+    Environment(EnvError),
 }
 
 impl std::fmt::Display for WorktreeError {
@@ -91,12 +97,26 @@ impl std::fmt::Display for WorktreeError {
             WorktreeError::UserCancelled => {
                 write!(f, "Operation cancelled by user")
             }
+            WorktreeError::Interpolation(err) => write!(f, "{}", err),
+            WorktreeError::Environment(err) => write!(f, "{}", err),
         }
     }
 }
 
 impl std::error::Error for WorktreeError {}
 
+impl From<InterpolationError> for WorktreeError {
+    fn from(err: InterpolationError) -> Self {
+        WorktreeError::Interpolation(err)
+    }
+}
+
+impl From<EnvError> for WorktreeError {
+    fn from(err: EnvError) -> Self {
+        WorktreeError::Environment(err)
+    }
+}
+
 impl From<crate::infrastructure::git::GitError> for WorktreeError {
     fn from(err: crate::infrastructure::git::GitError) -> Self {
         WorktreeError::GitError(err.to_string())
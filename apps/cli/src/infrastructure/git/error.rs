@@ -17,6 +17,10 @@ pub enum GitError {
     WorktreeCreationFailed(String),
     /// Failed to remove a worktree
     WorktreeRemovalFailed(String),
+    /// Failed to clone a repository
+    CloneFailed(String),
+    /// Failed to create a repository
+    InitFailed(String),
     /// The worktree has uncommitted changes
     WorktreeHasUncommittedChanges(PathBuf),
     /// The worktree has unpushed commits
@@ -46,6 +50,12 @@ impl std::fmt::Display for GitError {
             GitError::WorktreeRemovalFailed(msg) => {
                 write!(f, "Failed to remove worktree: {}", msg)
             }
+            GitError::CloneFailed(msg) => {
+                write!(f, "Failed to clone repository: {}", msg)
+            }
+            GitError::InitFailed(msg) => {
+                write!(f, "Failed to create repository: {}", msg)
+            }
             GitError::WorktreeHasUncommittedChanges(path) => {
                 write!(f, "Worktree has uncommitted changes: {}", path.display())
             }
@@ -35,6 +35,36 @@ pub struct WorktreeInfo {
     pub is_main: bool,
 }
 
+/// Summary of the working tree and branch of a repository
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositoryStatus {
+    /// Branch that is checked out, `None` in detached HEAD state
+    pub branch: Option<String>,
+    /// Upstream of the branch, e.g. "origin/main"
+    pub upstream: Option<String>,
+    /// Commits on the branch that are not on the upstream
+    pub ahead: usize,
+    /// Commits on the upstream that are not on the branch
+    pub behind: usize,
+    /// Files with staged changes
+    pub staged: usize,
+    /// Files with changes that are not staged
+    pub modified: usize,
+    /// Files that are not tracked
+    pub untracked: usize,
+    /// Files with merge conflicts
+    pub conflicted: usize,
+    /// Entries in the stash
+    pub stashes: usize,
+}
+
+impl RepositoryStatus {
+    /// Whether there are uncommitted changes or untracked files
+    pub fn is_dirty(&self) -> bool {
+        self.staged + self.modified + self.untracked + self.conflicted > 0
+    }
+}
+
 /// Get the root worktree path (main worktree) from any path within a git repository.
 ///
 /// This works from both the main worktree and any linked worktrees.
@@ -251,6 +281,58 @@ pub fn create_worktree(
     }
 }
 
+/// Clone a repository.
+///
+/// # Arguments
+/// * `url` - Url or path of the repository to clone
+/// * `destination` - Path to clone into, parent directories are created
+///
+/// # Returns
+/// `Ok(())` on success, or an error describing what went wrong
+pub fn clone_repository(url: &str, destination: &Path) -> Result<(), GitError> {
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| GitError::IoError(e.to_string()))?;
+    }
+
+    let result = cmd!("git", "clone", "--quiet", "--", url, destination)
+        .stderr_to_stdout()
+        .stdout_capture()
+        .unchecked()
+        .run();
+
+    match result {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(GitError::CloneFailed(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        )),
+        Err(e) => Err(GitError::CloneFailed(e.to_string())),
+    }
+}
+
+/// Create a new repository.
+///
+/// # Arguments
+/// * `path` - Existing directory to create the repository in
+///
+/// # Returns
+/// `Ok(())` on success, or an error describing what went wrong
+pub fn init_repository(path: &Path) -> Result<(), GitError> {
+    let result = cmd!("git", "init", "--quiet")
+        .dir(path)
+        .stderr_to_stdout()
+        .stdout_capture()
+        .unchecked()
+        .run();
+
+    match result {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(GitError::InitFailed(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        )),
+        Err(e) => Err(GitError::InitFailed(e.to_string())),
+    }
+}
+
 /// Remove a git worktree.
 ///
 /// # Arguments
@@ -300,6 +382,25 @@ pub fn force_remove_worktree(worktree_path: &Path) -> Result<(), GitError> {
     }
 }
 
+/// Remove the administrative files of worktrees whose directory no longer exists.
+///
+/// # Arguments
+/// * `repo_path` - Path to any location within the git repository
+///
+/// # Returns
+/// `Ok(())` on success, or an error describing what went wrong
+pub fn prune_worktrees(repo_path: &Path) -> Result<(), GitError> {
+    let result = cmd!("git", "worktree", "prune")
+        .dir(repo_path)
+        .stderr_to_stdout()
+        .read();
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(GitError::WorktreeRemovalFailed(e.to_string())),
+    }
+}
+
 /// Check if the working directory is clean (no uncommitted changes).
 ///
 /// # Arguments
@@ -362,6 +463,30 @@ pub fn check_unpushed_commits(path: &Path) -> Result<bool, GitError> {
     }
 }
 
+/// Get the branch, ahead/behind counts, changed files and stash size of a repository.
+///
+/// # Arguments
+/// * `path` - Path within a git repository
+///
+/// # Returns
+/// The status of the repository, or an error if not in a repo
+pub fn get_repository_status(path: &Path) -> Result<RepositoryStatus, GitError> {
+    let output = cmd!("git", "status", "--porcelain=v2", "--branch")
+        .dir(path)
+        .stderr_null()
+        .read()
+        .map_err(|_| GitError::NotInGitRepo(path.to_path_buf()))?;
+
+    let mut status = parse_status_from_porcelain_v2(&output);
+    status.stashes = cmd!("git", "stash", "list")
+        .dir(path)
+        .stderr_null()
+        .read()
+        .map(|x| x.lines().count())
+        .unwrap_or(0);
+    Ok(status)
+}
+
 /// List all worktrees for a repository.
 ///
 /// # Arguments
@@ -417,6 +542,48 @@ pub fn is_worktree(path: &Path) -> bool {
     false
 }
 
+/// Find the git repositories in a directory tree.
+///
+/// Directories are searched up to `max_depth` levels below `dir`, the search does not descend
+/// into repositories or hidden directories. Linked worktrees and submodules are skipped.
+///
+/// # Arguments
+/// * `dir` - Directory to search
+/// * `max_depth` - How many levels below `dir` to search, 0 only checks `dir` itself
+///
+/// # Returns
+/// The paths of the main worktrees of the repositories, sorted by path
+pub fn find_repositories(dir: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut repositories = Vec::new();
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+
+    while let Some((path, depth)) = pending.pop() {
+        let git_path = path.join(".git");
+        if git_path.is_dir() {
+            repositories.push(path);
+            continue;
+        }
+        // Linked worktrees belong to a repository that is found on its own
+        if is_worktree(&path) || depth >= max_depth {
+            continue;
+        }
+
+        let Ok(entries) = std::fs::read_dir(&path) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            let is_dir = entry.file_type().map(|x| x.is_dir()).unwrap_or(false);
+            if is_dir && !hidden {
+                pending.push((entry.path(), depth + 1));
+            }
+        }
+    }
+
+    repositories.sort();
+    repositories
+}
+
 /// Remove empty parent directories up to (but not including) a stop path.
 ///
 /// # Arguments
@@ -457,6 +624,42 @@ pub fn remove_empty_parent_directories(path: &Path, stop_at: &Path) -> Result<()
     Ok(())
 }
 
+/// Parse `git status --porcelain=v2 --branch` output, the stash is not part of it.
+fn parse_status_from_porcelain_v2(output: &str) -> RepositoryStatus {
+    let mut status = RepositoryStatus::default();
+
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            status.branch = (head != "(detached)").then(|| head.to_string());
+        } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_string());
+        } else if let Some(counts) = line.strip_prefix("# branch.ab ") {
+            for count in counts.split_whitespace() {
+                if let Some(ahead) = count.strip_prefix('+') {
+                    status.ahead = ahead.parse().unwrap_or(0);
+                } else if let Some(behind) = count.strip_prefix('-') {
+                    status.behind = behind.parse().unwrap_or(0);
+                }
+            }
+        } else if line.starts_with("1 ") || line.starts_with("2 ") {
+            // The second field holds the staged and unstaged state, `.` means unchanged
+            let mut xy = line[2..].chars();
+            if xy.next().is_some_and(|x| x != '.') {
+                status.staged += 1;
+            }
+            if xy.next().is_some_and(|y| y != '.') {
+                status.modified += 1;
+            }
+        } else if line.starts_with("u ") {
+            status.conflicted += 1;
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        }
+    }
+
+    status
+}
+
 /// Parse worktree information from `git worktree list --porcelain` output.
 fn parse_worktrees_from_porcelain(output: &str) -> Vec<WorktreeInfo> {
     let mut worktrees = Vec::new();
@@ -735,6 +938,27 @@ mod tests {
             .ok();
     }
 
+    #[test]
+    fn test_prune_worktrees_forgets_deleted_worktrees() {
+        let temp_dir = create_temp_git_repo();
+        let worktree_path = temp_dir.1.parent().unwrap().join("deleted");
+        create_worktree(
+            &temp_dir.1,
+            "deleted",
+            &worktree_path,
+            &BranchLocation::None,
+            None,
+        )
+        .unwrap();
+        fs::remove_dir_all(&worktree_path).unwrap();
+        assert_eq!(list_worktrees(&temp_dir.1).unwrap().len(), 2);
+
+        let result = prune_worktrees(&temp_dir.1);
+
+        assert!(result.is_ok());
+        assert_eq!(list_worktrees(&temp_dir.1).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_create_worktree_fails_when_path_exists() {
         let temp_dir = create_temp_git_repo();
@@ -868,6 +1092,84 @@ mod tests {
         assert!(result.unwrap());
     }
 
+    #[test]
+    fn test_get_repository_status_counts_changes_and_stashes() {
+        let temp_dir = create_temp_git_repo();
+
+        fs::write(temp_dir.1.join("README.md"), "# Changed").unwrap();
+        Command::new("git")
+            .args(["stash"])
+            .current_dir(&temp_dir.1)
+            .output()
+            .expect("Failed to stash");
+        fs::write(temp_dir.1.join("README.md"), "# Changed again").unwrap();
+        fs::write(temp_dir.1.join("staged-file.txt"), "content").unwrap();
+        fs::write(temp_dir.1.join("untracked-file.txt"), "content").unwrap();
+        Command::new("git")
+            .args(["add", "staged-file.txt"])
+            .current_dir(&temp_dir.1)
+            .output()
+            .expect("Failed to stage file");
+
+        let result = get_repository_status(&temp_dir.1).unwrap();
+
+        assert!(result.branch.is_some());
+        assert_eq!(result.upstream, None);
+        assert_eq!(result.staged, 1);
+        assert_eq!(result.modified, 1);
+        assert_eq!(result.untracked, 1);
+        assert_eq!(result.stashes, 1);
+        assert!(result.is_dirty());
+    }
+
+    #[test]
+    fn test_get_repository_status_fails_when_not_in_git_repo() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let result = get_repository_status(temp_dir.path());
+
+        assert!(matches!(result, Err(GitError::NotInGitRepo(_))));
+    }
+
+    #[test]
+    fn test_parse_status_from_porcelain_v2() {
+        let output = "# branch.oid 0123456789abcdef\n\
+            # branch.head main\n\
+            # branch.upstream origin/main\n\
+            # branch.ab +2 -3\n\
+            1 M. N... 100644 100644 100644 0123 4567 staged.rs\n\
+            1 .M N... 100644 100644 100644 0123 4567 modified.rs\n\
+            2 RM N... 100644 100644 100644 0123 4567 R100 new.rs\told.rs\n\
+            u UU N... 100644 100644 100644 100644 0123 4567 89ab conflict.rs\n\
+            ? untracked.rs\n";
+
+        let result = parse_status_from_porcelain_v2(output);
+
+        assert_eq!(
+            result,
+            RepositoryStatus {
+                branch: Some("main".to_string()),
+                upstream: Some("origin/main".to_string()),
+                ahead: 2,
+                behind: 3,
+                staged: 2,
+                modified: 2,
+                untracked: 1,
+                conflicted: 1,
+                stashes: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_status_from_porcelain_v2_detached_head() {
+        let result =
+            parse_status_from_porcelain_v2("# branch.oid 0123\n# branch.head (detached)\n");
+
+        assert_eq!(result.branch, None);
+        assert!(!result.is_dirty());
+    }
+
     #[test]
     fn test_list_worktrees_returns_all_worktrees() {
         let temp_dir = create_temp_git_repo();
@@ -1110,4 +1412,33 @@ mod tests {
         assert!(!level2.exists());
         assert!(!level1.exists());
     }
+
+    #[test]
+    fn test_find_repositories_skips_worktrees_and_nested_repositories() {
+        let temp_dir = create_temp_git_repo();
+        let root = temp_dir.0.path();
+        let worktree_path = root.join("feature");
+        Command::new("git")
+            .args([
+                "worktree",
+                "add",
+                "-b",
+                "feature",
+                worktree_path.to_str().unwrap(),
+            ])
+            .current_dir(&temp_dir.1)
+            .output()
+            .expect("Failed to create worktree");
+        fs::create_dir_all(root.join("work/api/.git")).unwrap();
+        fs::create_dir_all(root.join("work/api/vendor/lib/.git")).unwrap();
+        fs::create_dir_all(root.join(".cache/hidden/.git")).unwrap();
+        fs::create_dir_all(root.join("a/b/c/deep/.git")).unwrap();
+
+        let result = find_repositories(root, 3);
+
+        assert_eq!(
+            result,
+            vec![root.join("mainworktree"), root.join("work/api")]
+        );
+    }
 }
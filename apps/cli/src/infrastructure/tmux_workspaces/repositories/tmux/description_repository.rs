@@ -11,13 +11,18 @@ use crate::{
         },
         repositories::{
             tmux::{
-                description_repository::SessionDescriptionRepository,
+                description_repository::{SessionDescriptionRepository, UnresolvedSession},
                 session_repository::TmuxSessionRepository,
             },
             workspace::workspace_repository::WorkspaceRepository,
         },
     },
-    storage::tmux::{Session, TmuxStorage},
+    storage::tmux::{PathSession, Session, TmuxStorage},
+    utils::{
+        env::{EnvError, resolve_env},
+        interpolation::{InterpolationContext, interpolate_variables},
+        path::expand_path,
+    },
 };
 
 pub struct ImplDescriptionRepository<
@@ -38,78 +43,72 @@ where
     TTmuxSessionRepository: TmuxSessionRepository,
     TTmuxStorage: TmuxStorage,
 {
-    fn get_session_descriptions(&self) -> Vec<SessionDescription> {
-        let workspaces = self.workspace_repository.get_workspaces();
-        let mut result: Vec<SessionDescription> = vec![];
-        let default_window_descriptions: Vec<WindowDescription> = self
-            .tmux_storage
-            .read()
-            .default_windows
-            .iter()
-            .map(|x| WindowDescription {
-                name: x.name.clone(),
-                command: x.command.clone(),
-            })
-            .collect();
-
-        let workspace_namespace = uuid!("dd66ca72-805f-4efb-85cc-f235a925d593");
-        let path_namespace = uuid!("3598273a-f7fe-4588-b5a4-fef0ed1ab31b");
-
-        for workspace in workspaces {
-            let id = Uuid::new_v5(&workspace_namespace, workspace.id.as_bytes());
-            result.push(SessionDescription {
-                id: id.to_string(),
-                name: workspace.name.clone(),
-                windows: default_window_descriptions.clone(),
-                kind: SessionKind::Workspace(workspace),
-                session: None,
+    fn get_session_description_entries(
+        &self,
+    ) -> Vec<Result<SessionDescription, UnresolvedSession>> {
+        let tmux = self.tmux_storage.read();
+        let sessions = tmux.sessions.unwrap_or_default();
+        let mut result: Vec<Result<SessionDescription, UnresolvedSession>> = vec![];
+
+        for entry in self.workspace_repository.get_workspace_entries() {
+            let workspace = match entry {
+                Ok(workspace) => workspace,
+                Err(unresolved) => {
+                    result.push(Err(UnresolvedSession {
+                        id: workspace_session_id(&unresolved.id),
+                        name: unresolved.name,
+                        error: unresolved.error.into(),
+                    }));
+                    continue;
+                }
+            };
+            // A session in the configuration replaces the default windows of its workspace, sessions
+            // for workspaces that no longer exist are ignored
+            let (windows, location) = sessions
+                .iter()
+                .rev()
+                .find_map(|session| match session {
+                    Session::Workspace(x) if x.workspace == workspace.id => Some((
+                        &x.windows,
+                        format!("tmux.sessions[workspace={}].windows", x.workspace),
+                    )),
+                    _ => None,
+                })
+                .unwrap_or((&tmux.default_windows, "tmux.defaultWindows".to_string()));
+            let id = workspace_session_id(&workspace.id);
+            let context =
+                InterpolationContext::for_workspace(&workspace.id, &expand_path(&workspace.path));
+            let resolved = WindowDescription::from_config(windows, &context, &location)
+                .map_err(EnvError::from)
+                .and_then(|windows| Ok((windows, workspace.environment(&context)?)));
+
+            result.push(match resolved {
+                Ok((windows, env)) => Ok(SessionDescription {
+                    id,
+                    name: workspace.name.clone(),
+                    windows,
+                    env,
+                    kind: SessionKind::Workspace(Box::new(workspace)),
+                    session: None,
+                }),
+                Err(error) => Err(UnresolvedSession {
+                    id,
+                    name: workspace.name,
+                    error,
+                }),
             });
         }
 
-        for session in self
-            .tmux_storage
-            .read()
-            .sessions
-            .clone()
-            .unwrap_or_default()
-        {
-            match session {
-                Session::Workspace(workspace) => {
-                    let windows: Vec<WindowDescription> = workspace
-                        .windows
-                        .iter()
-                        .map(|x| WindowDescription {
-                            name: x.name.clone(),
-                            command: x.command.clone(),
-                        })
-                        .collect();
-                    // We already added this to the list so we just need to replace the windows
-                    let res_workspace = result
-                        .iter_mut()
-                        .find(|x| match &x.kind {
-                            SessionKind::Path(_) => false,
-                            SessionKind::Workspace(w) => w.id == workspace.workspace,
-                        })
-                        .unwrap();
-                    res_workspace.windows = windows;
-                }
-                Session::Path(path) => {
-                    let id = Uuid::new_v5(&path_namespace, path.name.as_bytes());
-                    result.push(SessionDescription {
-                        id: id.to_string(),
-                        name: path.name,
-                        kind: SessionKind::Path(PathSessionDescription { path: path.path }),
-                        windows: path
-                            .windows
-                            .iter()
-                            .map(|x| WindowDescription {
-                                name: x.name.clone(),
-                                command: x.command.clone(),
-                            })
-                            .collect(),
-                        session: None,
-                    });
-                }
+        for session in sessions {
+            if let Session::Path(path) = session {
+                let id = path_session_id(&path.name);
+                result.push(
+                    describe_path_session(&path, &id).map_err(|error| UnresolvedSession {
+                        id,
+                        name: path.name.clone(),
+                        error,
+                    }),
+                );
             }
         }
 
@@ -120,12 +119,10 @@ where
 
         for session in sessions {
             let env = self.session_repository.get_environment(&session.id);
-            let id_opt = find_session_id(&env);
-            if id_opt.is_none() {
+            let Some(id) = find_session_id(&env) else {
                 continue;
-            }
-            let id = id_opt.unwrap();
-            let res_session = result.iter_mut().find(|x| x.id == id);
+            };
+            let res_session = result.iter_mut().flatten().find(|x| x.id == id);
             if let Some(sess) = res_session {
                 sess.session = Some(session)
             }
@@ -135,7 +132,52 @@ where
     }
 }
 
-fn find_session_id(input: &str) -> Option<String> {
+fn describe_path_session(path: &PathSession, id: &str) -> Result<SessionDescription, EnvError> {
+    let location = format!("tmux.sessions[name={}]", path.name);
+    let context = InterpolationContext::default();
+    let session_path = interpolate_variables(&path.path, &context)
+        .map_err(|err| err.at(format!("{}.path", location)))?;
+    let env = resolve_env(
+        path.env.as_ref(),
+        path.env_file.as_deref(),
+        &expand_path(&session_path),
+        &context,
+        &location,
+    )?;
+    Ok(SessionDescription {
+        id: id.to_string(),
+        windows: WindowDescription::from_config(
+            &path.windows,
+            &context,
+            &format!("{}.windows", location),
+        )?,
+        env,
+        name: path.name.clone(),
+        kind: SessionKind::Path(PathSessionDescription { path: session_path }),
+        session: None,
+    })
+}
+
+/// The id of the session of a path session from the configuration
+fn path_session_id(name: &str) -> String {
+    let path_namespace = uuid!("3598273a-f7fe-4588-b5a4-fef0ed1ab31b");
+    Uuid::new_v5(&path_namespace, name.as_bytes()).to_string()
+}
+
+/// The id of the session of a workspace, sessions keep it in `RAFAELTAB_SESSION_ID`
+pub fn workspace_session_id(workspace_id: &str) -> String {
+    let workspace_namespace = uuid!("dd66ca72-805f-4efb-85cc-f235a925d593");
+    Uuid::new_v5(&workspace_namespace, workspace_id.as_bytes()).to_string()
+}
+
+/// The id of the session of a worktree, worktree sessions are named `{workspace}-{branch}`
+pub fn worktree_session_id(session_name: &str) -> String {
+    let worktree_namespace = uuid!("f47ac10b-58cc-4372-a567-0e02b2c3d479");
+    Uuid::new_v5(&worktree_namespace, session_name.as_bytes()).to_string()
+}
+
+/// The value of `RAFAELTAB_SESSION_ID` in the environment of a session
+pub fn find_session_id(input: &str) -> Option<String> {
     // Define the target identifier
     let target = "RAFAELTAB_SESSION_ID=";
 
@@ -176,6 +218,7 @@ mod tests {
         },
         infrastructure::tmux_workspaces::repositories::workspace::workspace_repository::ImplWorkspaceRepository,
         storage::{
+            storage_interface::Storage,
             test::mocks::{MockTmuxStorage, MockWorkspaceStorage},
             tmux::{PathSession, Session, Tmux, TmuxStorage, Window, WorkspaceSession},
             workspace::{Workspace, WorkspaceStorage},
@@ -192,14 +235,14 @@ mod tests {
                     id: "home".to_string(),
                     root: "~".to_string(),
                     tags: Some(vec![]),
-                    worktree: None,
+                    ..Default::default()
                 },
                 Workspace {
                     name: "Source".to_string(),
                     id: "source".to_string(),
                     root: "~/source".to_string(),
                     tags: Some(vec![]),
-                    worktree: None,
+                    ..Default::default()
                 },
             ],
         }
@@ -213,14 +256,17 @@ mod tests {
                         windows: vec![Window {
                             name: "zsh".to_string(),
                             command: None,
+                            ..Default::default()
                         }],
                         path: "/usr/bin".to_string(),
                         name: "User binaries".to_string(),
+                        ..Default::default()
                     }),
                     Session::Workspace(WorkspaceSession {
                         windows: vec![Window {
                             name: "zsh".to_string(),
                             command: None,
+                            ..Default::default()
                         }],
                         workspace: "home".to_string(),
                         name: None,
@@ -230,10 +276,12 @@ mod tests {
                     Window {
                         name: "Neovim".to_string(),
                         command: Some("nvim".to_string()),
+                        ..Default::default()
                     },
                     Window {
                         name: "zsh".to_string(),
                         command: None,
+                        ..Default::default()
                     },
                 ],
             },
@@ -275,7 +323,7 @@ mod tests {
         let session_repository = session_repo_factory();
         let sut = sut_factory(&tmux_storage, &workspace_repo, &session_repository);
 
-        let result = sut.get_session_descriptions();
+        let result = sut.get_session_descriptions().unwrap();
 
         let workspace_sessions: Vec<&SessionDescription> = result
             .iter()
@@ -292,7 +340,7 @@ mod tests {
         let session_repository = session_repo_factory();
         let sut = sut_factory(&tmux_storage, &workspace_repo, &session_repository);
 
-        let result = sut.get_session_descriptions();
+        let result = sut.get_session_descriptions().unwrap();
 
         let binaries_session = result
             .iter()
@@ -309,7 +357,7 @@ mod tests {
         let session_repository = session_repo_factory();
         let sut = sut_factory(&tmux_storage, &workspace_repo, &session_repository);
 
-        let result = sut.get_session_descriptions();
+        let result = sut.get_session_descriptions().unwrap();
 
         let home_session = result.iter().find(|x| x.name == "Home").unwrap();
         assert_eq!(home_session.windows.len(), 1);
@@ -323,7 +371,7 @@ mod tests {
         let session_repository = session_repo_factory();
         let sut = sut_factory(&tmux_storage, &workspace_repo, &session_repository);
 
-        let result = sut.get_session_descriptions();
+        let result = sut.get_session_descriptions().unwrap();
 
         let source_session = result.iter().find(|x| x.name == "Source").unwrap();
         assert_eq!(source_session.windows.len(), 2);
@@ -337,13 +385,39 @@ mod tests {
         let session_repository = session_repo_factory();
         let sut = sut_factory(&tmux_storage, &workspace_repo, &session_repository);
 
-        let result = sut.get_session_descriptions();
+        let result = sut.get_session_descriptions().unwrap();
 
         let home_session: Vec<&SessionDescription> =
             result.iter().filter(|x| x.name == "Home").collect();
         assert_eq!(home_session.len(), 1);
     }
 
+    #[test]
+    fn should_report_sessions_that_can_not_be_resolved_on_their_own() {
+        let tmux_storage = tmux_storage_factory();
+        let mut workspace_storage = MockWorkspaceStorage {
+            data: workspace_storage_factory().read(),
+        };
+        workspace_storage.data[1].root = "${RAFAELTAB_UNSET_TEST_VARIABLE}/source".to_string();
+        let workspace_repo = workspace_repo_factory(&workspace_storage);
+        let session_repository = session_repo_factory();
+        let sut = sut_factory(&tmux_storage, &workspace_repo, &session_repository);
+
+        let result = sut.get_session_description_entries();
+
+        assert_eq!(result.len(), 3);
+        let unresolved = result[1].as_ref().err().unwrap();
+        assert_eq!(unresolved.name, "Source");
+        assert_eq!(unresolved.id, super::workspace_session_id("source"));
+        assert!(
+            unresolved
+                .error
+                .to_string()
+                .starts_with("workspaces[id=source].root")
+        );
+        assert!(sut.get_session_descriptions().is_err());
+    }
+
     struct MockSessionRepo {}
 
     impl TmuxSessionRepository for MockSessionRepo {
@@ -362,6 +436,14 @@ mod tests {
         ) {
         }
 
+        fn rename_session(
+            &self,
+            _session: &crate::domain::tmux_workspaces::aggregates::tmux::session::TmuxSession,
+            _name: &str,
+            _session_id: &str,
+        ) {
+        }
+
         fn get_environment(&self, _session_id: &str) -> String {
             "".to_string()
         }
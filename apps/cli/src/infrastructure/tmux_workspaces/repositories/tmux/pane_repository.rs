@@ -1,18 +1,15 @@
-use std::collections::HashSet;
-
 use serde::Deserialize;
 use serde_json::json;
 
 use crate::{
     domain::tmux_workspaces::{
-        aggregates::tmux::{pane::TmuxPane, window::WindowIncludeFields},
-        repositories::tmux::{
-            pane_repository::{GetPanesTarget, SplitDirection, TmuxPaneRepository},
-            window_repository::{GetWindowsTarget, TmuxWindowRepository},
+        aggregates::tmux::pane::TmuxPane,
+        repositories::tmux::pane_repository::{
+            GetPanesTarget, SplitDirection, SplitWindowBuilder, TmuxPaneRepository,
         },
     },
     infrastructure::tmux_workspaces::tmux::{
-        tmux_format::{TmuxFilterAstBuilder, TmuxFilterNode},
+        tmux_format::TmuxFilterNode,
         tmux_format_variables::{TmuxFormatField, TmuxFormatVariable},
     },
     storage::tmux::TmuxStorage,
@@ -25,13 +22,7 @@ where
     TTmuxStorage: TmuxStorage,
 {
     fn get_panes(&self, filter: Option<TmuxFilterNode>, target: GetPanesTarget) -> Vec<TmuxPane> {
-        let list_format = json!({
-            "id": TmuxFormatVariable::PaneId.to_format(),
-            "index": TmuxFormatVariable::PaneIndex.to_format(),
-            "title": TmuxFormatVariable::PaneTitle.to_format(),
-            "window_id": TmuxFormatVariable::WindowId.to_format(),
-        })
-        .to_string();
+        let list_format = pane_format();
         let mut args = vec!["list-panes", "-F", &list_format];
 
         let target_args = match target {
@@ -58,12 +49,7 @@ where
             .expect("Failed to get panes");
         res.lines()
             .map(|x| serde_json::from_str::<ListPaneResponse>(x).expect("Failed to get panes"))
-            .map(|x| TmuxPane {
-                id: x.id,
-                index: x.index,
-                title: x.title,
-                window_id: x.window_id,
-            })
+            .map(TmuxPane::from)
             .collect()
     }
 
@@ -79,18 +65,16 @@ where
             .expect("Failed to kill pane");
     }
 
-    fn split_window(
-        &self,
-        pane: Option<&TmuxPane>,
-        cwd: Option<&str>,
-        direction: SplitDirection,
-    ) -> TmuxPane {
-        let mut args = vec!["split-window"];
-        if let Some(cwd_path) = cwd {
-            args.extend(["-c", cwd_path]);
+    fn split_window(&self, split: &SplitWindowBuilder) -> TmuxPane {
+        let mut args = vec!["split-window", "-d"];
+        if let Some(target) = &split.target {
+            args.extend(["-t", &target.id]);
+        }
+        if let Some(dir) = &split.dir {
+            args.extend(["-c", dir]);
         }
 
-        match direction {
+        match split.direction {
             SplitDirection::Horizontal => {
                 args.push("-h");
             }
@@ -99,49 +83,61 @@ where
             }
         }
 
-        args.extend(["-l", "50%"]);
-        let old_panes: HashSet<String> = self
-            .get_current_panes(pane)
+        let size = split.size.clone().unwrap_or("50%".to_string());
+        args.extend(["-l", &size]);
+
+        let env: Vec<String> = split
+            .environment
             .iter()
-            .map(|x| x.id.clone())
+            .map(|(key, value)| format!("{}={}", key, value))
             .collect();
+        for val in &env {
+            args.extend(["-e", val]);
+        }
+
+        let format = pane_format();
+        args.extend(["-P", "-F", &format]);
+
+        let command_with_shell = split
+            .command
+            .as_ref()
+            .map(|command| format!("{}; exec $SHELL", command));
+        if let Some(ref command) = command_with_shell {
+            args.push(command);
+        }
 
-        let _ = self
+        let out = self
             .connection
             .cmd(args)
             .stderr_to_stdout()
             .read()
-            .expect("Failed to get panes");
+            .expect("Failed to split window");
+        serde_json::from_str::<ListPaneResponse>(&out)
+            .expect("Failed to parse pane response")
+            .into()
+    }
 
-        let new_panes = self.get_current_panes(pane);
-        new_panes
-            .iter()
-            .find(|x| !old_panes.contains(&x.id))
-            .unwrap()
-            .clone()
+    fn select_pane(&self, pane: &TmuxPane) {
+        self.connection
+            .cmd(["select-pane", "-t", &pane.id])
+            .stderr_to_stdout()
+            .read()
+            .expect("Failed to select pane");
     }
 }
 
-impl<TTmuxStorage> TmuxRepository<'_, TTmuxStorage>
-where
-    TTmuxStorage: TmuxStorage,
-{
-    fn get_current_panes(&self, pane: Option<&TmuxPane>) -> Vec<TmuxPane> {
-        match pane {
-            Some(pane_value) => {
-                let window_id = &pane_value.window_id;
-                let window = self.get_windows(
-                    Some(TmuxFilterAstBuilder::build(|b| {
-                        b.eq(b.var(TmuxFormatVariable::WindowId), b.const_val(window_id))
-                    })),
-                    WindowIncludeFields { panes: Some(()) },
-                    GetWindowsTarget::None,
-                );
-                window.first().unwrap().clone().panes.unwrap()
-            }
-            None => self.get_panes(None, GetPanesTarget::None),
-        }
-    }
+fn pane_format() -> String {
+    json!({
+        "id": TmuxFormatVariable::PaneId.to_format(),
+        "index": TmuxFormatVariable::PaneIndex.to_format(),
+        "title": TmuxFormatVariable::PaneTitle.to_format(),
+        "window_id": TmuxFormatVariable::WindowId.to_format(),
+        "pid": TmuxFormatVariable::PanePid.to_format(),
+        "current_path": TmuxFormatVariable::PaneCurrentPath.to_format(),
+        "current_command": TmuxFormatVariable::PaneCurrentCommand.to_format(),
+        "active": TmuxFormatVariable::PaneActive.to_format(),
+    })
+    .to_string()
 }
 
 #[derive(Deserialize)]
@@ -150,4 +146,23 @@ struct ListPaneResponse {
     index: String,
     title: String,
     window_id: String,
+    pid: String,
+    current_path: String,
+    current_command: String,
+    active: String,
+}
+
+impl From<ListPaneResponse> for TmuxPane {
+    fn from(value: ListPaneResponse) -> Self {
+        TmuxPane {
+            id: value.id,
+            index: value.index,
+            title: value.title,
+            window_id: value.window_id,
+            pid: value.pid,
+            current_path: value.current_path,
+            current_command: value.current_command,
+            active: value.active == "1",
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use serde::Deserialize;
 use serde_json::json;
 
@@ -5,13 +7,17 @@ use crate::{
     domain::tmux_workspaces::{
         aggregates::tmux::{
             description::{
+                pane::PaneDescription,
                 session::{SessionDescription, SessionKind},
                 window::WindowDescription,
             },
             include_fields_builder::IncludeFieldsBuilder,
+            pane::TmuxPane,
             session::{SessionIncludeFields, TmuxSession},
+            window::{TmuxWindow, WindowIncludeFields},
         },
         repositories::tmux::{
+            pane_repository::{SplitDirection, SplitWindowBuilder, TmuxPaneRepository},
             session_repository::TmuxSessionRepository,
             window_repository::{GetWindowsTarget, NewWindowBuilder, TmuxWindowRepository},
         },
@@ -20,7 +26,7 @@ use crate::{
         tmux_format::{TmuxFilterAstBuilder, TmuxFilterNode},
         tmux_format_variables::{TmuxFormatField, TmuxFormatVariable},
     },
-    storage::tmux::TmuxStorage,
+    storage::tmux::{PaneSplit, TmuxStorage},
     utils::path::expand_path,
 };
 
@@ -40,22 +46,37 @@ where
         };
         let id = &description.id;
 
-        let mut windows: Vec<NewWindowBuilder> = vec![];
+        let full_path = expand_path(path);
+        let mut windows: Vec<(NewWindowBuilder, &WindowDescription, String)> = vec![];
 
-        for window in description.windows.clone().iter().skip(1) {
-            let builder = NewWindowBuilder::new()
-                .with_dir(path.clone())
-                .with_name(window.name.clone());
-            windows.push(builder);
+        for window in description.windows.iter().skip(1) {
+            let window_dir = resolve_dir(&full_path, window.cwd.as_deref());
+            let mut builder = window.env.iter().fold(
+                NewWindowBuilder::new()
+                    .with_dir(resolve_dir(&window_dir, window.first_cwd()))
+                    .with_name(window.name.clone())
+                    .with_background(window.background),
+                |builder, (key, value)| builder.add_env(key, value),
+            );
+            if let Some(command) = window.first_command() {
+                builder = builder.with_command(command);
+            }
+            windows.push((builder, window, window_dir));
         }
         let default_description = WindowDescription {
             command: None,
             name: "zsh".to_string(),
+            env: Default::default(),
+            panes: None,
+            layout: None,
+            cwd: None,
+            background: false,
+            focus: false,
         };
         let first_window = description.windows.first().unwrap_or(&default_description);
-        let env = format!("{}={}", TMUX_SESSION_ID_KEY, id);
         let format = TmuxFormatVariable::SessionId.to_format();
-        let full_path = expand_path(path);
+        let first_window_dir = resolve_dir(&full_path, first_window.cwd.as_deref());
+        let first_dir = resolve_dir(&first_window_dir, first_window.first_cwd());
         let mut args = vec![
             "new-session",
             "-d",
@@ -63,16 +84,27 @@ where
             "-F",
             &format,
             "-c",
-            &full_path,
-            "-e",
-            &env,
+            &first_dir,
             "-n",
             &first_window.name,
             "-s",
             &name,
         ];
 
-        let first_command_with_shell = command_with_shell(first_window.command.clone());
+        // The first window is created together with the session, so its variables are set on the
+        // session and taken back once the session exists
+        let mut env = description.env.clone();
+        env.extend(first_window.env.clone());
+        env.insert(TMUX_SESSION_ID_KEY.to_string(), id.to_string());
+        let env: Vec<String> = env
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        for val in &env {
+            args.extend(["-e", val]);
+        }
+
+        let first_command_with_shell = command_with_shell(first_window.first_command());
 
         if let Some(ref command) = first_command_with_shell {
             args.push(command);
@@ -97,8 +129,43 @@ where
 
         let session = sessions.first().unwrap().clone();
 
-        for window in windows {
-            self.new_window(&window.with_target(session.clone()).with_dir(&full_path));
+        for key in first_window.env.keys() {
+            let args = match description.env.get(key) {
+                Some(value) => vec!["set-environment", "-t", &session.id, key, value],
+                None => vec!["set-environment", "-t", &session.id, "-u", key],
+            };
+            self.connection
+                .cmd(args)
+                .stderr_to_stdout()
+                .read()
+                .expect("Failed to reset session environment");
+        }
+
+        let mut focus = None;
+        if first_window.panes.is_some() || first_window.layout.is_some() || first_window.focus {
+            let created = self.get_windows(
+                None,
+                WindowIncludeFields { panes: Some(()) },
+                GetWindowsTarget::Session { id: &session.id },
+            );
+            if let Some(created) = created.first() {
+                self.build_panes(created, first_window, &first_window_dir);
+                if first_window.focus {
+                    focus = Some(created.clone());
+                }
+            }
+        }
+
+        for (builder, window, window_dir) in windows {
+            let created = self.new_window(&builder.with_target(session.clone()));
+            self.build_panes(&created, window, &window_dir);
+            if window.focus {
+                focus = Some(created);
+            }
+        }
+
+        if let Some(focus) = focus {
+            self.select_window(&focus);
         }
 
         session
@@ -116,6 +183,27 @@ where
             .expect("Failed to get sessions");
     }
 
+    fn rename_session(&self, session: &TmuxSession, name: &str, session_id: &str) {
+        self.connection
+            .cmd([
+                "set-environment",
+                "-t",
+                &session.id,
+                TMUX_SESSION_ID_KEY,
+                session_id,
+            ])
+            .stderr_to_stdout()
+            .read()
+            .expect("Failed to set the session id");
+        if session.name != name {
+            self.connection
+                .cmd(["rename-session", "-t", &session.id, name])
+                .stderr_to_stdout()
+                .read()
+                .expect("Failed to rename session");
+        }
+    }
+
     fn get_environment(&self, session_id: &str) -> String {
         self.connection
             .cmd(["show-environment", "-t", session_id])
@@ -188,6 +276,96 @@ where
     }
 }
 
+impl<TTmuxStorage> TmuxRepository<'_, TTmuxStorage>
+where
+    TTmuxStorage: TmuxStorage,
+{
+    /// Split a freshly created window into the panes of its description, then apply its layout
+    /// and focus the pane that asks for it
+    fn build_panes(&self, window: &TmuxWindow, description: &WindowDescription, window_dir: &str) {
+        if let (Some(root), Some(pane)) = (
+            &description.panes,
+            window.panes.as_ref().and_then(|x| x.first()),
+        ) {
+            let mut focus = None;
+            self.split_pane(pane, root, description, window_dir, &mut focus);
+            if let Some(focus) = focus {
+                self.select_pane(&focus);
+            }
+        }
+
+        if let Some(layout) = &description.layout
+            && let Err(err) = self.select_layout(window, layout)
+        {
+            eprintln!(
+                "Warning: could not apply layout '{}' to window '{}': {}",
+                layout, description.name, err
+            );
+        }
+    }
+
+    /// Split `pane` into the children of `description`, each split takes what is left for the
+    /// panes that follow, and the children are split in turn once they all exist
+    fn split_pane(
+        &self,
+        pane: &TmuxPane,
+        description: &PaneDescription,
+        window: &WindowDescription,
+        window_dir: &str,
+        focus: &mut Option<TmuxPane>,
+    ) {
+        if description.panes.is_empty() {
+            if description.focus {
+                *focus = Some(pane.clone());
+            }
+            return;
+        }
+
+        let direction = match description.split {
+            PaneSplit::Horizontal => SplitDirection::Horizontal,
+            PaneSplit::Vertical => SplitDirection::Vertical,
+        };
+        let mut created = vec![pane.clone()];
+        for (child, size) in description
+            .panes
+            .iter()
+            .skip(1)
+            .zip(description.split_sizes())
+        {
+            let leaf = child.first_leaf();
+            let mut split = window.env.iter().fold(
+                SplitWindowBuilder::new(direction)
+                    .with_target(created.last().unwrap().clone())
+                    .with_size(format!("{}%", size))
+                    .with_dir(resolve_dir(window_dir, leaf.cwd.as_deref())),
+                |builder, (key, value)| builder.add_env(key, value),
+            );
+            if let Some(command) = &leaf.command {
+                split = split.with_command(command);
+            }
+            created.push(self.split_window(&split));
+        }
+
+        for (child, pane) in description.panes.iter().zip(created.iter()) {
+            self.split_pane(pane, child, window, window_dir, focus);
+        }
+    }
+}
+
+/// The directory of a window or pane, relative directories start at `base`
+fn resolve_dir(base: &str, cwd: Option<&str>) -> String {
+    match cwd {
+        Some(cwd) => {
+            if cwd.starts_with('~') || Path::new(cwd).is_absolute() {
+                expand_path(cwd)
+            } else {
+                expand_path(&Path::new(base).join(cwd).to_string_lossy())
+            }
+        }
+        None => base.to_string(),
+    }
+}
+
 fn command_with_shell(cmd: Option<String>) -> Option<String> {
     cmd.map(|cmd_str| cmd_str + "; exec $SHELL")
 }
@@ -26,6 +26,9 @@ where
 {
     fn new_window(&self, new_window: &NewWindowBuilder) -> TmuxWindow {
         let mut args = vec!["new-window"];
+        if new_window.background {
+            args.push("-d");
+        }
         if let Some(dir_val) = &new_window.dir {
             args.extend(["-c", dir_val]);
         }
@@ -47,20 +50,15 @@ where
             args.extend(["-t", &target.id]);
         }
 
-        let window_command_with_shell = command_with_shell(new_window.command.clone());
+        let list_format = window_format();
+        args.extend(["-P", "-F", &list_format]);
 
+        // The command goes last, tmux takes everything after it as part of the command
+        let window_command_with_shell = command_with_shell(new_window.command.clone());
         if let Some(ref command) = window_command_with_shell {
             args.push(command);
         }
 
-        let list_format = json!({
-            "id": TmuxFormatVariable::WindowId.to_format(),
-            "index": TmuxFormatVariable::WindowIndex.to_format(),
-            "name": TmuxFormatVariable::WindowName.to_format(),
-            "session_id": TmuxFormatVariable::SessionId.to_format(),
-        })
-        .to_string();
-        args.extend(["-P", "-F", &list_format]);
         let out = self
             .connection
             .cmd(args)
@@ -85,6 +83,9 @@ where
             index: response.index,
             name: response.name,
             panes: Some(panes),
+            layout: response.layout,
+            active: response.active == "1",
+            activity: response.activity == "1",
             include_fields: WindowIncludeFields { panes: Some(()) },
         }
     }
@@ -101,19 +102,36 @@ where
             .expect("Failed to kill window");
     }
 
+    fn select_window(&self, window: &TmuxWindow) {
+        self.connection
+            .cmd(["select-window", "-t", &window.id])
+            .stderr_to_stdout()
+            .read()
+            .expect("Failed to select window");
+    }
+
+    fn select_layout(&self, window: &TmuxWindow, layout: &str) -> Result<(), String> {
+        let result = self
+            .connection
+            .cmd(["select-layout", "-t", &window.id, layout])
+            .stderr_to_stdout()
+            .unchecked()
+            .run()
+            .map_err(|err| err.to_string())?;
+        if result.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&result.stdout).trim().to_string())
+        }
+    }
+
     fn get_windows(
         &self,
         filter: Option<TmuxFilterNode>,
         include: WindowIncludeFields,
         target: GetWindowsTarget,
     ) -> Vec<TmuxWindow> {
-        let list_format = json!({
-            "id": TmuxFormatVariable::WindowId.to_format(),
-            "index": TmuxFormatVariable::WindowIndex.to_format(),
-            "name": TmuxFormatVariable::WindowName.to_format(),
-            "session_id": TmuxFormatVariable::SessionId.to_format(),
-        })
-        .to_string();
+        let list_format = window_format();
         let mut args = vec!["list-windows", "-F", &list_format];
 
         let target_filter = match target {
@@ -170,6 +188,9 @@ where
                                 .cloned()
                                 .collect(),
                         ),
+                        layout: x.layout.clone(),
+                        active: x.active == "1",
+                        activity: x.activity == "1",
                         include_fields: include.clone(),
                     })
                     .collect()
@@ -181,6 +202,9 @@ where
                     id: x.id.clone(),
                     index: x.index.clone(),
                     panes: None,
+                    layout: x.layout.clone(),
+                    active: x.active == "1",
+                    activity: x.activity == "1",
                     include_fields: include.clone(),
                 })
                 .collect(),
@@ -192,9 +216,25 @@ fn command_with_shell(cmd: Option<String>) -> Option<String> {
     cmd.map(|cmd_str| cmd_str + "; exec $SHELL")
 }
 
+fn window_format() -> String {
+    json!({
+        "id": TmuxFormatVariable::WindowId.to_format(),
+        "index": TmuxFormatVariable::WindowIndex.to_format(),
+        "name": TmuxFormatVariable::WindowName.to_format(),
+        "session_id": TmuxFormatVariable::SessionId.to_format(),
+        "layout": TmuxFormatVariable::WindowLayout.to_format(),
+        "active": TmuxFormatVariable::WindowActive.to_format(),
+        "activity": TmuxFormatVariable::WindowActivityFlag.to_format(),
+    })
+    .to_string()
+}
+
 #[derive(Deserialize)]
 struct ListWindowsResponse {
     name: String,
     id: String,
     index: String,
+    layout: String,
+    active: String,
+    activity: String,
 }
@@ -1,9 +1,11 @@
 use crate::{
     domain::tmux_workspaces::{
         aggregates::workspaces::workspace::{Workspace, WorkspaceTag},
-        repositories::workspace::workspace_repository::WorkspaceRepository,
+        repositories::workspace::workspace_repository::{
+            UnresolvedWorkspace, WorkspaceRepository, WorkspaceUpdate,
+        },
     },
-    storage::{self, workspace::WorkspaceStorage},
+    storage::{self, error::StorageError, workspace::WorkspaceStorage},
 };
 
 pub struct ImplWorkspaceRepository<'a, TWorkspaceStorage: WorkspaceStorage> {
@@ -14,27 +16,17 @@ impl<TWorkspaceStorage> WorkspaceRepository for ImplWorkspaceRepository<'_, TWor
 where
     TWorkspaceStorage: WorkspaceStorage,
 {
-    fn get_workspaces(&self) -> Vec<Workspace> {
+    fn get_workspace_entries(&self) -> Vec<Result<Workspace, UnresolvedWorkspace>> {
         self.workspace_storage
             .read()
             .iter()
-            .map(|workspace| Workspace {
-                id: workspace.id.clone(),
-                tags: workspace
-                    .tags
-                    .clone()
-                    .map(|x| {
-                        x.iter()
-                            .map(|tag| WorkspaceTag {
-                                name: tag.to_string(),
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default(),
-                name: workspace.name.clone(),
-                path: workspace.root.clone(),
-                importance: 0,
-                worktree: workspace.worktree.clone(),
+            .map(|workspace| match workspace.resolved_root() {
+                Ok(path) => Ok(map_workspace(workspace, path)),
+                Err(error) => Err(UnresolvedWorkspace {
+                    id: workspace.id.clone(),
+                    name: workspace.name.clone(),
+                    error,
+                }),
             })
             .collect()
     }
@@ -45,46 +37,106 @@ where
         tags: Vec<String>,
         root: String,
         id: String,
-    ) -> Workspace {
+    ) -> Result<Workspace, StorageError> {
         let workspace = storage::workspace::Workspace {
             id,
             name,
             tags: Some(tags),
             root,
-            worktree: None,
+            ..Default::default()
         };
 
         let mut workspaces = self.workspace_storage.read().clone();
         workspaces.push(workspace.clone());
-        self.workspace_storage.write(&workspaces).expect("");
-
-        Workspace {
-            id: workspace.id.clone(),
-            tags: workspace
-                .tags
-                .clone()
-                .map(|x| {
-                    x.iter()
-                        .map(|tag| WorkspaceTag {
-                            name: tag.to_string(),
-                        })
-                        .collect()
-                })
-                .unwrap_or_default(),
-            name: workspace.name.clone(),
-            path: workspace.root.clone(),
-            importance: 0,
-            worktree: workspace.worktree.clone(),
+        self.workspace_storage.write(&workspaces)?;
+
+        Ok(map_written_workspace(&workspace))
+    }
+
+    fn update_workspace(
+        &self,
+        id: &str,
+        update: WorkspaceUpdate,
+    ) -> Result<Option<Workspace>, StorageError> {
+        let mut workspaces = self.workspace_storage.read();
+        let Some(workspace) = workspaces.iter_mut().find(|x| x.id == id) else {
+            return Ok(None);
+        };
+
+        if let Some(id) = update.id {
+            workspace.id = id;
         }
+        if let Some(name) = update.name {
+            workspace.name = name;
+        }
+        if let Some(root) = update.root {
+            workspace.root = root;
+        }
+        if let Some(tags) = update.tags {
+            workspace.tags = Some(tags);
+        }
+        if let Some(worktree) = update.worktree {
+            workspace.worktree = worktree;
+        }
+        let updated = workspace.clone();
+
+        self.workspace_storage.write(&workspaces)?;
+        Ok(Some(map_written_workspace(&updated)))
+    }
+
+    fn remove_workspace(&self, id: &str) -> Result<Option<Workspace>, StorageError> {
+        let mut workspaces = self.workspace_storage.read();
+        let Some(index) = workspaces.iter().position(|x| x.id == id) else {
+            return Ok(None);
+        };
+        let removed = workspaces.remove(index);
+
+        self.workspace_storage.write(&workspaces)?;
+        Ok(Some(map_written_workspace(&removed)))
+    }
+}
+
+/// Map a workspace that was just written, keeping its root as written when it can not be resolved
+/// so the write itself is still reported
+fn map_written_workspace(workspace: &storage::workspace::Workspace) -> Workspace {
+    let path = workspace
+        .resolved_root()
+        .unwrap_or_else(|_| workspace.root.clone());
+    map_workspace(workspace, path)
+}
+
+fn map_workspace(workspace: &storage::workspace::Workspace, path: String) -> Workspace {
+    Workspace {
+        id: workspace.id.clone(),
+        tags: workspace
+            .tags
+            .clone()
+            .map(|x| {
+                x.iter()
+                    .map(|tag| WorkspaceTag {
+                        name: tag.to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        name: workspace.name.clone(),
+        path,
+        importance: workspace.importance.unwrap_or(0),
+        worktree: workspace.worktree.clone(),
+        env: workspace.env.clone(),
+        env_file: workspace.env_file.clone(),
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
-        domain::tmux_workspaces::repositories::workspace::workspace_repository::WorkspaceRepository,
+        domain::tmux_workspaces::repositories::workspace::workspace_repository::{
+            WorkspaceRepository, WorkspaceUpdate,
+        },
         storage::{
-            test::mocks::MockWorkspaceStorage,
+            storage_interface::Storage,
+            test::mocks::{InMemoryStorage, MockWorkspaceStorage},
             workspace::{Workspace, WorkspaceStorage},
             worktree::WorkspaceWorktreeConfig,
         },
@@ -99,15 +151,14 @@ mod test {
                     id: "workspace-1".to_string(),
                     root: "~".to_string(),
                     name: "Workspace 1".to_string(),
-                    tags: None,
-                    worktree: None,
+                    ..Default::default()
                 },
                 Workspace {
                     id: "workspace-2".to_string(),
                     root: "~/home".to_string(),
                     name: "Workspace 2".to_string(),
                     tags: Some(vec!["tag-1".to_string(), "tag-2".to_string()]),
-                    worktree: None,
+                    ..Default::default()
                 },
             ],
         }
@@ -121,7 +172,7 @@ mod test {
             workspace_storage: &mut workspace_storage,
         };
 
-        let result = sut.get_workspaces();
+        let result = sut.get_workspaces().unwrap();
 
         assert_eq!(result.len(), 2);
     }
@@ -134,7 +185,7 @@ mod test {
             workspace_storage: &mut workspace_storage,
         };
 
-        let result = sut.get_workspaces();
+        let result = sut.get_workspaces().unwrap();
 
         assert_eq!(result.first().unwrap().path, "~");
     }
@@ -147,7 +198,7 @@ mod test {
             workspace_storage: &mut workspace_storage,
         };
 
-        let result = sut.get_workspaces();
+        let result = sut.get_workspaces().unwrap();
 
         assert_eq!(result.first().unwrap().name, "Workspace 1");
         assert_eq!(result.first().unwrap().id, "workspace-1");
@@ -161,7 +212,7 @@ mod test {
             workspace_storage: &mut workspace_storage,
         };
 
-        let result = sut.get_workspaces();
+        let result = sut.get_workspaces().unwrap();
 
         assert_eq!(result.first().unwrap().tags.len(), 0);
     }
@@ -174,7 +225,7 @@ mod test {
             workspace_storage: &mut workspace_storage,
         };
 
-        let result = sut.get_workspaces();
+        let result = sut.get_workspaces().unwrap();
         let tag_result = &result.last().unwrap().tags;
 
         assert_eq!(tag_result.len(), 2);
@@ -196,8 +247,8 @@ mod test {
                 id: "workspace-with-config".to_string(),
                 root: "~/test".to_string(),
                 name: "Test Workspace".to_string(),
-                tags: None,
                 worktree: Some(worktree_config.clone()),
+                ..Default::default()
             }],
         };
 
@@ -205,7 +256,7 @@ mod test {
             workspace_storage: &workspace_storage,
         };
 
-        let result = sut.get_workspaces();
+        let result = sut.get_workspaces().unwrap();
         let workspace = result.first().unwrap();
 
         assert!(workspace.worktree.is_some());
@@ -225,9 +276,90 @@ mod test {
             workspace_storage: &mut workspace_storage,
         };
 
-        let result = sut.get_workspaces();
+        let result = sut.get_workspaces().unwrap();
 
         assert!(result.first().unwrap().worktree.is_none());
         assert!(result.last().unwrap().worktree.is_none());
     }
+
+    #[test]
+    fn should_report_workspaces_whose_root_can_not_be_resolved() {
+        let workspace_storage = InMemoryStorage::new(storage_factory().read());
+        let mut workspaces = workspace_storage.read();
+        workspaces[0].root = "${RAFAELTAB_UNSET_TEST_VARIABLE}/api".to_string();
+        workspace_storage.write(&workspaces).unwrap();
+        let sut = ImplWorkspaceRepository {
+            workspace_storage: &workspace_storage,
+        };
+
+        let result = sut.get_workspace_entries();
+
+        assert_eq!(result[0].as_ref().err().unwrap().id, "workspace-1");
+        assert!(result[1].is_ok());
+        assert!(sut.get_workspaces().is_err());
+        assert_eq!(sut.get_workspace_ids(), vec!["workspace-1", "workspace-2"]);
+    }
+
+    #[test]
+    fn should_update_only_given_fields() {
+        let workspace_storage = InMemoryStorage::new(storage_factory().read());
+        let sut = ImplWorkspaceRepository {
+            workspace_storage: &workspace_storage,
+        };
+
+        let result = sut
+            .update_workspace(
+                "workspace-2",
+                WorkspaceUpdate {
+                    name: Some("Renamed".to_string()),
+                    tags: Some(vec!["tag-3".to_string()]),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.name, "Renamed");
+        assert_eq!(result.path, "~/home");
+        let stored = workspace_storage.read();
+        assert_eq!(stored[1].name, "Renamed");
+        assert_eq!(stored[1].root, "~/home");
+        assert_eq!(stored[1].tags, Some(vec!["tag-3".to_string()]));
+    }
+
+    #[test]
+    fn should_return_resolved_root_of_updated_workspace() {
+        let workspace_storage = InMemoryStorage::new(storage_factory().read());
+        let sut = ImplWorkspaceRepository {
+            workspace_storage: &workspace_storage,
+        };
+
+        let result = sut
+            .update_workspace(
+                "workspace-1",
+                WorkspaceUpdate {
+                    root: Some("~/src/${workspace.id}".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.path, "~/src/workspace-1");
+        assert_eq!(workspace_storage.read()[0].root, "~/src/${workspace.id}");
+    }
+
+    #[test]
+    fn should_remove_workspace() {
+        let workspace_storage = InMemoryStorage::new(storage_factory().read());
+        let sut = ImplWorkspaceRepository {
+            workspace_storage: &workspace_storage,
+        };
+
+        let result = sut.remove_workspace("workspace-1").unwrap();
+
+        assert_eq!(result.unwrap().id, "workspace-1");
+        assert_eq!(workspace_storage.read().len(), 1);
+        assert!(sut.remove_workspace("workspace-1").unwrap().is_none());
+    }
 }
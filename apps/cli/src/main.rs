@@ -1,20 +1,48 @@
 // #![feature(coroutines, coroutine_trait)]
 // #![feature(stmt_expr_attributes)]
-use std::{io, rc::Rc};
+use std::{io, rc::Rc, thread};
 
-use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand};
 use commands::{
     command::RafaeltabCommand,
+    config::{
+        convert::{ConfigConvertCommand, ConfigConvertOptions},
+        get::{ConfigGetCommand, ConfigGetOptions},
+        migrate::{ConfigMigrateCommand, ConfigMigrateOptions},
+        schema::{ConfigSchemaCommand, ConfigSchemaOptions},
+        set::{ConfigSetCommand, ConfigSetOptions},
+        unset::{ConfigUnsetCommand, ConfigUnsetOptions},
+        validate::{ConfigValidateCommand, ConfigValidateOptions},
+        values::ConfigStorages,
+    },
+    doctor::{
+        DoctorCommand, DoctorContext, DoctorOptions, DoctorStorage, checks::default_registry,
+    },
     tmux::{
+        gc::{TmuxGcCommand, TmuxGcOptions},
         list::{TmuxListCommand, TmuxListOptions},
+        restore::{TmuxRestoreCommand, TmuxRestoreOptions},
+        save::{TmuxSaveCommand, TmuxSaveOptions},
         start::{TmuxStartCommand, TmuxStartOptions},
+        status::{
+            DEFAULT_STATUS_MAX_AGE, DEFAULT_STATUS_TEMPLATE, TmuxStatusCommand, TmuxStatusOptions,
+        },
+        stop::{StopTarget, TmuxStopCommand, TmuxStopOptions},
     },
     workspaces::{
         add::{WorkspaceAddCommand, WorkspaceAddOptions},
+        clone::{WorkspaceCloneCommand, WorkspaceCloneOptions},
         current::{CurrentWorkspaceOptions, get_current_workspace},
+        edit::{WorkspaceEditCommand, WorkspaceEditOptions},
+        exec::{WorkspaceExecOptions, workspace_exec},
         find::{FindWorkspaceOptions, find_workspace_cmd},
         find_tag::{FindTagWorkspaceOptions, find_tag_workspace},
         list::{ListWorkspacesCommand, ListWorkspacesCommandArgs},
+        new::{WorkspaceNewCommand, WorkspaceNewOptions},
+        remove::{WorkspaceRemoveCommand, WorkspaceRemoveOptions},
+        rename::{WorkspaceRenameCommand, WorkspaceRenameOptions},
+        scan::{WorkspaceScanCommand, WorkspaceScanOptions},
+        status::{WorkspaceStatusCommand, WorkspaceStatusOptions},
         tmux::{ListTmuxWorkspaceOptions, list_tmux_workspaces},
     },
     worktree::{
@@ -29,12 +57,24 @@ use infrastructure::tmux_workspaces::{
     },
     tmux::connection::TmuxConnection,
 };
-use storage::kinds::json_storage::JsonStorageProvider;
+use storage::kinds::file_storage::FileStorageProvider;
+use storage::kinds::snapshot_storage::JsonSnapshotStorage;
+use storage::kinds::state_storage::JsonStateStorage;
+use storage::kinds::status_cache_storage::JsonStatusCacheStorage;
 use utils::display::{JsonDisplay, JsonPrettyDisplay, PrettyDisplay, RafaeltabDisplay};
 
 use crate::{
     commands::tmux::switch::{TmuxSwitchCommand, TmuxSwitchOptions},
-    domain::tmux_workspaces::repositories::workspace::workspace_repository::WorkspaceRepository,
+    domain::tmux_workspaces::{
+        aggregates::workspaces::tag_query::TagQuery,
+        repositories::{
+            tmux::{
+                description_repository::SessionDescriptionRepository,
+                session_repository::TmuxSessionRepository,
+            },
+            workspace::workspace_repository::WorkspaceRepository,
+        },
+    },
 };
 
 #[allow(dead_code)]
@@ -53,7 +93,7 @@ mod utils;
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None, disable_help_subcommand(true))]
 struct Cli {
-    /// Path to configuration file (defaults to ~/.rafaeltab.json)
+    /// Path to configuration file (defaults to the layered configuration, see the README)
     #[arg(short, long, value_name = "FILE", global = true)]
     pub config: Option<String>,
 
@@ -65,6 +105,10 @@ struct Cli {
 enum Commands {
     /// Open the command palette
     CommandPalette(CommandPaletteArgs),
+    /// Manage configuration files
+    Config(ConfigArgs),
+    /// Check the environment and configuration for problems
+    Doctor(DoctorArgs),
     /// Run tmux sessions
     Tmux(TmuxArgs),
     /// Manage workspaces
@@ -73,6 +117,16 @@ enum Commands {
     Worktree(WorktreeArgs),
 }
 
+#[derive(Debug, Args)]
+struct DoctorArgs {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Apply the fixes that are safe to do automatically
+    #[arg(long)]
+    fix: bool,
+}
+
 #[derive(Debug, Args)]
 struct CommandPaletteArgs {
     #[command(subcommand)]
@@ -85,6 +139,86 @@ enum CommandPaletteCommands {
     Show,
 }
 
+#[derive(Debug, Args)]
+struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommands {
+    /// Convert a configuration file between JSON, TOML and YAML
+    Convert(ConfigConvertArgs),
+    /// Upgrade the configuration files to the current version
+    Migrate(ConfigMigrateArgs),
+    /// Print the JSON Schema of the configuration file
+    Schema,
+    /// Check the configuration files for problems
+    Validate(DisplayCommand),
+    /// Print a value from the configuration, such as `workspaces[id=api].tags`
+    Get(ConfigGetArgs),
+    /// Change a value in the configuration, such as `worktree.onCreate[+] "pnpm install"`
+    Set(ConfigSetArgs),
+    /// Remove a value from the configuration
+    Unset(ConfigUnsetArgs),
+}
+
+#[derive(Debug, Args)]
+struct ConfigConvertArgs {
+    /// The configuration file to convert
+    #[arg()]
+    input: String,
+
+    /// The file to write, the format is picked by its extension
+    #[arg()]
+    output: String,
+
+    /// Overwrite the output file if it exists
+    #[arg(short, long)]
+    force: bool,
+}
+
+#[derive(Debug, Args)]
+struct ConfigGetArgs {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Path of the value, prints the whole configuration when omitted
+    #[arg()]
+    path: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct ConfigSetArgs {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Path of the value, use `[+]` to append to a list and `[key=value]` to pick an item
+    #[arg()]
+    path: String,
+
+    /// The new value, parsed as JSON when possible and used as a string otherwise
+    #[arg()]
+    value: String,
+}
+
+#[derive(Debug, Args)]
+struct ConfigUnsetArgs {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Path of the value to remove
+    #[arg()]
+    path: String,
+}
+
+#[derive(Debug, Args)]
+struct ConfigMigrateArgs {
+    /// Show the changes without writing them
+    #[arg(long)]
+    dry_run: bool,
+}
+
 #[derive(Debug, Args)]
 struct TmuxArgs {
     #[command(subcommand)]
@@ -94,11 +228,21 @@ struct TmuxArgs {
 #[derive(Debug, Subcommand)]
 enum TmuxCommands {
     /// List all tmux sessions with descriptions
-    List(DisplayCommand),
-    /// Start a new tmux session interactively
-    Start,
+    List(TmuxListArgs),
+    /// Start the tmux sessions that are not running yet
+    Start(TmuxStartArgs),
+    /// Kill running tmux sessions and the sessions of their worktrees
+    Stop(TmuxStopArgs),
     /// Switch to a different tmux session
     Switch,
+    /// Save the windows and panes of every running session to a snapshot
+    Save(TmuxSnapshotArgs),
+    /// Recreate the sessions of a snapshot that are not running
+    Restore(TmuxSnapshotArgs),
+    /// Print the workspace, branch and activity of other sessions for the tmux status line
+    Status(TmuxStatusArgs),
+    /// Kill sessions whose directory, worktree or configuration no longer exists
+    Gc(TmuxGcArgs),
 }
 
 #[derive(Debug, Args)]
@@ -110,10 +254,10 @@ struct WorkspaceArgs {
 #[derive(Debug, Subcommand)]
 enum WorkspaceCommands {
     /// List all known workspaces
-    List(DisplayCommand),
+    List(WorkspaceListArgs),
     /// Get the current workspace
     Current(DisplayCommand),
-    /// Find a specific workspace using an id
+    /// Find a specific workspace by id, alias or name
     Find(FindCommand),
     /// Find workspaces that have a tag
     FindTag(FindTagCommand),
@@ -121,6 +265,25 @@ enum WorkspaceCommands {
     Tmux(DisplayCommand),
     /// Add a new workspace
     Add(AddCommand),
+    /// Remove a workspace
+    Remove(RemoveCommand),
+    /// Change the name of a workspace
+    ///
+    /// When `--reslug` changes the id, the running tmux session of the workspace is moved to the new
+    /// id, or killed with `--kill-sessions`. When interactive it asks which of the two to do.
+    Rename(RenameCommand),
+    /// Change the name, root, tags or worktree configuration of a workspace
+    Edit(EditCommand),
+    /// Find git repositories in a directory and add them as workspaces
+    Scan(ScanCommand),
+    /// Clone a repository and add it as a workspace
+    Clone(CloneCommand),
+    /// Create a project from a template and add it as a workspace
+    New(NewCommand),
+    /// Run a command in the root of every workspace
+    Exec(ExecCommand),
+    /// Show the branch, changes and tmux session of every workspace
+    Status(StatusCommand),
 }
 
 #[derive(Debug, Args)]
@@ -134,6 +297,91 @@ struct DisplayCommand {
     pub json_pretty: bool,
 }
 
+#[derive(Debug, Args)]
+struct FilterArgs {
+    /// Only include workspaces whose tags match a query, such as `rust && !archived`
+    #[arg(long)]
+    filter: Option<TagQuery>,
+}
+
+#[derive(Debug, Args)]
+struct TmuxListArgs {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    #[command(flatten)]
+    filter_args: FilterArgs,
+}
+
+#[derive(Debug, Args)]
+struct TmuxStartArgs {
+    #[command(flatten)]
+    filter_args: FilterArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("target").required(true)))]
+struct TmuxStopArgs {
+    /// Name of the session, or id of the workspace, to stop
+    #[arg(group = "target")]
+    name: Option<String>,
+
+    /// Stop every session from the configuration
+    #[arg(long, group = "target")]
+    all: bool,
+
+    /// Stop the sessions of workspaces whose tags match a query, such as `rust && !archived`
+    #[arg(long, group = "target")]
+    tag: Option<TagQuery>,
+
+    /// List the sessions that would be stopped without stopping them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+struct TmuxSnapshotArgs {
+    /// Name of the snapshot
+    #[arg(long, default_value = storage::snapshot::DEFAULT_SNAPSHOT)]
+    name: String,
+}
+
+#[derive(Debug, Args)]
+struct TmuxStatusArgs {
+    /// Text to print, `{workspace}`, `{branch}`, `{dirty}` and `{activity}` are replaced and tmux
+    /// style tags such as `#[fg=blue]` are kept
+    #[arg(long, default_value = DEFAULT_STATUS_TEMPLATE)]
+    template: String,
+
+    /// Directory to show the workspace of, such as `#{pane_current_path}`
+    #[arg(long)]
+    path: Option<String>,
+
+    /// Session to show the status of, such as `#{session_id}`, the session in `$TMUX` without it
+    #[arg(long)]
+    session: Option<String>,
+
+    /// Seconds the git state of a directory is reused for
+    #[arg(long, default_value_t = DEFAULT_STATUS_MAX_AGE)]
+    max_age: u64,
+}
+
+#[derive(Debug, Args)]
+struct TmuxGcArgs {
+    /// Kill every orphaned session without asking
+    #[arg(short = 'y', long)]
+    yes: bool,
+}
+
+#[derive(Debug, Args)]
+struct WorkspaceListArgs {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    #[command(flatten)]
+    filter_args: FilterArgs,
+}
+
 #[derive(Debug, Args)]
 struct AddCommand {
     #[command(flatten)]
@@ -157,13 +405,210 @@ struct AddCommand {
 }
 
 #[derive(Debug, Args)]
-struct FindCommand {
+struct RemoveCommand {
+    /// Id of the workspace to remove
+    #[arg()]
+    id: String,
+
+    /// Remove without asking, killing its tmux sessions and removing its tmux configuration
+    #[arg(short = 'y', long)]
+    yes: bool,
+
+    /// Keep its running tmux sessions and tmux configuration
+    #[arg(long)]
+    keep_sessions: bool,
+}
+
+#[derive(Debug, Args)]
+struct ExecCommand {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Only run in workspaces whose tags match a query, such as `rust && !archived`
+    #[arg(long)]
+    tag: Option<TagQuery>,
+
+    /// How many workspaces run the command at the same time
+    #[arg(long, default_value_t = 4)]
+    parallel: usize,
+
+    /// Stop starting the command in more workspaces once it failed in one
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// The command to run, a single argument is run by `sh -c`
+    #[arg(last = true, required = true)]
+    command: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct StatusCommand {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Only show workspaces whose tags match a query, such as `rust && !archived`
+    #[arg(long)]
+    tag: Option<TagQuery>,
+
+    /// Show the workspaces as a table
+    #[arg(long)]
+    table: bool,
+
+    /// How many workspaces are checked at the same time, defaults to the number of CPUs
+    #[arg(long)]
+    parallel: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct CloneCommand {
     #[command(flatten)]
     display_command: DisplayCommand,
 
-    /// Workspace identifier to search for
+    /// Url or path of the repository
+    #[arg()]
+    url: String,
+
+    /// Name of the workspace, defaults to the repository name
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Tags to add besides the ones inferred from the url
+    #[arg(long)]
+    tags: Option<Vec<String>>,
+
+    /// Directory to clone into, defaults to `clone.path` from the configuration
+    #[arg(long)]
+    path: Option<String>,
+
+    /// Run the worktree onCreate commands in the clone
+    #[arg(long)]
+    run_on_create: bool,
+
+    /// Start the tmux session of the workspace
+    #[arg(long)]
+    start: bool,
+}
+
+#[derive(Debug, Args)]
+struct NewCommand {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Name of the workspace
+    #[arg()]
+    name: String,
+
+    /// Name of the template from the `templates` configuration
+    #[arg(long)]
+    template: String,
+
+    /// Tags to add besides the ones of the template
+    #[arg(long)]
+    tags: Option<Vec<String>>,
+
+    /// Directory to create the project in, defaults to a directory named after the id
+    #[arg(long)]
+    path: Option<String>,
+
+    /// Start the tmux session of the workspace
+    #[arg(long)]
+    start: bool,
+}
+
+#[derive(Debug, Args)]
+struct ScanCommand {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Directory to search for git repositories
+    #[arg()]
+    dir: String,
+
+    /// How many directory levels to search
+    #[arg(long, default_value_t = 3)]
+    depth: usize,
+
+    /// Add every repository that was found without asking
+    #[arg(short = 'y', long)]
+    yes: bool,
+}
+
+#[derive(Debug, Args)]
+struct RenameCommand {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Id of the workspace to rename
     #[arg()]
     id: String,
+
+    /// The new name
+    #[arg()]
+    name: String,
+
+    /// Derive a new id from the new name instead of keeping the current id
+    #[arg(long)]
+    reslug: bool,
+
+    /// Kill the running tmux session when the id changes instead of moving it to the new id
+    #[arg(long, requires = "reslug")]
+    kill_sessions: bool,
+}
+
+#[derive(Debug, Args)]
+struct EditCommand {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Id of the workspace to edit
+    #[arg()]
+    id: String,
+
+    /// New name of the workspace
+    #[arg(long)]
+    name: Option<String>,
+
+    /// New path of the workspace directory
+    #[arg(long)]
+    root: Option<String>,
+
+    /// Replace all tags
+    #[arg(long, value_delimiter = ',')]
+    tags: Option<Vec<String>>,
+
+    /// Add a tag
+    #[arg(long)]
+    add_tag: Vec<String>,
+
+    /// Remove a tag
+    #[arg(long)]
+    remove_tag: Vec<String>,
+
+    /// Replace the worktree configuration, as JSON
+    #[arg(long)]
+    worktree: Option<String>,
+
+    /// Remove the worktree configuration
+    #[arg(long)]
+    clear_worktree: bool,
+
+    /// Prompt for the name, root and tags when nothing else is changed
+    #[arg(long)]
+    interactive: Option<bool>,
+}
+
+#[derive(Debug, Args)]
+struct FindCommand {
+    #[command(flatten)]
+    display_command: DisplayCommand,
+
+    /// Id, alias or name of the workspace, a prefix or a fuzzy match
+    #[arg()]
+    query: String,
+
+    /// Only accept the exact id or an alias of the workspace
+    #[arg(long)]
+    exact: bool,
 }
 
 #[derive(Debug, Args)]
@@ -171,9 +616,9 @@ struct FindTagCommand {
     #[command(flatten)]
     display_command: DisplayCommand,
 
-    /// Tag name to search for
+    /// Tag query, a tag name or an expression such as `(backend || infra) && !archived`
     #[arg()]
-    tag: String,
+    query: TagQuery,
 }
 
 #[derive(Debug, Args)]
@@ -232,11 +677,80 @@ struct WorktreeCompleteArgs {
     yes: bool,
 }
 
+/// The configuration files to use, exits when there are none
+fn config_provider(config: Option<String>) -> FileStorageProvider {
+    FileStorageProvider::new(config).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    })
+}
+
 fn main() -> Result<(), io::Error> {
     let cli = Cli::parse();
 
-    let storage_provider = JsonStorageProvider::new(cli.config)?;
+    // Most config commands operate on files directly and must work without a loadable config
+    if let Some(Commands::Config(config_args)) = &cli.command {
+        let handled = match &config_args.command {
+            ConfigCommands::Convert(args) => {
+                ConfigConvertCommand.execute(ConfigConvertOptions {
+                    input: args.input.clone(),
+                    output: args.output.clone(),
+                    force: args.force,
+                });
+                true
+            }
+            ConfigCommands::Migrate(args) => {
+                let storage_provider = config_provider(cli.config.clone());
+                ConfigMigrateCommand.execute(ConfigMigrateOptions {
+                    paths: storage_provider.paths(),
+                    dry_run: args.dry_run,
+                });
+                true
+            }
+            ConfigCommands::Schema => {
+                ConfigSchemaCommand.execute(ConfigSchemaOptions);
+                true
+            }
+            ConfigCommands::Validate(args) => {
+                let storage_provider = config_provider(cli.config.clone());
+                ConfigValidateCommand.execute(ConfigValidateOptions {
+                    paths: storage_provider.paths(),
+                    json: args.json,
+                    display: &*create_display(args),
+                });
+                true
+            }
+            ConfigCommands::Get(_) | ConfigCommands::Set(_) | ConfigCommands::Unset(_) => false,
+        };
+        if handled {
+            return Ok(());
+        }
+    }
+
+    // Doctor reports a configuration that can not be loaded instead of failing on it
+    if let Some(Commands::Doctor(args)) = &cli.command {
+        let storage_provider = config_provider(cli.config.clone());
+        let paths = storage_provider.paths();
+        let storage = storage_provider.load().ok();
+        DoctorCommand.execute(DoctorOptions {
+            registry: &default_registry(),
+            context: DoctorContext {
+                config_paths: &paths,
+                storage: storage.as_ref().map(|storage| DoctorStorage {
+                    workspace_storage: storage,
+                    tmux_storage: storage,
+                    worktree_storage: storage,
+                }),
+                fix: args.fix,
+            },
+            display: &*create_display(&args.display_command),
+        });
+        return Ok(());
+    }
+
+    let storage_provider = config_provider(cli.config);
     let storage = storage_provider.load()?;
+    let state_storage = JsonStateStorage::new();
 
     // Support test isolation via environment variable
     let tmux_connection = match std::env::var("RAFAELTAB_TMUX_SOCKET") {
@@ -247,7 +761,8 @@ fn main() -> Result<(), io::Error> {
     match &cli.command {
         Some(Commands::Tmux(tmux_args)) => match &tmux_args.command {
             TmuxCommands::List(args) => TmuxListCommand.execute(TmuxListOptions {
-                display: &*create_display(args),
+                display: &*create_display(&args.display_command),
+                filter: args.filter_args.filter.as_ref(),
                 session_description_repository: &ImplDescriptionRepository {
                     workspace_repository: &ImplWorkspaceRepository {
                         workspace_storage: &storage,
@@ -259,7 +774,7 @@ fn main() -> Result<(), io::Error> {
                     tmux_storage: &storage,
                 },
             }),
-            TmuxCommands::Start => {
+            TmuxCommands::Start(args) => {
                 let session_repository = &TmuxRepository {
                     tmux_storage: &storage,
                     connection: &tmux_connection,
@@ -274,6 +789,7 @@ fn main() -> Result<(), io::Error> {
                     },
                     session_repository,
                     tmux_storage: &storage,
+                    filter: args.filter_args.filter.as_ref(),
                 })
             }
             TmuxCommands::Switch => {
@@ -292,6 +808,99 @@ fn main() -> Result<(), io::Error> {
                     session_repository: tmux_repository,
                     client_repository: tmux_repository,
                     tmux_storage: &storage,
+                    frecency_storage: &state_storage,
+                })
+            }
+            TmuxCommands::Stop(args) => {
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                let target = match (&args.name, &args.tag) {
+                    (Some(name), _) => StopTarget::Name(name),
+                    (_, Some(query)) => StopTarget::Tag(query),
+                    _ => StopTarget::All,
+                };
+                TmuxStopCommand.execute(TmuxStopOptions {
+                    session_description_repository: &ImplDescriptionRepository {
+                        workspace_repository: &ImplWorkspaceRepository {
+                            workspace_storage: &storage,
+                        },
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                    target,
+                    dry_run: args.dry_run,
+                })
+            }
+            TmuxCommands::Save(args) => {
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                TmuxSaveCommand.execute(TmuxSaveOptions {
+                    session_description_repository: &ImplDescriptionRepository {
+                        workspace_repository: &ImplWorkspaceRepository {
+                            workspace_storage: &storage,
+                        },
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                    snapshot_storage: &JsonSnapshotStorage::new(),
+                    name: &args.name,
+                })
+            }
+            TmuxCommands::Restore(args) => {
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                TmuxRestoreCommand.execute(TmuxRestoreOptions {
+                    session_description_repository: &ImplDescriptionRepository {
+                        workspace_repository: &ImplWorkspaceRepository {
+                            workspace_storage: &storage,
+                        },
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                    snapshot_storage: &JsonSnapshotStorage::new(),
+                    name: &args.name,
+                })
+            }
+            TmuxCommands::Status(args) => TmuxStatusCommand.execute(TmuxStatusOptions {
+                workspace_repository: &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                },
+                session_repository: &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                },
+                status_cache_storage: &JsonStatusCacheStorage::new(),
+                template: &args.template,
+                path: args.path.as_deref(),
+                session: args.session.as_deref(),
+                max_age: args.max_age,
+            }),
+            TmuxCommands::Gc(args) => {
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                let workspace_repository = &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                };
+                TmuxGcCommand.execute(TmuxGcOptions {
+                    session_description_repository: &ImplDescriptionRepository {
+                        workspace_repository,
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                    workspace_repository,
+                    yes: args.yes,
                 })
             }
         },
@@ -299,7 +908,9 @@ fn main() -> Result<(), io::Error> {
             WorkspaceCommands::List(args) => {
                 ListWorkspacesCommand.execute(ListWorkspacesCommandArgs {
                     workspace_storage: &storage,
-                    display: &*create_display(args),
+                    frecency_storage: &state_storage,
+                    filter: args.filter_args.filter.as_ref(),
+                    display: &*create_display(&args.display_command),
                 })
             }
             WorkspaceCommands::Current(args) => get_current_workspace(
@@ -310,14 +921,15 @@ fn main() -> Result<(), io::Error> {
             ),
             WorkspaceCommands::Find(args) => find_workspace_cmd(
                 &storage,
-                &args.id,
+                &args.query,
                 FindWorkspaceOptions {
                     display: &*create_display(&args.display_command),
+                    exact: args.exact,
                 },
             ),
             WorkspaceCommands::FindTag(args) => find_tag_workspace(
                 &storage,
-                &args.tag,
+                &args.query,
                 FindTagWorkspaceOptions {
                     display: &*create_display(&args.display_command),
                 },
@@ -341,6 +953,162 @@ fn main() -> Result<(), io::Error> {
                     path: args.path.clone(),
                 })
             }
+            WorkspaceCommands::Remove(args) => {
+                let workspace_repository = &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                };
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                WorkspaceRemoveCommand.execute(WorkspaceRemoveOptions {
+                    id: args.id.clone(),
+                    yes: args.yes,
+                    keep_sessions: args.keep_sessions,
+                    workspace_repository,
+                    config_storage: &storage,
+                    description_repository: &ImplDescriptionRepository {
+                        workspace_repository,
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                })
+            }
+            WorkspaceCommands::Rename(args) => {
+                let workspace_repository = &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                };
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                WorkspaceRenameCommand.execute(WorkspaceRenameOptions {
+                    id: args.id.clone(),
+                    name: args.name.clone(),
+                    reslug: args.reslug,
+                    kill_sessions: args.kill_sessions,
+                    workspace_repository,
+                    config_storage: &storage,
+                    description_repository: &ImplDescriptionRepository {
+                        workspace_repository,
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                    display: &*create_display(&args.display_command),
+                })
+            }
+            WorkspaceCommands::Edit(args) => WorkspaceEditCommand.execute(WorkspaceEditOptions {
+                id: args.id.clone(),
+                name: args.name.clone(),
+                root: args.root.clone(),
+                tags: args.tags.clone(),
+                add_tags: args.add_tag.clone(),
+                remove_tags: args.remove_tag.clone(),
+                worktree: args.worktree.clone(),
+                clear_worktree: args.clear_worktree,
+                interactive: args.interactive,
+                workspace_repository: &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                },
+                display: &*create_display(&args.display_command),
+            }),
+            WorkspaceCommands::Exec(args) => workspace_exec(
+                &storage,
+                WorkspaceExecOptions {
+                    command: args.command.clone(),
+                    filter: args.tag.as_ref(),
+                    parallel: args.parallel,
+                    fail_fast: args.fail_fast,
+                    print_output: !(args.display_command.json || args.display_command.json_pretty),
+                    display: &*create_display(&args.display_command),
+                },
+            ),
+            WorkspaceCommands::Status(args) => {
+                let workspace_repository = &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                };
+                WorkspaceStatusCommand.execute(WorkspaceStatusOptions {
+                    filter: args.tag.as_ref(),
+                    table: args.table,
+                    parallel: args
+                        .parallel
+                        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |x| x.get())),
+                    workspace_repository,
+                    session_description_repository: &ImplDescriptionRepository {
+                        workspace_repository,
+                        session_repository: &TmuxRepository {
+                            tmux_storage: &storage,
+                            connection: &tmux_connection,
+                        },
+                        tmux_storage: &storage,
+                    },
+                    display: &*create_display(&args.display_command),
+                })
+            }
+            WorkspaceCommands::Clone(args) => {
+                let workspace_repository = &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                };
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                WorkspaceCloneCommand.execute(WorkspaceCloneOptions {
+                    url: args.url.clone(),
+                    name: args.name.clone(),
+                    tags: args.tags.clone().unwrap_or_default(),
+                    path: args.path.clone(),
+                    run_on_create: args.run_on_create,
+                    start: args.start,
+                    workspace_repository,
+                    clone_storage: &storage,
+                    worktree_storage: &storage,
+                    description_repository: &ImplDescriptionRepository {
+                        workspace_repository,
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                    display: &*create_display(&args.display_command),
+                })
+            }
+            WorkspaceCommands::New(args) => {
+                let workspace_repository = &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                };
+                let session_repository = &TmuxRepository {
+                    tmux_storage: &storage,
+                    connection: &tmux_connection,
+                };
+                WorkspaceNewCommand.execute(WorkspaceNewOptions {
+                    name: args.name.clone(),
+                    template: args.template.clone(),
+                    tags: args.tags.clone().unwrap_or_default(),
+                    path: args.path.clone(),
+                    start: args.start,
+                    workspace_repository,
+                    template_storage: &storage,
+                    tmux_storage: &storage,
+                    description_repository: &ImplDescriptionRepository {
+                        workspace_repository,
+                        session_repository,
+                        tmux_storage: &storage,
+                    },
+                    session_repository,
+                    display: &*create_display(&args.display_command),
+                })
+            }
+            WorkspaceCommands::Scan(args) => WorkspaceScanCommand.execute(WorkspaceScanOptions {
+                dir: args.dir.clone(),
+                depth: args.depth,
+                yes: args.yes,
+                workspace_repository: &ImplWorkspaceRepository {
+                    workspace_storage: &storage,
+                },
+                display: &*create_display(&args.display_command),
+            }),
         },
         Some(Commands::Worktree(worktree_args)) => {
             let tmux_repository = &TmuxRepository {
@@ -394,7 +1162,12 @@ fn main() -> Result<(), io::Error> {
         Some(Commands::CommandPalette(palette_args)) => {
             use crate::commands::{
                 CommandPalette, TestConfirmCommand, TestPickerCommand, TestTextInputCommand,
-                TestTextInputSuggestionsCommand, builtin::AddWorkspaceCommand,
+                TestTextInputSuggestionsCommand,
+                builtin::{
+                    AddWorkspaceCommand, CloneWorkspaceCommand, EditWorkspaceCommand,
+                    NewWorkspaceCommand, RemoveWorkspaceCommand, RenameWorkspaceCommand,
+                    StopSessionCommand,
+                },
                 registry::CommandRegistry,
             };
 
@@ -403,6 +1176,12 @@ fn main() -> Result<(), io::Error> {
 
             // Register normal commands
             registry.register(AddWorkspaceCommand::new());
+            registry.register(CloneWorkspaceCommand::new());
+            registry.register(NewWorkspaceCommand::new());
+            registry.register(RenameWorkspaceCommand::new());
+            registry.register(EditWorkspaceCommand::new());
+            registry.register(RemoveWorkspaceCommand::new());
+            registry.register(StopSessionCommand::new());
 
             // Register test commands only in TEST_MODE
             if std::env::var("TEST_MODE").is_ok() {
@@ -420,10 +1199,31 @@ fn main() -> Result<(), io::Error> {
                 CommandPaletteCommands::Show => {
                     // TODO move to using DI so we don't have to do this guly magic
                     let storage_leaked = Box::leak(Box::new(storage));
+                    let connection_leaked = Box::leak(Box::new(tmux_connection));
+                    let state_storage_leaked = Box::leak(Box::new(state_storage));
+                    let workspace_repository_leaked =
+                        Box::leak(Box::new(ImplWorkspaceRepository {
+                            workspace_storage: storage_leaked,
+                        }));
+                    let session_repository_leaked = Box::leak(Box::new(TmuxRepository {
+                        tmux_storage: storage_leaked,
+                        connection: connection_leaked,
+                    }));
                     let workspace_repository: Rc<dyn WorkspaceRepository> =
                         Rc::new(ImplWorkspaceRepository {
                             workspace_storage: storage_leaked,
                         });
+                    let description_repository: Rc<dyn SessionDescriptionRepository> =
+                        Rc::new(ImplDescriptionRepository {
+                            workspace_repository: workspace_repository_leaked,
+                            session_repository: session_repository_leaked,
+                            tmux_storage: storage_leaked,
+                        });
+                    let session_repository: Rc<dyn TmuxSessionRepository> =
+                        Rc::new(TmuxRepository {
+                            tmux_storage: storage_leaked,
+                            connection: connection_leaked,
+                        });
 
                     // Run the command palette
                     if palette.registry().is_empty() {
@@ -431,13 +1231,44 @@ fn main() -> Result<(), io::Error> {
                     } else {
                         // Create command context and run
                         use crate::commands::Command;
-                        let mut ctx = crate::commands::CommandCtx::new(workspace_repository)
-                            .expect("Failed to create command context");
+                        let mut ctx = crate::commands::CommandCtx::new(
+                            workspace_repository,
+                            storage_leaked,
+                            description_repository,
+                            session_repository,
+                            state_storage_leaked,
+                        )
+                        .expect("Failed to create command context");
                         palette.run(&mut ctx);
                     }
                 }
             }
         }
+        Some(Commands::Config(config_args)) => {
+            let storages = ConfigStorages {
+                config_storage: &storage,
+            };
+            match &config_args.command {
+                ConfigCommands::Get(args) => ConfigGetCommand.execute(ConfigGetOptions {
+                    path: args.path.clone(),
+                    storages,
+                    display: &*create_display(&args.display_command),
+                }),
+                ConfigCommands::Set(args) => ConfigSetCommand.execute(ConfigSetOptions {
+                    path: args.path.clone(),
+                    value: args.value.clone(),
+                    storages,
+                    display: &*create_display(&args.display_command),
+                }),
+                ConfigCommands::Unset(args) => ConfigUnsetCommand.execute(ConfigUnsetOptions {
+                    path: args.path.clone(),
+                    storages,
+                    display: &*create_display(&args.display_command),
+                }),
+                _ => unreachable!("other config commands are handled before loading"),
+            }
+        }
+        Some(Commands::Doctor(_)) => unreachable!("doctor is handled before loading"),
         None => {
             let _ = Cli::command().print_help();
         }
@@ -0,0 +1,21 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::storage_interface::Storage;
+
+/// Trait for storage that can read/write the settings of `workspace clone`
+pub trait CloneStorage: Storage<Option<CloneConfig>> {}
+
+/// The directory used when no layout is configured
+pub const DEFAULT_CLONE_PATH: &str = "~/src/{host}/{owner}/{repo}";
+
+/// Settings for cloning repositories as new workspaces
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneConfig {
+    /// Directory a repository is cloned into. `{host}`, `{owner}` and `{repo}` are replaced by the
+    /// parts of the url. Defaults to `~/src/{host}/{owner}/{repo}` when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "String")]
+    pub path: Option<String>,
+}
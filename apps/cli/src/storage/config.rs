@@ -0,0 +1,32 @@
+use super::{
+    clone::{CloneConfig, CloneStorage},
+    error::StorageError,
+    template::{Template, TemplateStorage},
+    tmux::{Tmux, TmuxStorage},
+    workspace::{Workspace, WorkspaceStorage},
+    worktree::{WorktreeConfig, WorktreeStorage},
+};
+
+/// Storage of the whole configuration, for commands that change several parts of it at once
+pub trait ConfigStorage:
+    WorkspaceStorage + TmuxStorage + WorktreeStorage + CloneStorage + TemplateStorage
+{
+    /// Write the parts of the update together, when any file can not be written nothing is
+    fn write_config(&self, update: &ConfigUpdate) -> Result<(), StorageError>;
+}
+
+/// The parts of the configuration to write, parts that are `None` are left as they are
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigUpdate {
+    pub workspaces: Option<Vec<Workspace>>,
+    pub tmux: Option<Tmux>,
+    pub worktree: Option<Option<WorktreeConfig>>,
+    pub clone: Option<Option<CloneConfig>>,
+    pub templates: Option<Vec<Template>>,
+}
+
+impl ConfigUpdate {
+    pub fn is_empty(&self) -> bool {
+        *self == ConfigUpdate::default()
+    }
+}
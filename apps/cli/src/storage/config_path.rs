@@ -0,0 +1,423 @@
+//! Paths that address a single value in the configuration.
+//!
+//! A path is a list of keys separated by dots, where every key can be followed by selectors for
+//! array items: `[2]` picks an item by index, `[id=api]` picks the item whose `id` is `api` and
+//! `[+]` appends a new item. For example `workspaces[id=api].worktree.onCreate[+]`.
+
+use std::{fmt, str::FromStr};
+
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+    /// A new item at the end of an array, only valid as the last segment of a path being set
+    Append,
+    /// The first item of an array with a field that equals a value
+    Match {
+        key: String,
+        value: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigPath {
+    segments: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigPathError {
+    /// The path could not be parsed
+    Syntax { path: String, message: String },
+    /// Nothing exists at the path
+    NotFound { path: String },
+    /// The path can not be used on the value it points into
+    Invalid { path: String, message: String },
+}
+
+impl fmt::Display for ConfigPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigPathError::Syntax { path, message } => {
+                write!(f, "invalid path `{}`: {}", path, message)
+            }
+            ConfigPathError::NotFound { path } => write!(f, "nothing is set at `{}`", path),
+            ConfigPathError::Invalid { path, message } => write!(f, "`{}` {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigPathError {}
+
+impl FromStr for ConfigPath {
+    type Err = ConfigPathError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let syntax = |message: &str| ConfigPathError::Syntax {
+            path: path.to_string(),
+            message: message.to_string(),
+        };
+
+        let mut segments = vec![];
+        let mut chars = path.chars().peekable();
+        let mut expect_key = true;
+        while chars.peek().is_some() {
+            if expect_key {
+                let key: String =
+                    std::iter::from_fn(|| chars.next_if(|x| *x != '.' && *x != '[')).collect();
+                if key.is_empty() {
+                    return Err(syntax("expected a key"));
+                }
+                segments.push(PathSegment::Key(key));
+                expect_key = false;
+                continue;
+            }
+
+            match chars.next() {
+                Some('.') if chars.peek().is_some() => expect_key = true,
+                Some('.') => return Err(syntax("expected a key after `.`")),
+                Some('[') => {
+                    let selector: String =
+                        std::iter::from_fn(|| chars.next_if(|x| *x != ']')).collect();
+                    if chars.next() != Some(']') {
+                        return Err(syntax("missing `]`"));
+                    }
+                    segments.push(parse_selector(&selector).ok_or_else(|| {
+                        syntax("expected `[<index>]`, `[+]` or `[<key>=<value>]`")
+                    })?);
+                }
+                _ => return Err(syntax("expected `.` or `[`")),
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(syntax("the path is empty"));
+        }
+        if segments[..segments.len() - 1].contains(&PathSegment::Append) {
+            return Err(syntax("`[+]` can only be used at the end of a path"));
+        }
+        Ok(ConfigPath { segments })
+    }
+}
+
+fn parse_selector(selector: &str) -> Option<PathSegment> {
+    if selector == "+" {
+        return Some(PathSegment::Append);
+    }
+    if let Ok(index) = selector.parse() {
+        return Some(PathSegment::Index(index));
+    }
+    let (key, value) = selector.split_once('=')?;
+    if key.is_empty() {
+        return None;
+    }
+    Some(PathSegment::Match {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+impl fmt::Display for ConfigPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                PathSegment::Key(key) => write!(f, ".{}", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Append => write!(f, "[+]")?,
+                PathSegment::Match { key, value } => write!(f, "[{}={}]", key, value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ConfigPath {
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The value at this path
+    pub fn get<'a>(&self, root: &'a Value) -> Result<&'a Value, ConfigPathError> {
+        let mut value = root;
+        for (i, segment) in self.segments.iter().enumerate() {
+            value = match (segment, value) {
+                (PathSegment::Key(key), Value::Object(map)) => map.get(key),
+                (PathSegment::Index(index), Value::Array(items)) => items.get(*index),
+                (PathSegment::Match { key, value }, Value::Array(items)) => {
+                    items.iter().find(|x| field_matches(x, key, value))
+                }
+                (PathSegment::Append, _) => {
+                    return Err(self.invalid(i, "ends in `[+]`, which can only be set"));
+                }
+                (segment, value) => return Err(self.mismatch(i, segment, value)),
+            }
+            .ok_or_else(|| self.not_found(i))?;
+        }
+        Ok(value)
+    }
+
+    /// Set the value at this path, creating missing objects and arrays along the way.
+    ///
+    /// Returns the path of the value that was set, with `[+]` replaced by the index of the new
+    /// item.
+    pub fn set(&self, root: &mut Value, new_value: Value) -> Result<ConfigPath, ConfigPathError> {
+        let (last, parents) = self.segments.split_last().expect("paths are never empty");
+        let parent = self.parent_mut(root, parents.len(), true)?;
+        let mut resolved = self.segments.clone();
+
+        match (last, parent) {
+            (PathSegment::Key(key), Value::Object(map)) => {
+                map.insert(key.clone(), new_value);
+            }
+            (PathSegment::Index(index), Value::Array(items)) => {
+                let item = items
+                    .get_mut(*index)
+                    .ok_or_else(|| self.not_found(parents.len()))?;
+                *item = new_value;
+            }
+            (PathSegment::Append, Value::Array(items)) => {
+                items.push(new_value);
+                resolved[parents.len()] = PathSegment::Index(items.len() - 1);
+            }
+            (PathSegment::Match { key, value }, Value::Array(items)) => {
+                let item = items
+                    .iter_mut()
+                    .find(|x| field_matches(x, key, value))
+                    .ok_or_else(|| self.not_found(parents.len()))?;
+                *item = new_value;
+            }
+            (segment, value) => return Err(self.mismatch(parents.len(), segment, value)),
+        }
+
+        Ok(ConfigPath { segments: resolved })
+    }
+
+    /// Remove the value at this path, returning it
+    pub fn unset(&self, root: &mut Value) -> Result<Value, ConfigPathError> {
+        let (last, parents) = self.segments.split_last().expect("paths are never empty");
+        let parent = self.parent_mut(root, parents.len(), false)?;
+
+        match (last, parent) {
+            (PathSegment::Key(key), Value::Object(map)) => map.remove(key),
+            (PathSegment::Index(index), Value::Array(items)) if *index < items.len() => {
+                Some(items.remove(*index))
+            }
+            (PathSegment::Index(_), Value::Array(_)) => None,
+            (PathSegment::Match { key, value }, Value::Array(items)) => items
+                .iter()
+                .position(|x| field_matches(x, key, value))
+                .map(|index| items.remove(index)),
+            (PathSegment::Append, _) => {
+                return Err(self.invalid(parents.len(), "ends in `[+]`, which can only be set"));
+            }
+            (segment, value) => return Err(self.mismatch(parents.len(), segment, value)),
+        }
+        .ok_or_else(|| self.not_found(parents.len()))
+    }
+
+    /// Walk to the value that holds the first `depth` segments
+    fn parent_mut<'a>(
+        &self,
+        root: &'a mut Value,
+        depth: usize,
+        create: bool,
+    ) -> Result<&'a mut Value, ConfigPathError> {
+        let mut value = root;
+        for i in 0..depth {
+            let segment = &self.segments[i];
+            if create && let (PathSegment::Key(key), Value::Object(map)) = (segment, &mut *value) {
+                // Create the container the next segment expects
+                let empty = match self.segments[i + 1] {
+                    PathSegment::Key(_) => Value::Object(Map::new()),
+                    _ => Value::Array(vec![]),
+                };
+                let child = map.entry(key.clone()).or_insert(Value::Null);
+                if child.is_null() {
+                    *child = empty;
+                }
+            }
+
+            let mismatch = self.mismatch(i, segment, value);
+            value = match (segment, value) {
+                (PathSegment::Key(key), Value::Object(map)) => map.get_mut(key),
+                (PathSegment::Index(index), Value::Array(items)) => items.get_mut(*index),
+                (PathSegment::Match { key, value }, Value::Array(items)) => {
+                    items.iter_mut().find(|x| field_matches(x, key, value))
+                }
+                _ => return Err(mismatch),
+            }
+            .ok_or_else(|| self.not_found(i))?;
+        }
+        Ok(value)
+    }
+
+    /// The path up to and including the segment at `index`
+    fn prefix(&self, index: usize) -> String {
+        ConfigPath {
+            segments: self.segments[..=index].to_vec(),
+        }
+        .to_string()
+    }
+
+    fn not_found(&self, index: usize) -> ConfigPathError {
+        ConfigPathError::NotFound {
+            path: self.prefix(index),
+        }
+    }
+
+    fn invalid(&self, index: usize, message: &str) -> ConfigPathError {
+        ConfigPathError::Invalid {
+            path: self.prefix(index),
+            message: message.to_string(),
+        }
+    }
+
+    fn mismatch(&self, index: usize, segment: &PathSegment, value: &Value) -> ConfigPathError {
+        let expected = match segment {
+            PathSegment::Key(_) => "an object",
+            _ => "an array",
+        };
+        let found = match value {
+            Value::Null => "nothing",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        };
+        self.invalid(
+            index,
+            &format!("expects {} to select from, found {}", expected, found),
+        )
+    }
+}
+
+fn field_matches(item: &Value, key: &str, expected: &str) -> bool {
+    match item.get(key) {
+        Some(Value::String(x)) => x == expected,
+        Some(Value::Null) | None => false,
+        Some(x) => serde_json::from_str::<Value>(expected).is_ok_and(|expected| *x == expected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn config_factory() -> Value {
+        json!({
+            "workspaces": [
+                { "id": "api", "name": "Api", "tags": ["work"] },
+                { "id": "web", "name": "Web" }
+            ],
+            "worktree": { "tmux": true }
+        })
+    }
+
+    fn path(path: &str) -> ConfigPath {
+        path.parse().unwrap()
+    }
+
+    #[test]
+    fn should_parse_and_print_paths() {
+        for text in [
+            "worktree.onCreate[+]",
+            "workspaces[id=api].tags[0]",
+            "tmux.sessions[2].windows",
+        ] {
+            assert_eq!(path(text).to_string(), text);
+        }
+        assert_eq!(
+            path("workspaces[id=api]").segments(),
+            &[
+                PathSegment::Key("workspaces".to_string()),
+                PathSegment::Match {
+                    key: "id".to_string(),
+                    value: "api".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_paths() {
+        for text in ["", "a..b", "a.", "[0]", "a[", "a[x]", "a[+].b", "a[0]b"] {
+            assert!(
+                matches!(
+                    text.parse::<ConfigPath>(),
+                    Err(ConfigPathError::Syntax { .. })
+                ),
+                "{} should not parse",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn should_get_values() {
+        let config = config_factory();
+
+        assert_eq!(
+            path("workspaces[id=api].tags[0]").get(&config),
+            Ok(&json!("work"))
+        );
+        assert_eq!(path("workspaces[1].name").get(&config), Ok(&json!("Web")));
+        assert_eq!(
+            path("workspaces[id=nope].name").get(&config),
+            Err(ConfigPathError::NotFound {
+                path: "workspaces[id=nope]".to_string()
+            })
+        );
+        assert!(matches!(
+            path("worktree.tmux.value").get(&config),
+            Err(ConfigPathError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn should_set_values_and_create_parents() {
+        let mut config = config_factory();
+
+        let resolved = path("workspaces[id=web].worktree.onCreate[+]")
+            .set(&mut config, json!("pnpm install"))
+            .unwrap();
+
+        assert_eq!(
+            resolved.to_string(),
+            "workspaces[id=web].worktree.onCreate[0]"
+        );
+        assert_eq!(
+            config["workspaces"][1]["worktree"],
+            json!({ "onCreate": ["pnpm install"] })
+        );
+
+        path("worktree.tmux")
+            .set(&mut config, json!(false))
+            .unwrap();
+        assert_eq!(config["worktree"]["tmux"], json!(false));
+    }
+
+    #[test]
+    fn should_unset_values() {
+        let mut config = config_factory();
+
+        let removed = path("workspaces[id=api].tags").unset(&mut config).unwrap();
+        assert_eq!(removed, json!(["work"]));
+        assert_eq!(config["workspaces"][0].get("tags"), None);
+
+        let removed = path("workspaces[0]").unset(&mut config).unwrap();
+        assert_eq!(removed["id"], json!("api"));
+        assert_eq!(config["workspaces"].as_array().unwrap().len(), 1);
+
+        assert_eq!(
+            path("worktree.onCreate").unset(&mut config),
+            Err(ConfigPathError::NotFound {
+                path: "worktree.onCreate".to_string()
+            })
+        );
+    }
+}
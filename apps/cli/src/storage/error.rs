@@ -0,0 +1,78 @@
+//! Errors for reading and writing configuration files
+
+use std::{fmt, io};
+
+/// Errors that can occur while writing configuration
+#[derive(Debug)]
+pub enum StorageError {
+    /// The file was changed by something else since it was loaded
+    Conflict { path: String },
+    /// Reading, locking or writing a file failed
+    Io { path: String, source: io::Error },
+    /// The change removes a value defined in a file that is never written
+    ReadOnly { path: String, key: String },
+    /// There is no file the change can be written to
+    NoWritableLayer { key: String },
+}
+
+impl StorageError {
+    pub fn io(path: impl Into<String>, source: io::Error) -> Self {
+        StorageError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Conflict { path } => {
+                write!(
+                    f,
+                    "{} was changed by another process since it was loaded, nothing was written. Run the command again to apply it to the new contents.",
+                    path
+                )
+            }
+            StorageError::Io { path, source } => write!(f, "{}: {}", path, source),
+            StorageError::ReadOnly { path, key } => {
+                write!(
+                    f,
+                    "{} is defined in {}, which is read-only. Remove it from that file instead.",
+                    key, path
+                )
+            }
+            StorageError::NoWritableLayer { key } => {
+                write!(
+                    f,
+                    "{} can not be saved, there is no user or repository configuration file to write it to.",
+                    key
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Io { source, .. } => Some(source),
+            StorageError::Conflict { .. }
+            | StorageError::ReadOnly { .. }
+            | StorageError::NoWritableLayer { .. } => None,
+        }
+    }
+}
+
+impl From<StorageError> for io::Error {
+    fn from(value: StorageError) -> Self {
+        let kind = match &value {
+            StorageError::Io { source, .. } => source.kind(),
+            StorageError::ReadOnly { .. } => io::ErrorKind::PermissionDenied,
+            StorageError::Conflict { .. } | StorageError::NoWritableLayer { .. } => {
+                io::ErrorKind::Other
+            }
+        };
+        io::Error::new(kind, value)
+    }
+}
@@ -0,0 +1,131 @@
+//! How often and how recently workspaces, sessions and commands were used.
+//!
+//! Pickers rank their items by frecency, so the things that are used every hour end up on top.
+//! The history is state rather than configuration, it is kept in its own file and never written
+//! to a configuration layer.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{error::StorageError, storage_interface::Storage};
+
+/// Once the visits of all entries add up to more than this, every count is halved so old
+/// favourites make way for new ones
+const MAX_TOTAL_COUNT: u32 = 1000;
+
+const HOUR: i64 = 60 * 60;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+pub trait FrecencyStorage: Storage<Frecency> {
+    /// Record that the item with the given key was used just now
+    fn visit(&self, key: &str) -> Result<(), StorageError> {
+        let mut frecency = self.read();
+        frecency.visit(key, chrono::Utc::now().timestamp());
+        self.write(&frecency)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Frecency {
+    #[serde(default)]
+    pub entries: BTreeMap<String, FrecencyEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrecencyEntry {
+    /// How often the item was used
+    pub count: u32,
+    /// When the item was last used, in seconds since the unix epoch
+    pub last_used: i64,
+}
+
+impl Frecency {
+    /// Key of a workspace, also used for the tmux session of the workspace
+    pub fn workspace_key(id: &str) -> String {
+        format!("workspace:{}", id)
+    }
+
+    /// Key of a tmux session that is not tied to a workspace
+    pub fn session_key(name: &str) -> String {
+        format!("session:{}", name)
+    }
+
+    /// Key of a command palette command
+    pub fn command_key(name: &str) -> String {
+        format!("command:{}", name)
+    }
+
+    pub fn visit(&mut self, key: &str, now: i64) {
+        let entry = self
+            .entries
+            .entry(key.to_string())
+            .or_insert(FrecencyEntry {
+                count: 0,
+                last_used: now,
+            });
+        entry.count += 1;
+        entry.last_used = now;
+
+        let total: u32 = self.entries.values().map(|x| x.count).sum();
+        if total > MAX_TOTAL_COUNT {
+            for entry in self.entries.values_mut() {
+                entry.count /= 2;
+            }
+            self.entries.retain(|_, entry| entry.count > 0);
+        }
+    }
+
+    /// The frecency score of an item, items that were never used score 0
+    pub fn score(&self, key: &str, now: i64) -> f64 {
+        let Some(entry) = self.entries.get(key) else {
+            return 0.0;
+        };
+
+        let age = now - entry.last_used;
+        let weight = if age < HOUR {
+            4.0
+        } else if age < DAY {
+            2.0
+        } else if age < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+        entry.count as f64 * weight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn should_score_recent_visits_higher() {
+        let mut frecency = Frecency::default();
+        frecency.visit("old", NOW - 2 * WEEK);
+        frecency.visit("old", NOW - 2 * WEEK);
+        frecency.visit("old", NOW - 2 * WEEK);
+        frecency.visit("new", NOW - 10);
+
+        assert!(frecency.score("new", NOW) > frecency.score("old", NOW));
+        assert_eq!(frecency.score("unknown", NOW), 0.0);
+    }
+
+    #[test]
+    fn should_age_counts_when_history_grows_too_large() {
+        let mut frecency = Frecency::default();
+        frecency.visit("rare", NOW);
+        for _ in 0..MAX_TOTAL_COUNT {
+            frecency.visit("frequent", NOW);
+        }
+
+        assert!(!frecency.entries.contains_key("rare"));
+        assert_eq!(frecency.entries["frequent"].count, MAX_TOTAL_COUNT / 2);
+    }
+}
@@ -0,0 +1,178 @@
+//! Crash-safe file access for configuration files.
+//!
+//! Files are replaced by writing a temporary file next to them and renaming it over the
+//! original, so readers only ever see the old or the new contents. Read-modify-write cycles are
+//! guarded by an advisory lock on a `<file>.lock` sidecar, which is shared by every `rafaeltab`
+//! process.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Identifies the contents of a file at the time it was read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFingerprint {
+    hash: u64,
+}
+
+impl FileFingerprint {
+    fn new(content: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        FileFingerprint {
+            hash: hasher.finish(),
+        }
+    }
+
+    /// Whether the file still has the contents it had when this fingerprint was taken.
+    ///
+    /// Only the contents are compared, so a file that was touched without being changed is not
+    /// reported as changed.
+    pub fn matches(&self, other: &FileFingerprint) -> bool {
+        self.hash == other.hash
+    }
+}
+
+/// Read a file along with a fingerprint of its contents
+pub fn read_with_fingerprint(path: &str) -> Result<(String, FileFingerprint), io::Error> {
+    let content = fs::read_to_string(path)?;
+    let fingerprint = FileFingerprint::new(&content);
+    Ok((content, fingerprint))
+}
+
+/// An exclusive advisory lock, released when dropped
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    /// Block until the lock for `path` is acquired
+    pub fn acquire(path: &str) -> Result<FileLock, io::Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path(path))?;
+        file.lock()?;
+        Ok(FileLock { file })
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+fn lock_path(path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.lock", path))
+}
+
+/// Replace the contents of a file without ever leaving it partially written.
+///
+/// Symlinks are followed so the file they point to is replaced instead of the link, and the
+/// permissions of the existing file are kept.
+pub fn write_atomic(path: &str, content: &str) -> Result<(), io::Error> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    let temp_path = temp_path(&target);
+
+    let result =
+        write_temp(&target, &temp_path, content).and_then(|_| fs::rename(&temp_path, &target));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn write_temp(target: &Path, temp_path: &Path, content: &str) -> Result<(), io::Error> {
+    let mut file = File::create(temp_path)?;
+    if let Ok(metadata) = fs::metadata(target) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(content.as_bytes())?;
+    file.sync_all()
+}
+
+fn temp_path(target: &Path) -> PathBuf {
+    let file_name = target
+        .file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{}.{}.tmp", file_name, process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_replace_file_contents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(path.to_str().unwrap(), "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let leftovers: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .filter_map(|x| x.ok())
+            .filter(|x| x.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn should_write_through_symlinks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target = temp_dir.path().join("dotfiles.json");
+        let link = temp_dir.path().join("config.json");
+        fs::write(&target, "old").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomic(link.to_str().unwrap(), "new").unwrap();
+
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+    }
+
+    #[test]
+    fn should_detect_changed_contents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let path = path.to_str().unwrap();
+        fs::write(path, "first").unwrap();
+        let (_, before) = read_with_fingerprint(path).unwrap();
+
+        fs::write(path, "first").unwrap();
+        let (_, touched) = read_with_fingerprint(path).unwrap();
+        fs::write(path, "second").unwrap();
+        let (_, changed) = read_with_fingerprint(path).unwrap();
+
+        assert!(before.matches(&touched));
+        assert!(!before.matches(&changed));
+    }
+
+    #[test]
+    fn should_hold_lock_until_dropped() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let path = path.to_str().unwrap();
+
+        let lock = FileLock::acquire(path).unwrap();
+        let other = File::open(lock_path(path)).unwrap();
+        assert!(other.try_lock().is_err());
+
+        drop(lock);
+        assert!(other.try_lock().is_ok());
+    }
+}
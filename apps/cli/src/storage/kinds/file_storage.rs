@@ -0,0 +1,502 @@
+use std::{cell::RefCell, env, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    storage::{
+        clone::{CloneConfig, CloneStorage},
+        config::{ConfigStorage, ConfigUpdate},
+        error::StorageError,
+        kinds::{
+            atomic_file::{FileFingerprint, FileLock, read_with_fingerprint, write_atomic},
+            format::ConfigFormat,
+        },
+        layers::{
+            ConfigLayer, ConfigLayerKind, LayerData, LayerSearch, describe_layer_search,
+            discover_layer_paths, expand_includes, merge_layers, resolve_include, write_clone,
+            write_templates, write_tmux, write_workspaces, write_worktree,
+        },
+        migrations::migrate,
+        storage_interface::Storage,
+        template::{Template, TemplateStorage},
+        tmux::{Tmux, TmuxStorage},
+        workspace::{Workspace, WorkspaceStorage},
+        worktree::{WorktreeConfig, WorktreeStorage},
+    },
+    utils::path::expand_path,
+};
+
+pub struct FileStorageProvider {
+    layers: Vec<(ConfigLayerKind, String)>,
+}
+pub struct FileStorage {
+    layers: RefCell<Vec<LoadedLayer>>,
+    data: RefCell<FileData>,
+}
+
+/// A layer together with the fingerprint of the file it was read from
+#[derive(Debug, Clone)]
+struct LoadedLayer {
+    layer: ConfigLayer,
+    fingerprint: FileFingerprint,
+}
+
+impl FileStorageProvider {
+    pub fn new(path: Option<String>) -> Result<Self, io::Error> {
+        let layers = get_config_layers(path)?;
+
+        Ok(FileStorageProvider { layers })
+    }
+
+    /// The configuration files that make up the configuration, from lowest to highest precedence.
+    ///
+    /// Included files are part of the result, includes that can not be read are skipped.
+    pub fn paths(&self) -> Vec<String> {
+        // Broken includes are left out so the remaining files can still be validated or migrated
+        let readable_includes = |path: &str| {
+            Ok(read_includes(path)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|pattern| resolve_include(path, pattern).is_ok())
+                .collect())
+        };
+        expand_includes(&self.layers, readable_includes)
+            .unwrap_or_else(|_| self.layers.clone())
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect()
+    }
+
+    pub fn load(&self) -> Result<FileStorage, io::Error> {
+        let layers = load_layers(&self.layers)?;
+        let data = FileData::from_layers(&layers);
+
+        Ok(FileStorage {
+            layers: RefCell::new(layers),
+            data: RefCell::new(data),
+        })
+    }
+}
+
+impl FileStorage {
+    /// Apply an update to the layers and write every layer that changed.
+    ///
+    /// The changed files are locked for the duration of the write. When any of them changed on
+    /// disk since it was loaded nothing is written and `StorageError::Conflict` is returned.
+    fn write_layers(
+        &self,
+        update: impl FnOnce(&mut [ConfigLayer]) -> Result<Vec<usize>, StorageError>,
+    ) -> Result<(), StorageError> {
+        let loaded = self.layers.borrow().clone();
+        let mut layers: Vec<ConfigLayer> = loaded.iter().map(|x| x.layer.clone()).collect();
+        let mut changed = update(&mut layers)?;
+        if changed.is_empty() {
+            return Ok(());
+        }
+        // Always lock in the same order so concurrent writers can not deadlock
+        changed.sort();
+        changed.dedup();
+
+        let mut locks = vec![];
+        for &index in &changed {
+            let path = &layers[index].path;
+            locks.push(FileLock::acquire(path).map_err(|err| StorageError::io(path, err))?);
+        }
+
+        for &index in &changed {
+            let path = &layers[index].path;
+            let fingerprint = match read_with_fingerprint(path) {
+                Ok((_, fingerprint)) => fingerprint,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    return Err(StorageError::Conflict { path: path.clone() });
+                }
+                Err(err) => return Err(StorageError::io(path, err)),
+            };
+            if !loaded[index].fingerprint.matches(&fingerprint) {
+                return Err(StorageError::Conflict { path: path.clone() });
+            }
+        }
+
+        // Every file is serialized before the first one is written
+        let mut contents = vec![];
+        for &index in &changed {
+            let layer = &layers[index];
+            let content = ConfigFormat::from_path(&layer.path)
+                .serialize(&layer.data)
+                .map_err(|err| StorageError::io(&layer.path, err))?;
+            contents.push((&layer.path, content));
+        }
+        for (path, content) in contents {
+            write_atomic(path, &content).map_err(|err| StorageError::io(path, err))?;
+        }
+
+        // Reloading may migrate files, which takes the locks again
+        drop(locks);
+        // Includes are found again from the files that were not included themselves
+        let locations: Vec<(ConfigLayerKind, String)> = layers
+            .iter()
+            .filter(|x| x.kind != ConfigLayerKind::Include)
+            .map(|x| (x.kind, x.path.clone()))
+            .collect();
+        let layers = load_layers(&locations)?;
+        self.data.replace(FileData::from_layers(&layers));
+        self.layers.replace(layers);
+        Ok(())
+    }
+}
+
+impl ConfigStorage for FileStorage {
+    fn write_config(&self, update: &ConfigUpdate) -> Result<(), StorageError> {
+        self.write_layers(|layers| {
+            let mut changed = vec![];
+            if let Some(value) = &update.workspaces {
+                changed.extend(write_workspaces(layers, value)?);
+            }
+            if let Some(value) = &update.tmux {
+                changed.extend(write_tmux(layers, value)?);
+            }
+            if let Some(value) = &update.worktree {
+                changed.extend(write_worktree(layers, value)?);
+            }
+            if let Some(value) = &update.clone {
+                changed.extend(write_clone(layers, value)?);
+            }
+            if let Some(value) = &update.templates {
+                changed.extend(write_templates(layers, value)?);
+            }
+            Ok(changed)
+        })
+    }
+}
+
+impl WorkspaceStorage for FileStorage {
+    fn workspace_source(&self, id: &str) -> Option<String> {
+        self.layers
+            .borrow()
+            .iter()
+            .rev()
+            .find(|x| {
+                x.layer
+                    .data
+                    .workspaces
+                    .iter()
+                    .flatten()
+                    .any(|workspace| workspace.id == id)
+            })
+            .map(|x| x.layer.path.clone())
+    }
+}
+impl Storage<Vec<Workspace>> for FileStorage {
+    fn read(&self) -> Vec<Workspace> {
+        self.data.borrow().workspaces.clone()
+    }
+
+    fn write(&self, value: &Vec<Workspace>) -> Result<(), StorageError> {
+        self.write_layers(|layers| write_workspaces(layers, value))
+    }
+}
+
+impl TmuxStorage for FileStorage {}
+impl Storage<Tmux> for FileStorage {
+    fn read(&self) -> Tmux {
+        self.data.borrow().tmux.clone()
+    }
+
+    fn write(&self, value: &Tmux) -> Result<(), StorageError> {
+        self.write_layers(|layers| write_tmux(layers, value))
+    }
+}
+
+impl WorktreeStorage for FileStorage {}
+impl Storage<Option<WorktreeConfig>> for FileStorage {
+    fn read(&self) -> Option<WorktreeConfig> {
+        self.data.borrow().worktree.clone()
+    }
+
+    fn write(&self, value: &Option<WorktreeConfig>) -> Result<(), StorageError> {
+        self.write_layers(|layers| write_worktree(layers, value))
+    }
+}
+
+impl CloneStorage for FileStorage {}
+impl Storage<Option<CloneConfig>> for FileStorage {
+    fn read(&self) -> Option<CloneConfig> {
+        self.data.borrow().clone.clone()
+    }
+
+    fn write(&self, value: &Option<CloneConfig>) -> Result<(), StorageError> {
+        self.write_layers(|layers| write_clone(layers, value))
+    }
+}
+
+impl TemplateStorage for FileStorage {}
+impl Storage<Vec<Template>> for FileStorage {
+    fn read(&self) -> Vec<Template> {
+        self.data.borrow().templates.clone()
+    }
+
+    fn write(&self, value: &Vec<Template>) -> Result<(), StorageError> {
+        self.write_layers(|layers| write_templates(layers, value))
+    }
+}
+
+/// The merged configuration of all layers
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileData {
+    pub workspaces: Vec<Workspace>,
+    pub tmux: Tmux,
+    /// Global worktree configuration (optional)
+    pub worktree: Option<WorktreeConfig>,
+    /// Settings for cloning repositories (optional)
+    pub clone: Option<CloneConfig>,
+    /// Project templates for `workspace new`
+    pub templates: Vec<Template>,
+}
+
+impl FileData {
+    fn from_layers(layers: &[LoadedLayer]) -> Self {
+        let layers: Vec<ConfigLayer> = layers.iter().map(|x| x.layer.clone()).collect();
+        let merged = merge_layers(&layers);
+        FileData {
+            workspaces: merged.workspaces,
+            tmux: merged.tmux,
+            worktree: merged.worktree,
+            clone: merged.clone,
+            templates: merged.templates,
+        }
+    }
+}
+
+/// The include patterns of a configuration file, without migrating or fully parsing it
+fn read_includes(path: &str) -> Result<Vec<String>, io::Error> {
+    let content = fs::read_to_string(path)?;
+    let data: serde_json::Value = ConfigFormat::from_path(path).parse(&content)?;
+    match data.get("include") {
+        None | Some(serde_json::Value::Null) => Ok(vec![]),
+        Some(include) => serde_json::from_value(include.clone()).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("include must be a list of paths: {}", err),
+            )
+        }),
+    }
+}
+
+fn load_layers(locations: &[(ConfigLayerKind, String)]) -> Result<Vec<LoadedLayer>, StorageError> {
+    let locations = expand_includes(locations, read_includes)?;
+
+    locations
+        .iter()
+        .map(|(kind, path)| {
+            // Older files are migrated in memory, `config migrate` rewrites them on disk
+            let (content, fingerprint) =
+                read_with_fingerprint(path).map_err(|err| StorageError::io(path, err))?;
+            let format = ConfigFormat::from_path(path);
+            let data: LayerData = match migrate(format, &content) {
+                Ok(Some(migrated)) => Ok(migrated.data),
+                Ok(None) => format.parse(content.as_str()),
+                Err(err) => Err(err),
+            }
+            .map_err(|err| StorageError::io(path, err))?;
+            Ok(LoadedLayer {
+                layer: ConfigLayer {
+                    kind: *kind,
+                    path: path.clone(),
+                    data,
+                },
+                fingerprint,
+            })
+        })
+        .collect()
+}
+
+fn get_config_layers(path: Option<String>) -> Result<Vec<(ConfigLayerKind, String)>, io::Error> {
+    // An explicit config file replaces the layered lookup entirely
+    if let Some(path) = path {
+        return Ok(vec![(ConfigLayerKind::User, path)]);
+    }
+
+    let cwd = env::current_dir()?;
+    let home = expand_path("~");
+    let xdg_config_home = env::var("XDG_CONFIG_HOME").ok().filter(|x| !x.is_empty());
+    let search = LayerSearch {
+        cwd: &cwd,
+        home: Some(Path::new(&home)),
+        xdg_config_home: xdg_config_home.as_deref().map(Path::new),
+    };
+    let layers = discover_layer_paths(&search);
+
+    if layers.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            describe_layer_search(&search),
+        ));
+    }
+
+    Ok(layers
+        .into_iter()
+        .map(|(kind, path)| (kind, path.to_string_lossy().to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "workspaces": [{ "id": "api", "name": "Api", "root": "~/api" }],
+        "tmux": { "sessions": [], "defaultWindows": [] }
+    }"#;
+
+    fn workspace(id: &str) -> Workspace {
+        Workspace {
+            root: format!("~/{}", id),
+            id: id.to_string(),
+            name: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn storage_factory(path: &Path) -> FileStorage {
+        fs::write(path, CONFIG).unwrap();
+        FileStorageProvider::new(Some(path.to_string_lossy().to_string()))
+            .unwrap()
+            .load()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_write_and_reload_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let storage = storage_factory(&path);
+
+        let mut workspaces: Vec<Workspace> = storage.read();
+        workspaces.push(workspace("web"));
+        storage.write(&workspaces).unwrap();
+
+        assert_eq!(Storage::<Vec<Workspace>>::read(&storage), workspaces);
+        assert!(fs::read_to_string(&path).unwrap().contains("\"web\""));
+    }
+
+    #[test]
+    fn should_merge_included_files_and_write_to_including_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let team = temp_dir.path().join("team.json");
+        let path = temp_dir.path().join("config.json");
+        fs::write(
+            &team,
+            r#"{ "workspaces": [{ "id": "team", "name": "Team", "root": "~/team" }] }"#,
+        )
+        .unwrap();
+        fs::write(
+            &path,
+            CONFIG.replacen('{', r#"{ "include": ["team.json"],"#, 1),
+        )
+        .unwrap();
+        let storage = FileStorageProvider::new(Some(path.to_string_lossy().to_string()))
+            .unwrap()
+            .load()
+            .unwrap();
+
+        let mut workspaces: Vec<Workspace> = storage.read();
+        assert_eq!(workspaces.len(), 2);
+        assert_eq!(
+            storage.workspace_source("team"),
+            Some(team.to_string_lossy().to_string())
+        );
+
+        workspaces.push(workspace("web"));
+        storage.write(&workspaces).unwrap();
+
+        assert!(fs::read_to_string(&path).unwrap().contains("\"web\""));
+        assert!(!fs::read_to_string(&team).unwrap().contains("\"web\""));
+        assert_eq!(Storage::<Vec<Workspace>>::read(&storage), workspaces);
+    }
+
+    #[test]
+    fn should_migrate_older_files_without_rewriting_them() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let team = temp_dir.path().join("team.json");
+        let path = temp_dir.path().join("config.json");
+        let team_content = r#"{ "workspaces": [{ "id": "team", "name": "Team", "root": "~/team", "tags": null }] }"#;
+        let content = CONFIG.replacen('{', r#"{ "include": ["team.json"],"#, 1);
+        fs::write(&team, team_content).unwrap();
+        fs::write(&path, &content).unwrap();
+
+        let storage = FileStorageProvider::new(Some(path.to_string_lossy().to_string()))
+            .unwrap()
+            .load()
+            .unwrap();
+
+        assert_eq!(Storage::<Vec<Workspace>>::read(&storage).len(), 2);
+        assert_eq!(fs::read_to_string(&team).unwrap(), team_content);
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn should_write_several_parts_at_once() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let storage = storage_factory(&path);
+        let mut workspaces: Vec<Workspace> = storage.read();
+        workspaces.push(workspace("web"));
+        let update = ConfigUpdate {
+            workspaces: Some(workspaces.clone()),
+            templates: Some(vec![]),
+            clone: Some(Some(CloneConfig::default())),
+            ..Default::default()
+        };
+
+        let external = CONFIG.replace("Api", "Changed elsewhere");
+        fs::write(&path, &external).unwrap();
+        assert!(matches!(
+            storage.write_config(&update),
+            Err(StorageError::Conflict { .. })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), external);
+
+        let storage = storage_factory(&path);
+        storage.write_config(&update).unwrap();
+
+        assert_eq!(Storage::<Vec<Workspace>>::read(&storage), workspaces);
+        assert_eq!(
+            Storage::<Option<CloneConfig>>::read(&storage),
+            Some(CloneConfig::default())
+        );
+    }
+
+    #[test]
+    fn should_refuse_to_overwrite_external_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let storage = storage_factory(&path);
+        let external = CONFIG.replace("Api", "Changed elsewhere");
+        fs::write(&path, &external).unwrap();
+
+        let mut workspaces: Vec<Workspace> = storage.read();
+        workspaces.push(workspace("web"));
+        let result = storage.write(&workspaces);
+
+        assert!(matches!(result, Err(StorageError::Conflict { .. })));
+        assert_eq!(fs::read_to_string(&path).unwrap(), external);
+    }
+
+    #[test]
+    fn should_report_write_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let storage = storage_factory(&path);
+        fs::remove_file(&path).unwrap();
+
+        let mut workspaces: Vec<Workspace> = storage.read();
+        workspaces.push(workspace("web"));
+        let result = storage.write(&workspaces);
+
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+}
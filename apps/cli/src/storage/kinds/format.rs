@@ -0,0 +1,156 @@
+use std::{io, path::Path};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+/// The file format of a configuration file, picked by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// All extensions that are recognized, in the order they are searched for
+    pub const EXTENSIONS: &[&str] = &["json", "toml", "yaml", "yml"];
+
+    /// Pick the format from the extension of a path.
+    ///
+    /// Files without a recognized extension are treated as JSON.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_lowercase());
+        match extension.as_deref() {
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    /// Whether files of this format can have comments, which are lost when a file is rewritten
+    pub fn has_comments(&self) -> bool {
+        !matches!(self, ConfigFormat::Json)
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, io::Error> {
+        match self {
+            ConfigFormat::Json => Ok(serde_json::from_str(content)?),
+            ConfigFormat::Toml => toml::from_str(content).map_err(invalid_data),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(invalid_data),
+        }
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String, io::Error> {
+        match self {
+            ConfigFormat::Json => Ok(serde_json::to_string_pretty(value)?),
+            ConfigFormat::Toml => toml::to_string_pretty(value).map_err(invalid_data),
+            ConfigFormat::Yaml => serde_yaml::to_string(value).map_err(invalid_data),
+        }
+    }
+}
+
+fn invalid_data(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        layers::{LayerData, LayerTmux},
+        tmux::{PathSession, Session, Window, WorkspaceSession},
+        workspace::Workspace,
+        worktree::{WorkspaceWorktreeConfig, WorktreeConfig},
+    };
+
+    fn data_factory() -> LayerData {
+        LayerData {
+            workspaces: Some(vec![
+                Workspace {
+                    root: "~/Source/api".to_string(),
+                    id: "api".to_string(),
+                    name: "Api".to_string(),
+                    tags: Some(vec!["work".to_string()]),
+                    worktree: Some(WorkspaceWorktreeConfig {
+                        symlink_files: vec![".env".to_string()],
+                        on_create: vec!["pnpm install".to_string()],
+                        on_destroy: vec![],
+                        tmux: None,
+                    }),
+                    ..Default::default()
+                },
+                Workspace {
+                    root: "~/Source/web".to_string(),
+                    id: "web".to_string(),
+                    name: "Web".to_string(),
+                    ..Default::default()
+                },
+            ]),
+            tmux: Some(LayerTmux {
+                sessions: Some(vec![
+                    Session::Workspace(WorkspaceSession {
+                        windows: vec![Window {
+                            name: "nvim".to_string(),
+                            command: Some("nvim .".to_string()),
+                            ..Default::default()
+                        }],
+                        workspace: "api".to_string(),
+                        name: None,
+                    }),
+                    Session::Path(PathSession {
+                        windows: vec![Window {
+                            name: "zsh".to_string(),
+                            command: None,
+                            ..Default::default()
+                        }],
+                        path: "/usr/bin".to_string(),
+                        name: "binaries".to_string(),
+                        ..Default::default()
+                    }),
+                ]),
+                default_windows: Some(vec![]),
+            }),
+            worktree: Some(WorktreeConfig {
+                symlink_files: vec![],
+                on_create: vec!["git fetch".to_string()],
+                on_destroy: vec!["docker compose down".to_string()],
+                tmux: Some(false),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_pick_format_from_extension() {
+        assert_eq!(ConfigFormat::from_path("a.json"), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_path("a.toml"), ConfigFormat::Toml);
+        assert_eq!(ConfigFormat::from_path("a.yaml"), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path("a.YML"), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path(".rafaeltab"), ConfigFormat::Json);
+    }
+
+    #[test]
+    fn should_round_trip_every_format() {
+        for format in [ConfigFormat::Json, ConfigFormat::Toml, ConfigFormat::Yaml] {
+            let data = data_factory();
+            let content = format.serialize(&data).unwrap();
+            let parsed: LayerData = format.parse(&content).unwrap();
+
+            assert_eq!(
+                parsed, data,
+                "{:?} did not round trip:\n{}",
+                format, content
+            );
+        }
+    }
+
+    #[test]
+    fn should_report_invalid_toml_as_invalid_data() {
+        let result: Result<LayerData, io::Error> = ConfigFormat::Toml.parse("workspaces = [");
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1 +1,6 @@
-pub mod json_storage;
+pub mod atomic_file;
+pub mod file_storage;
+pub mod format;
+pub mod snapshot_storage;
+pub mod state_storage;
+pub mod status_cache_storage;
@@ -0,0 +1,101 @@
+use std::{fs, io, path::Path};
+
+use crate::storage::{
+    error::StorageError,
+    kinds::{atomic_file::write_atomic, state_storage::state_path},
+    snapshot::{SnapshotStorage, Snapshots},
+    storage_interface::Storage,
+};
+
+/// Snapshots of tmux sessions stored in a JSON file outside of the configuration.
+///
+/// The file is `snapshots.json` next to the usage history, so `$RAFAELTAB_STATE_FILE` moves both.
+/// A missing or unreadable file has no snapshots.
+pub struct JsonSnapshotStorage {
+    path: String,
+}
+
+impl JsonSnapshotStorage {
+    pub fn new() -> Self {
+        JsonSnapshotStorage {
+            path: Path::new(&state_path())
+                .with_file_name("snapshots.json")
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
+    pub fn with_path(path: impl Into<String>) -> Self {
+        JsonSnapshotStorage { path: path.into() }
+    }
+
+    fn write_file(&self, value: &Snapshots) -> Result<(), io::Error> {
+        if let Some(parent) = Path::new(&self.path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+        write_atomic(&self.path, &content)
+    }
+}
+
+impl Default for JsonSnapshotStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Storage<Snapshots> for JsonSnapshotStorage {
+    fn read(&self) -> Snapshots {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, value: &Snapshots) -> Result<(), StorageError> {
+        self.write_file(value)
+            .map_err(|err| StorageError::io(&self.path, err))
+    }
+}
+
+impl SnapshotStorage for JsonSnapshotStorage {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::snapshot::{PaneSnapshot, SessionSnapshot, Snapshot, WindowSnapshot};
+
+    #[test]
+    fn should_keep_snapshots_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("snapshots.json");
+        let storage = JsonSnapshotStorage::with_path(path.to_string_lossy());
+
+        let snapshot = Snapshot {
+            saved_at: 10,
+            sessions: vec![SessionSnapshot {
+                name: "api".to_string(),
+                path: "/src/api".to_string(),
+                id: None,
+                windows: vec![WindowSnapshot {
+                    name: "nvim".to_string(),
+                    layout: "b25d,80x24,0,0,0".to_string(),
+                    active: true,
+                    panes: vec![PaneSnapshot {
+                        cwd: "/src/api".to_string(),
+                        command: Some("nvim".to_string()),
+                        active: true,
+                    }],
+                }],
+            }],
+        };
+        let mut snapshots = storage.read();
+        snapshots
+            .snapshots
+            .insert("work".to_string(), snapshot.clone());
+        storage.write(&snapshots).unwrap();
+
+        let stored = JsonSnapshotStorage::with_path(path.to_string_lossy()).read();
+        assert_eq!(stored.snapshots.get("work"), Some(&snapshot));
+    }
+}
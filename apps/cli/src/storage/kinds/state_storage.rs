@@ -0,0 +1,124 @@
+use std::{env, fs, io, path::Path};
+
+use crate::{
+    storage::{
+        error::StorageError,
+        frecency::{Frecency, FrecencyStorage},
+        kinds::atomic_file::{FileLock, write_atomic},
+        storage_interface::Storage,
+    },
+    utils::path::expand_path,
+};
+
+/// Usage history stored in a JSON file outside of the configuration.
+///
+/// The file is `$RAFAELTAB_STATE_FILE` when set, otherwise `rafaeltab/state.json` in
+/// `$XDG_STATE_HOME` or `~/.local/state`. A missing or unreadable file is an empty history.
+pub struct JsonStateStorage {
+    path: String,
+}
+
+impl JsonStateStorage {
+    pub fn new() -> Self {
+        JsonStateStorage { path: state_path() }
+    }
+
+    pub fn with_path(path: impl Into<String>) -> Self {
+        JsonStateStorage { path: path.into() }
+    }
+
+    fn read_file(&self) -> Frecency {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_file(&self, value: &Frecency) -> Result<(), io::Error> {
+        if let Some(parent) = Path::new(&self.path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+        write_atomic(&self.path, &content)
+    }
+}
+
+impl Default for JsonStateStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Storage<Frecency> for JsonStateStorage {
+    fn read(&self) -> Frecency {
+        self.read_file()
+    }
+
+    fn write(&self, value: &Frecency) -> Result<(), StorageError> {
+        self.write_file(value)
+            .map_err(|err| StorageError::io(&self.path, err))
+    }
+}
+
+impl FrecencyStorage for JsonStateStorage {
+    fn visit(&self, key: &str) -> Result<(), StorageError> {
+        if let Some(parent) = Path::new(&self.path).parent() {
+            fs::create_dir_all(parent).map_err(|err| StorageError::io(&self.path, err))?;
+        }
+        // Concurrent switches should not lose each other's visits
+        let _lock =
+            FileLock::acquire(&self.path).map_err(|err| StorageError::io(&self.path, err))?;
+        let mut frecency = self.read_file();
+        frecency.visit(key, chrono::Utc::now().timestamp());
+        self.write(&frecency)
+    }
+}
+
+/// The usage history file, snapshots of tmux sessions are kept next to it
+pub fn state_path() -> String {
+    if let Some(path) = env::var("RAFAELTAB_STATE_FILE")
+        .ok()
+        .filter(|x| !x.is_empty())
+    {
+        return path;
+    }
+
+    let state_home = env::var("XDG_STATE_HOME")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .unwrap_or_else(|| expand_path("~/.local/state"));
+    Path::new(&state_home)
+        .join("rafaeltab")
+        .join("state.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_record_visits_in_a_new_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("state.json");
+        let storage = JsonStateStorage::with_path(path.to_string_lossy());
+
+        storage.visit("workspace:api").unwrap();
+        storage.visit("workspace:api").unwrap();
+
+        let stored = JsonStateStorage::with_path(path.to_string_lossy()).read();
+        assert_eq!(stored.entries["workspace:api"].count, 2);
+    }
+
+    #[test]
+    fn should_treat_a_broken_file_as_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        fs::write(&path, "not json").unwrap();
+
+        let storage = JsonStateStorage::with_path(path.to_string_lossy());
+
+        assert_eq!(storage.read(), Frecency::default());
+    }
+}
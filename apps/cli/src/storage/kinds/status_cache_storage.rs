@@ -0,0 +1,87 @@
+use std::{fs, io, path::Path};
+
+use crate::storage::{
+    error::StorageError,
+    kinds::{atomic_file::write_atomic, state_storage::state_path},
+    status_cache::{StatusCache, StatusCacheStorage},
+    storage_interface::Storage,
+};
+
+/// Git state for the tmux status line stored in a JSON file outside of the configuration.
+///
+/// The file is `status-cache.json` next to the usage history, so `$RAFAELTAB_STATE_FILE` moves
+/// both. A missing or unreadable file is an empty cache.
+pub struct JsonStatusCacheStorage {
+    path: String,
+}
+
+impl JsonStatusCacheStorage {
+    pub fn new() -> Self {
+        JsonStatusCacheStorage {
+            path: Path::new(&state_path())
+                .with_file_name("status-cache.json")
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
+    pub fn with_path(path: impl Into<String>) -> Self {
+        JsonStatusCacheStorage { path: path.into() }
+    }
+
+    fn write_file(&self, value: &StatusCache) -> Result<(), io::Error> {
+        if let Some(parent) = Path::new(&self.path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(value).map_err(io::Error::other)?;
+        write_atomic(&self.path, &content)
+    }
+}
+
+impl Default for JsonStatusCacheStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Storage<StatusCache> for JsonStatusCacheStorage {
+    fn read(&self) -> StatusCache {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, value: &StatusCache) -> Result<(), StorageError> {
+        self.write_file(value)
+            .map_err(|err| StorageError::io(&self.path, err))
+    }
+}
+
+impl StatusCacheStorage for JsonStatusCacheStorage {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::status_cache::CachedGitStatus;
+
+    #[test]
+    fn should_keep_git_state_by_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("status-cache.json");
+        let storage = JsonStatusCacheStorage::with_path(path.to_string_lossy());
+
+        let status = CachedGitStatus {
+            checked_at: 10,
+            root: Some("/src/api".to_string()),
+            branch: Some("main".to_string()),
+            dirty: true,
+        };
+        let mut cache = storage.read();
+        cache.paths.insert("/src/api".to_string(), status.clone());
+        storage.write(&cache).unwrap();
+
+        let stored = JsonStatusCacheStorage::with_path(path.to_string_lossy()).read();
+        assert_eq!(stored.paths.get("/src/api"), Some(&status));
+    }
+}
@@ -0,0 +1,1236 @@
+//! Layered configuration.
+//!
+//! The configuration is assembled from up to three files. From lowest to highest precedence:
+//!
+//! 1. The system file (`/etc/rafaeltab/config.json` or `/etc/rafaeltab.json`)
+//! 2. The user file (`$XDG_CONFIG_HOME/rafaeltab/config.json`, `~/.config/rafaeltab/config.json`
+//!    or `~/.rafaeltab.json`, whichever exists first)
+//! 3. A repository-local `.rafaeltab.json`, discovered by walking up from the current directory
+//!    (the home directory itself is never considered repository-local)
+//!
+//! Layers are merged with the following rules:
+//! - `workspaces` are merged by id, a higher layer replaces a workspace with the same id
+//! - `tmux.sessions` are merged by workspace id (workspace sessions) or name (path sessions)
+//! - `tmux.defaultWindows` is taken from the highest layer that sets it
+//! - `worktree` lists are combined from low to high without duplicates, `worktree.tmux` is taken
+//!   from the highest layer that sets it
+//! - `clone.path` is taken from the highest layer that sets it
+//! - `templates` are merged by name, a higher layer replaces a template with the same name
+//!
+//! Writes are routed back to the layer that owns a value. Values that no layer owns yet are
+//! written to the primary layer, which is the user file when present. The system file and included
+//! files are never written: changes to values they own are written to the first writable layer
+//! above them, and removing a value they define is an error.
+//!
+//! Every location may also use the `.toml`, `.yaml` or `.yml` extension instead of `.json`.
+//! When several exist in the same location the first in that order is used.
+//!
+//! Any file may list other files in `include`, as paths or glob patterns relative to the file
+//! itself. Included files are layers directly below the file that includes them, so the including
+//! file overrides them. Every file is included at most once.
+
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    clone::CloneConfig,
+    error::StorageError,
+    kinds::format::ConfigFormat,
+    template::Template,
+    tmux::{Session, Tmux, Window},
+    workspace::Workspace,
+    worktree::WorktreeConfig,
+};
+use crate::utils::interpolation::{InterpolationContext, interpolate};
+
+// Locations without their extension, see `ConfigFormat::EXTENSIONS`
+static SYSTEM_PATH_LOCATIONS_LINUX: &[&str] = &["/etc/rafaeltab/config", "/etc/rafaeltab"];
+static USER_PATH_LOCATIONS_LINUX: &[&str] = &["~/.config/rafaeltab/config", "~/.rafaeltab"];
+static REPOSITORY_FILE_NAME: &str = ".rafaeltab";
+
+/// Where a configuration layer was loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigLayerKind {
+    /// Machine wide configuration
+    System,
+    /// The personal configuration of the current user
+    User,
+    /// Configuration found in a parent directory of the current directory
+    Repository,
+    /// A file listed in the `include` of another layer
+    Include,
+}
+
+impl ConfigLayerKind {
+    /// Whether writes may change files of this kind
+    pub fn is_writable(&self) -> bool {
+        matches!(self, ConfigLayerKind::User | ConfigLayerKind::Repository)
+    }
+}
+
+/// A single configuration file taking part in the merged configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLayer {
+    pub kind: ConfigLayerKind,
+    pub path: String,
+    pub data: LayerData,
+}
+
+/// The contents of a single configuration file.
+///
+/// Every field is optional so a layer only overrides what it defines.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(title = "rafaeltab configuration")]
+pub struct LayerData {
+    /// The JSON Schema this file is validated against
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Version of the file format, older files are migrated when they are loaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    /// Other configuration files to merge in, paths or glob patterns relative to this file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspaces: Option<Vec<Workspace>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmux: Option<LayerTmux>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<WorktreeConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clone: Option<CloneConfig>,
+    /// Project templates for `workspace new`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates: Option<Vec<Template>>,
+}
+
+/// The tmux section of a single configuration file
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(rename = "Tmux")]
+pub struct LayerTmux {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions: Option<Vec<Session>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_windows: Option<Vec<Window>>,
+}
+
+/// Locations that are searched for configuration layers
+pub struct LayerSearch<'a> {
+    /// The directory to start the repository-local search from
+    pub cwd: &'a Path,
+    /// The home directory of the current user
+    pub home: Option<&'a Path>,
+    /// The value of `$XDG_CONFIG_HOME`
+    pub xdg_config_home: Option<&'a Path>,
+}
+
+/// Find the configuration files that make up the layered configuration, ordered from lowest to
+/// highest precedence.
+pub fn discover_layer_paths(search: &LayerSearch) -> Vec<(ConfigLayerKind, PathBuf)> {
+    let mut result = vec![];
+
+    if let Some(path) = first_existing(SYSTEM_PATH_LOCATIONS_LINUX.iter().map(PathBuf::from)) {
+        result.push((ConfigLayerKind::System, path));
+    }
+
+    if let Some(path) = first_existing(user_locations(search).into_iter()) {
+        result.push((ConfigLayerKind::User, path));
+    }
+
+    if let Some(path) = find_repository_file(search.cwd, search.home)
+        && !result
+            .iter()
+            .any(|(_, existing)| same_file(existing, &path))
+    {
+        result.push((ConfigLayerKind::Repository, path));
+    }
+
+    result
+}
+
+/// The user files that are looked for, most preferred first and without their extension
+fn user_locations(search: &LayerSearch) -> Vec<PathBuf> {
+    let xdg_path = search
+        .xdg_config_home
+        .map(|xdg| xdg.join("rafaeltab").join("config"));
+    let user_paths = USER_PATH_LOCATIONS_LINUX
+        .iter()
+        .filter_map(|path| expand_home(path, search.home));
+    xdg_path.into_iter().chain(user_paths).collect()
+}
+
+/// Describe where configuration files are looked for, for when none was found
+pub fn describe_layer_search(search: &LayerSearch) -> String {
+    let mut lines = vec!["No config file found, create one of:".to_string()];
+    let locations = SYSTEM_PATH_LOCATIONS_LINUX
+        .iter()
+        .map(PathBuf::from)
+        .chain(user_locations(search));
+    for location in locations {
+        lines.push(format!("  {}.json", location.display()));
+    }
+    lines.push(format!(
+        "  {}.json in {} or one of its parents",
+        REPOSITORY_FILE_NAME,
+        search.cwd.display()
+    ));
+    lines.push(format!(
+        "The {} extensions work as well",
+        ConfigFormat::EXTENSIONS
+            .iter()
+            .map(|x| format!(".{}", x))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    lines.join("\n")
+}
+
+fn expand_home(path: &str, home: Option<&Path>) -> Option<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.map(|home| home.join(rest)),
+        None => Some(PathBuf::from(path)),
+    }
+}
+
+fn first_existing(paths: impl Iterator<Item = PathBuf>) -> Option<PathBuf> {
+    paths.flat_map(with_extensions).find(|path| path.is_file())
+}
+
+fn with_extensions(stem: PathBuf) -> impl Iterator<Item = PathBuf> {
+    ConfigFormat::EXTENSIONS.iter().map(move |extension| {
+        let mut path = stem.clone().into_os_string();
+        path.push(".");
+        path.push(extension);
+        PathBuf::from(path)
+    })
+}
+
+fn find_repository_file(cwd: &Path, home: Option<&Path>) -> Option<PathBuf> {
+    let home = home.and_then(|home| home.canonicalize().ok());
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+
+    for dir in cwd.ancestors() {
+        if home.as_deref() == Some(dir) {
+            return None;
+        }
+        if let Some(candidate) = first_existing(std::iter::once(dir.join(REPOSITORY_FILE_NAME))) {
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+/// Add the files included by every location, directly before the location that includes them.
+///
+/// `includes` returns the include patterns of a file. Files that were already added, for example
+/// because two files include the same file or files include each other, are skipped.
+pub fn expand_includes(
+    locations: &[(ConfigLayerKind, String)],
+    mut includes: impl FnMut(&str) -> Result<Vec<String>, io::Error>,
+) -> Result<Vec<(ConfigLayerKind, String)>, StorageError> {
+    let mut result = vec![];
+    let mut seen = HashSet::new();
+    for (kind, path) in locations {
+        add_with_includes(*kind, path, &mut includes, &mut seen, &mut result)?;
+    }
+    Ok(result)
+}
+
+fn add_with_includes(
+    kind: ConfigLayerKind,
+    path: &str,
+    includes: &mut impl FnMut(&str) -> Result<Vec<String>, io::Error>,
+    seen: &mut HashSet<PathBuf>,
+    result: &mut Vec<(ConfigLayerKind, String)>,
+) -> Result<(), StorageError> {
+    let key = Path::new(path)
+        .canonicalize()
+        .unwrap_or_else(|_| PathBuf::from(path));
+    if !seen.insert(key) {
+        return Ok(());
+    }
+
+    let patterns = includes(path).map_err(|err| StorageError::io(path, err))?;
+    let included = resolve_includes(path, &patterns).map_err(|err| StorageError::io(path, err))?;
+    for included in included {
+        add_with_includes(ConfigLayerKind::Include, &included, includes, seen, result)?;
+    }
+    result.push((kind, path.to_string()));
+    Ok(())
+}
+
+/// Resolve the include patterns of the file at `path` to the files they refer to
+pub fn resolve_includes(path: &str, patterns: &[String]) -> Result<Vec<String>, io::Error> {
+    let mut result = vec![];
+    for (index, pattern) in patterns.iter().enumerate() {
+        let files = resolve_include(path, pattern).map_err(|(kind, message)| {
+            io::Error::new(kind, format!("include[{}]: {}", index, message))
+        })?;
+        result.extend(files);
+    }
+    Ok(result)
+}
+
+/// Resolve a single include pattern of the file at `path`.
+///
+/// Patterns may use variables and `~`. Relative patterns are relative to the directory of the
+/// including file. Glob patterns may match nothing, plain paths must exist.
+pub fn resolve_include(path: &str, pattern: &str) -> Result<Vec<String>, (io::ErrorKind, String)> {
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let expanded = interpolate(pattern, &InterpolationContext::default())
+        .map_err(|err| (io::ErrorKind::InvalidData, err.to_string()))?;
+    let full = dir.join(&expanded).to_string_lossy().to_string();
+
+    if !full.contains(['*', '?', '[']) {
+        if !Path::new(&full).is_file() {
+            return Err((
+                io::ErrorKind::NotFound,
+                format!("included file {} does not exist", full),
+            ));
+        }
+        return Ok(vec![full]);
+    }
+
+    let matches = glob::glob(&full).map_err(|err| {
+        (
+            io::ErrorKind::InvalidData,
+            format!("invalid pattern `{}`: {}", pattern, err),
+        )
+    })?;
+    Ok(matches
+        .filter_map(Result::ok)
+        .filter(|x| x.is_file())
+        .map(|x| x.to_string_lossy().to_string())
+        .collect())
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// The merged view over all layers
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedLayers {
+    pub workspaces: Vec<Workspace>,
+    pub tmux: Tmux,
+    pub worktree: Option<WorktreeConfig>,
+    pub clone: Option<CloneConfig>,
+    pub templates: Vec<Template>,
+}
+
+/// Merge all layers into a single configuration, see the module documentation for the rules.
+pub fn merge_layers(layers: &[ConfigLayer]) -> MergedLayers {
+    let mut workspaces: Vec<Workspace> = vec![];
+    let mut sessions: Option<Vec<Session>> = None;
+    let mut default_windows: Vec<Window> = vec![];
+    let mut worktree: Option<WorktreeConfig> = None;
+    let mut clone: Option<CloneConfig> = None;
+    let mut templates: Vec<Template> = vec![];
+
+    for layer in layers {
+        for workspace in layer.data.workspaces.iter().flatten() {
+            upsert_by_key(&mut workspaces, workspace.clone(), |x| x.id.clone());
+        }
+
+        if let Some(tmux) = &layer.data.tmux {
+            if let Some(layer_sessions) = &tmux.sessions {
+                let merged_sessions = sessions.get_or_insert_with(Vec::new);
+                for session in layer_sessions {
+                    upsert_by_key(merged_sessions, session.clone(), session_key);
+                }
+            }
+            if let Some(windows) = &tmux.default_windows {
+                default_windows = windows.clone();
+            }
+        }
+
+        if let Some(layer_worktree) = &layer.data.worktree {
+            worktree = Some(merge_worktree(worktree.as_ref(), layer_worktree));
+        }
+
+        if let Some(layer_clone) = &layer.data.clone {
+            let merged = clone.get_or_insert_with(CloneConfig::default);
+            if layer_clone.path.is_some() {
+                merged.path = layer_clone.path.clone();
+            }
+        }
+
+        for template in layer.data.templates.iter().flatten() {
+            upsert_by_key(&mut templates, template.clone(), |x| x.name.clone());
+        }
+    }
+
+    MergedLayers {
+        workspaces,
+        tmux: Tmux {
+            sessions,
+            default_windows,
+        },
+        worktree,
+        clone,
+        templates,
+    }
+}
+
+fn upsert_by_key<T>(items: &mut Vec<T>, item: T, key: impl Fn(&T) -> String) {
+    let item_key = key(&item);
+    match items.iter_mut().find(|x| key(x) == item_key) {
+        Some(existing) => *existing = item,
+        None => items.push(item),
+    }
+}
+
+fn session_key(session: &Session) -> String {
+    match session {
+        Session::Workspace(workspace) => format!("workspace:{}", workspace.workspace),
+        Session::Path(path) => format!("path:{}", path.name),
+    }
+}
+
+fn merge_worktree(lower: Option<&WorktreeConfig>, higher: &WorktreeConfig) -> WorktreeConfig {
+    let mut merged = lower.cloned().unwrap_or_default();
+    extend_unique(&mut merged.symlink_files, &higher.symlink_files);
+    extend_unique(&mut merged.on_create, &higher.on_create);
+    extend_unique(&mut merged.on_destroy, &higher.on_destroy);
+    if higher.tmux.is_some() {
+        merged.tmux = higher.tmux;
+    }
+    merged
+}
+
+fn extend_unique(target: &mut Vec<String>, items: &[String]) {
+    for item in items {
+        if !target.contains(item) {
+            target.push(item.clone());
+        }
+    }
+}
+
+/// Index of the layer that receives values no layer owns yet
+fn primary_layer(layers: &[ConfigLayer]) -> Option<usize> {
+    layers
+        .iter()
+        .position(|x| x.kind == ConfigLayerKind::User)
+        .or_else(|| layers.iter().rposition(|x| x.kind.is_writable()))
+}
+
+/// Index of the layer that receives a changed value, given the highest layer that defines it.
+///
+/// A writable owner receives the value itself, otherwise the first writable layer above it does
+/// so the change overrides the read-only value. Values without an owner go to the primary layer.
+fn write_target(
+    layers: &[ConfigLayer],
+    owner: Option<usize>,
+    key: &str,
+) -> Result<usize, StorageError> {
+    let target = match owner {
+        Some(index) => (index..layers.len()).find(|x| layers[*x].kind.is_writable()),
+        None => primary_layer(layers),
+    };
+    target.ok_or_else(|| StorageError::NoWritableLayer {
+        key: key.to_string(),
+    })
+}
+
+fn read_only(layer: &ConfigLayer, key: impl Into<String>) -> StorageError {
+    StorageError::ReadOnly {
+        path: layer.path.clone(),
+        key: key.into(),
+    }
+}
+
+/// Route a new list of items back to the layers that own them.
+///
+/// Items keep their position in the layer that owns them, items that are no longer present are
+/// removed from every layer and items that no layer owns yet are appended to the primary layer.
+/// Changed items owned by a read-only layer are appended to the first writable layer above it,
+/// removing an item that a read-only layer defines is an error.
+/// Returns the new list for every layer, or `None` when the layer did not change.
+fn split_by_owner<T: Clone + PartialEq>(
+    layers: &[ConfigLayer],
+    layer_items: &[Option<&Vec<T>>],
+    value: &[T],
+    key: impl Fn(&T) -> String,
+) -> Result<Vec<Option<Vec<T>>>, StorageError> {
+    let owner = |item_key: &str| {
+        layer_items
+            .iter()
+            .rposition(|items| items.is_some_and(|items| items.iter().any(|x| key(x) == item_key)))
+    };
+
+    let mut results: Vec<Vec<T>> = vec![vec![]; layer_items.len()];
+    for (index, items) in layer_items.iter().enumerate() {
+        let writable = layers[index].kind.is_writable();
+        for item in items.iter().flat_map(|x| x.iter()) {
+            let item_key = key(item);
+            let new_item = value.iter().find(|x| key(x) == item_key);
+            match new_item {
+                None if !writable => return Err(read_only(&layers[index], item_key)),
+                None => {}
+                Some(new_item) if writable && owner(&item_key) == Some(index) => {
+                    results[index].push(new_item.clone())
+                }
+                Some(_) => results[index].push(item.clone()),
+            }
+        }
+    }
+
+    for item in value {
+        let item_key = key(item);
+        let item_owner = owner(&item_key);
+        if let Some(index) = item_owner {
+            let unchanged = layer_items[index]
+                .is_some_and(|items| items.iter().any(|x| key(x) == item_key && x == item));
+            if layers[index].kind.is_writable() || unchanged {
+                continue;
+            }
+        }
+        let target = write_target(layers, item_owner, &item_key)?;
+        results[target].push(item.clone());
+    }
+
+    Ok(layer_items
+        .iter()
+        .zip(results)
+        .map(|(items, result)| match items {
+            Some(existing) if **existing == result => None,
+            None if result.is_empty() => None,
+            _ => Some(result),
+        })
+        .collect())
+}
+
+/// Apply a new list of workspaces to the layers.
+///
+/// Returns the indices of the layers that changed.
+pub fn write_workspaces(
+    layers: &mut [ConfigLayer],
+    value: &[Workspace],
+) -> Result<Vec<usize>, StorageError> {
+    let layer_items: Vec<Option<&Vec<Workspace>>> =
+        layers.iter().map(|x| x.data.workspaces.as_ref()).collect();
+    let updates = split_by_owner(layers, &layer_items, value, |x| x.id.clone())?;
+
+    let mut changed = vec![];
+    for (index, update) in updates.into_iter().enumerate() {
+        if let Some(workspaces) = update {
+            layers[index].data.workspaces = Some(workspaces);
+            changed.push(index);
+        }
+    }
+    Ok(changed)
+}
+
+/// Apply a new list of templates to the layers.
+///
+/// Returns the indices of the layers that changed.
+pub fn write_templates(
+    layers: &mut [ConfigLayer],
+    value: &[Template],
+) -> Result<Vec<usize>, StorageError> {
+    let layer_items: Vec<Option<&Vec<Template>>> =
+        layers.iter().map(|x| x.data.templates.as_ref()).collect();
+    let updates = split_by_owner(layers, &layer_items, value, |x| x.name.clone())?;
+
+    let mut changed = vec![];
+    for (index, update) in updates.into_iter().enumerate() {
+        if let Some(templates) = update {
+            layers[index].data.templates = Some(templates);
+            changed.push(index);
+        }
+    }
+    Ok(changed)
+}
+
+/// Apply new tmux settings to the layers.
+///
+/// Returns the indices of the layers that changed.
+pub fn write_tmux(layers: &mut [ConfigLayer], value: &Tmux) -> Result<Vec<usize>, StorageError> {
+    let mut changed = vec![];
+
+    let layer_sessions: Vec<Option<&Vec<Session>>> = layers
+        .iter()
+        .map(|x| x.data.tmux.as_ref().and_then(|tmux| tmux.sessions.as_ref()))
+        .collect();
+    let sessions = value.sessions.clone().unwrap_or_default();
+    let updates = split_by_owner(layers, &layer_sessions, &sessions, session_key)?;
+    for (index, update) in updates.into_iter().enumerate() {
+        if let Some(sessions) = update {
+            let tmux = layers[index]
+                .data
+                .tmux
+                .get_or_insert_with(LayerTmux::default);
+            tmux.sessions = Some(sessions);
+            changed.push(index);
+        }
+    }
+
+    let merged_windows = merge_layers(layers).tmux.default_windows;
+    if merged_windows != value.default_windows {
+        let owner = layers.iter().rposition(|x| {
+            x.data
+                .tmux
+                .as_ref()
+                .is_some_and(|tmux| tmux.default_windows.is_some())
+        });
+        let owner = write_target(layers, owner, "tmux.defaultWindows")?;
+        let tmux = layers[owner]
+            .data
+            .tmux
+            .get_or_insert_with(LayerTmux::default);
+        tmux.default_windows = Some(value.default_windows.clone());
+        if !changed.contains(&owner) {
+            changed.push(owner);
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Apply a new global worktree configuration to the layers.
+///
+/// List entries that are no longer present are removed from every layer, new entries and the
+/// `tmux` setting are written to the highest writable layer at or above the highest layer that
+/// defines a worktree section. Removing an entry from a read-only layer is an error.
+/// Returns the indices of the layers that changed.
+pub fn write_worktree(
+    layers: &mut [ConfigLayer],
+    value: &Option<WorktreeConfig>,
+) -> Result<Vec<usize>, StorageError> {
+    if merge_layers(layers).worktree == *value {
+        return Ok(vec![]);
+    }
+
+    let mut changed = vec![];
+    let Some(value) = value else {
+        if let Some(layer) = layers
+            .iter()
+            .find(|x| !x.kind.is_writable() && x.data.worktree.is_some())
+        {
+            return Err(read_only(layer, "worktree"));
+        }
+        for (index, layer) in layers.iter_mut().enumerate() {
+            if layer.data.worktree.take().is_some() {
+                changed.push(index);
+            }
+        }
+        return Ok(changed);
+    };
+
+    for layer in layers.iter().filter(|x| !x.kind.is_writable()) {
+        let Some(worktree) = &layer.data.worktree else {
+            continue;
+        };
+        let removed = subtract(&worktree.symlink_files, &value.symlink_files)
+            .into_iter()
+            .chain(subtract(&worktree.on_create, &value.on_create))
+            .chain(subtract(&worktree.on_destroy, &value.on_destroy))
+            .next();
+        if let Some(entry) = removed {
+            return Err(read_only(layer, format!("worktree entry '{}'", entry)));
+        }
+    }
+
+    let owner = layers.iter().rposition(|x| x.data.worktree.is_some());
+    let owner = write_target(layers, owner, "worktree")?;
+
+    for (index, layer) in layers.iter_mut().enumerate() {
+        if index == owner || !layer.kind.is_writable() {
+            continue;
+        }
+        if let Some(worktree) = &mut layer.data.worktree {
+            let before = worktree.clone();
+            worktree
+                .symlink_files
+                .retain(|x| value.symlink_files.contains(x));
+            worktree.on_create.retain(|x| value.on_create.contains(x));
+            worktree.on_destroy.retain(|x| value.on_destroy.contains(x));
+            if *worktree != before {
+                changed.push(index);
+            }
+        }
+    }
+
+    let others = layers
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != owner)
+        .filter_map(|(_, layer)| layer.data.worktree.as_ref())
+        .fold(None, |acc: Option<WorktreeConfig>, x| {
+            Some(merge_worktree(acc.as_ref(), x))
+        })
+        .unwrap_or_default();
+
+    let owned = WorktreeConfig {
+        symlink_files: subtract(&value.symlink_files, &others.symlink_files),
+        on_create: subtract(&value.on_create, &others.on_create),
+        on_destroy: subtract(&value.on_destroy, &others.on_destroy),
+        tmux: if value.tmux == others.tmux {
+            None
+        } else {
+            value.tmux
+        },
+    };
+    if layers[owner].data.worktree.as_ref() != Some(&owned) {
+        layers[owner].data.worktree = Some(owned);
+        changed.push(owner);
+    }
+
+    Ok(changed)
+}
+
+/// Apply new clone settings to the layers.
+///
+/// The settings are written to the highest writable layer at or above the highest layer that
+/// defines a clone section, removing them removes the section from every layer.
+/// Returns the indices of the layers that changed.
+pub fn write_clone(
+    layers: &mut [ConfigLayer],
+    value: &Option<CloneConfig>,
+) -> Result<Vec<usize>, StorageError> {
+    if merge_layers(layers).clone == *value {
+        return Ok(vec![]);
+    }
+
+    let Some(value) = value else {
+        if let Some(layer) = layers
+            .iter()
+            .find(|x| !x.kind.is_writable() && x.data.clone.is_some())
+        {
+            return Err(read_only(layer, "clone"));
+        }
+        let mut changed = vec![];
+        for (index, layer) in layers.iter_mut().enumerate() {
+            if layer.data.clone.take().is_some() {
+                changed.push(index);
+            }
+        }
+        return Ok(changed);
+    };
+
+    let owner = layers.iter().rposition(|x| x.data.clone.is_some());
+    let owner = write_target(layers, owner, "clone")?;
+    layers[owner].data.clone = Some(value.clone());
+    Ok(vec![owner])
+}
+
+fn subtract(items: &[String], other: &[String]) -> Vec<String> {
+    items
+        .iter()
+        .filter(|x| !other.contains(x))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::storage::tmux::{PathSession, WorkspaceSession};
+
+    fn workspace(id: &str, name: &str) -> Workspace {
+        Workspace {
+            root: format!("~/{}", id),
+            id: id.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn window(name: &str) -> Window {
+        Window {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn layer(kind: ConfigLayerKind, data: LayerData) -> ConfigLayer {
+        ConfigLayer {
+            kind,
+            path: format!("{:?}.json", kind),
+            data,
+        }
+    }
+
+    fn layers_factory() -> Vec<ConfigLayer> {
+        vec![
+            layer(
+                ConfigLayerKind::System,
+                LayerData {
+                    workspaces: Some(vec![workspace("shared", "System Shared")]),
+                    tmux: Some(LayerTmux {
+                        sessions: None,
+                        default_windows: Some(vec![window("system")]),
+                    }),
+                    worktree: Some(WorktreeConfig {
+                        symlink_files: vec![".env".to_string()],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ),
+            layer(
+                ConfigLayerKind::User,
+                LayerData {
+                    workspaces: Some(vec![
+                        workspace("home", "Home"),
+                        workspace("shared", "User Shared"),
+                    ]),
+                    tmux: Some(LayerTmux {
+                        sessions: Some(vec![Session::Path(PathSession {
+                            windows: vec![window("zsh")],
+                            path: "/usr/bin".to_string(),
+                            name: "binaries".to_string(),
+                            ..Default::default()
+                        })]),
+                        default_windows: None,
+                    }),
+                    worktree: None,
+                    ..Default::default()
+                },
+            ),
+            layer(
+                ConfigLayerKind::Repository,
+                LayerData {
+                    workspaces: Some(vec![workspace("repo", "Repo")]),
+                    tmux: None,
+                    worktree: Some(WorktreeConfig {
+                        symlink_files: vec![".env".to_string(), ".env.local".to_string()],
+                        on_create: vec!["pnpm install".to_string()],
+                        on_destroy: vec![],
+                        tmux: Some(false),
+                    }),
+                    ..Default::default()
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn should_take_clone_path_from_highest_layer_and_write_it_back_there() {
+        let mut layers = layers_factory();
+        layers[0].data.clone = Some(CloneConfig {
+            path: Some("/src/{repo}".to_string()),
+        });
+        layers[1].data.clone = Some(CloneConfig { path: None });
+
+        assert_eq!(
+            merge_layers(&layers).clone.unwrap().path.as_deref(),
+            Some("/src/{repo}")
+        );
+
+        let value = Some(CloneConfig {
+            path: Some("~/code/{owner}/{repo}".to_string()),
+        });
+        let changed = write_clone(&mut layers, &value).unwrap();
+
+        assert_eq!(changed, vec![1]);
+        assert_eq!(merge_layers(&layers).clone, value);
+    }
+
+    #[test]
+    fn should_replace_workspaces_with_same_id_from_higher_layer() {
+        let merged = merge_layers(&layers_factory());
+
+        let ids: Vec<&str> = merged.workspaces.iter().map(|x| x.id.as_str()).collect();
+        assert_eq!(ids, vec!["shared", "home", "repo"]);
+        assert_eq!(merged.workspaces[0].name, "User Shared");
+    }
+
+    #[test]
+    fn should_take_default_windows_from_highest_layer_that_sets_them() {
+        let merged = merge_layers(&layers_factory());
+
+        assert_eq!(merged.tmux.default_windows, vec![window("system")]);
+    }
+
+    #[test]
+    fn should_union_worktree_lists_and_override_tmux() {
+        let merged = merge_layers(&layers_factory());
+        let worktree = merged.worktree.unwrap();
+
+        assert_eq!(worktree.symlink_files, vec![".env", ".env.local"]);
+        assert_eq!(worktree.on_create, vec!["pnpm install"]);
+        assert_eq!(worktree.tmux, Some(false));
+    }
+
+    #[test]
+    fn should_write_changed_workspace_to_owning_layer() {
+        let mut layers = layers_factory();
+        let mut workspaces = merge_layers(&layers).workspaces;
+        workspaces[2].name = "Renamed Repo".to_string();
+
+        let changed = write_workspaces(&mut layers, &workspaces).unwrap();
+
+        assert_eq!(changed, vec![2]);
+        assert_eq!(
+            layers[2].data.workspaces.as_ref().unwrap()[0].name,
+            "Renamed Repo"
+        );
+    }
+
+    #[test]
+    fn should_write_new_workspace_to_user_layer() {
+        let mut layers = layers_factory();
+        let mut workspaces = merge_layers(&layers).workspaces;
+        workspaces.push(workspace("new", "New"));
+
+        let changed = write_workspaces(&mut layers, &workspaces).unwrap();
+
+        assert_eq!(changed, vec![1]);
+        assert_eq!(layers[1].data.workspaces.as_ref().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn should_remove_workspace_from_every_writable_layer() {
+        let mut layers = layers_factory();
+        layers[2]
+            .data
+            .workspaces
+            .as_mut()
+            .unwrap()
+            .push(workspace("home", "Repo Home"));
+        let workspaces: Vec<Workspace> = merge_layers(&layers)
+            .workspaces
+            .into_iter()
+            .filter(|x| x.id != "home")
+            .collect();
+
+        let changed = write_workspaces(&mut layers, &workspaces).unwrap();
+
+        assert_eq!(changed, vec![1, 2]);
+        assert_eq!(merge_layers(&layers).workspaces, workspaces);
+    }
+
+    #[test]
+    fn should_refuse_to_remove_workspace_defined_in_system_layer() {
+        let mut layers = layers_factory();
+        let before = layers.clone();
+        let workspaces: Vec<Workspace> = merge_layers(&layers)
+            .workspaces
+            .into_iter()
+            .filter(|x| x.id != "shared")
+            .collect();
+
+        let result = write_workspaces(&mut layers, &workspaces);
+
+        assert!(matches!(
+            result,
+            Err(StorageError::ReadOnly { ref path, ref key }) if path == "System.json" && key == "shared"
+        ));
+        assert_eq!(layers, before);
+    }
+
+    #[test]
+    fn should_write_changed_system_workspace_to_user_layer() {
+        let mut layers = layers_factory();
+        layers[0]
+            .data
+            .workspaces
+            .as_mut()
+            .unwrap()
+            .push(workspace("system", "System"));
+        let mut workspaces = merge_layers(&layers).workspaces;
+        let system = workspaces.iter_mut().find(|x| x.id == "system").unwrap();
+        system.name = "Renamed System".to_string();
+
+        let changed = write_workspaces(&mut layers, &workspaces).unwrap();
+
+        assert_eq!(changed, vec![1]);
+        assert_eq!(
+            layers[0].data.workspaces.as_ref().unwrap()[1].name,
+            "System"
+        );
+        assert_eq!(merge_layers(&layers).workspaces, workspaces);
+    }
+
+    #[test]
+    fn should_write_changed_included_workspace_to_including_layer() {
+        let mut layers = layers_factory();
+        layers.insert(
+            2,
+            layer(
+                ConfigLayerKind::Include,
+                LayerData {
+                    workspaces: Some(vec![workspace("team", "Team")]),
+                    ..Default::default()
+                },
+            ),
+        );
+        let mut workspaces = merge_layers(&layers).workspaces;
+        let team = workspaces.iter_mut().find(|x| x.id == "team").unwrap();
+        team.name = "Renamed Team".to_string();
+
+        let changed = write_workspaces(&mut layers, &workspaces).unwrap();
+
+        assert_eq!(changed, vec![3]);
+        assert_eq!(layers[2].data.workspaces.as_ref().unwrap()[0].name, "Team");
+        assert_eq!(merge_layers(&layers).workspaces, workspaces);
+
+        let workspaces: Vec<Workspace> =
+            workspaces.into_iter().filter(|x| x.id != "team").collect();
+        let result = write_workspaces(&mut layers, &workspaces);
+
+        assert!(matches!(
+            result,
+            Err(StorageError::ReadOnly { ref path, .. }) if path == "Include.json"
+        ));
+    }
+
+    #[test]
+    fn should_write_default_windows_over_system_layer_to_user_layer() {
+        let mut layers = layers_factory();
+        let mut tmux = merge_layers(&layers).tmux;
+        tmux.default_windows.push(window("extra"));
+
+        let changed = write_tmux(&mut layers, &tmux).unwrap();
+
+        assert_eq!(changed, vec![1]);
+        assert_eq!(
+            layers[0].data.tmux.as_ref().unwrap().default_windows,
+            Some(vec![window("system")])
+        );
+        assert_eq!(merge_layers(&layers).tmux, tmux);
+    }
+
+    #[test]
+    fn should_write_new_session_to_user_layer() {
+        let mut layers = layers_factory();
+        let mut tmux = merge_layers(&layers).tmux;
+        tmux.sessions
+            .get_or_insert_with(Vec::new)
+            .push(Session::Workspace(WorkspaceSession {
+                windows: vec![window("nvim")],
+                workspace: "repo".to_string(),
+                name: None,
+            }));
+
+        let changed = write_tmux(&mut layers, &tmux).unwrap();
+
+        assert_eq!(changed, vec![1]);
+        assert_eq!(merge_layers(&layers).tmux, tmux);
+    }
+
+    #[test]
+    fn should_write_worktree_additions_to_highest_defining_layer() {
+        let mut layers = layers_factory();
+        let mut worktree = merge_layers(&layers).worktree.unwrap();
+        worktree.on_destroy.push("docker compose down".to_string());
+        worktree.symlink_files.retain(|x| x != ".env.local");
+
+        let changed = write_worktree(&mut layers, &Some(worktree.clone())).unwrap();
+
+        assert_eq!(changed, vec![2]);
+        assert_eq!(
+            layers[2].data.worktree.as_ref().unwrap().on_destroy,
+            vec!["docker compose down"]
+        );
+        assert_eq!(merge_layers(&layers).worktree, Some(worktree));
+    }
+
+    #[test]
+    fn should_refuse_to_remove_worktree_entry_defined_in_system_layer() {
+        let mut layers = layers_factory();
+        let mut worktree = merge_layers(&layers).worktree.unwrap();
+        worktree.symlink_files.retain(|x| x != ".env");
+
+        let result = write_worktree(&mut layers, &Some(worktree));
+
+        assert!(matches!(result, Err(StorageError::ReadOnly { .. })));
+    }
+
+    #[test]
+    fn should_not_change_layers_when_value_is_unchanged() {
+        let mut layers = layers_factory();
+        let merged = merge_layers(&layers);
+
+        assert!(
+            write_workspaces(&mut layers, &merged.workspaces)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(write_tmux(&mut layers, &merged.tmux).unwrap().is_empty());
+        assert!(
+            write_worktree(&mut layers, &merged.worktree)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn should_place_included_files_below_including_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("team")).unwrap();
+        fs::write(dir.join("team").join("b.json"), "{}").unwrap();
+        fs::write(dir.join("team").join("a.json"), "{}").unwrap();
+        fs::write(dir.join("extra.json"), "{}").unwrap();
+        fs::write(dir.join("user.json"), "{}").unwrap();
+        let user = dir.join("user.json").to_string_lossy().to_string();
+        let path_of = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+        let result = expand_includes(&[(ConfigLayerKind::User, user.clone())], |path| {
+            Ok(match path {
+                x if x == user => vec!["team/*.json".to_string(), "extra.json".to_string()],
+                // Files including each other are only added once
+                x if x.ends_with("/a.json") => vec!["../user.json".to_string()],
+                _ => vec![],
+            })
+        })
+        .unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                (ConfigLayerKind::Include, path_of("team/a.json")),
+                (ConfigLayerKind::Include, path_of("team/b.json")),
+                (ConfigLayerKind::Include, path_of("extra.json")),
+                (ConfigLayerKind::User, user),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_missing_included_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let user = temp_dir
+            .path()
+            .join("user.json")
+            .to_string_lossy()
+            .to_string();
+
+        let result = resolve_includes(&user, &["missing.json".to_string()]);
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().starts_with("include[0]: included file"));
+        assert!(resolve_includes(&user, &["missing/*.json".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn should_describe_searched_locations() {
+        let search = LayerSearch {
+            cwd: Path::new("/src/api"),
+            home: Some(Path::new("/home/me")),
+            xdg_config_home: Some(Path::new("/home/me/.xdg")),
+        };
+
+        let result = describe_layer_search(&search);
+
+        assert!(result.contains("/home/me/.xdg/rafaeltab/config.json"));
+        assert!(result.contains("/home/me/.config/rafaeltab/config.json"));
+        assert!(result.contains("/home/me/.rafaeltab.json"));
+        assert!(result.contains(".rafaeltab.json in /src/api or one of its parents"));
+    }
+
+    #[test]
+    fn should_discover_repository_file_in_parent_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let repo = temp_dir.path().join("repo");
+        let nested = repo.join("src").join("nested");
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(home.join(".rafaeltab.json"), "{}").unwrap();
+        fs::write(repo.join(".rafaeltab.json"), "{}").unwrap();
+
+        let result = discover_layer_paths(&LayerSearch {
+            cwd: &nested,
+            home: Some(&home),
+            xdg_config_home: None,
+        });
+        let result: Vec<(ConfigLayerKind, PathBuf)> = result
+            .into_iter()
+            .filter(|(kind, _)| *kind != ConfigLayerKind::System)
+            .collect();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0],
+            (ConfigLayerKind::User, home.join(".rafaeltab.json"))
+        );
+        assert_eq!(result[1].0, ConfigLayerKind::Repository);
+        assert!(same_file(&result[1].1, &repo.join(".rafaeltab.json")));
+    }
+
+    #[test]
+    fn should_not_treat_home_file_as_repository_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let project = home.join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(home.join(".rafaeltab.json"), "{}").unwrap();
+
+        let result = discover_layer_paths(&LayerSearch {
+            cwd: &project,
+            home: Some(&home),
+            xdg_config_home: None,
+        });
+
+        assert!(
+            !result
+                .iter()
+                .any(|(kind, _)| *kind == ConfigLayerKind::Repository)
+        );
+    }
+
+    #[test]
+    fn should_discover_files_with_other_extensions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(home.join(".rafaeltab.yaml"), "{}").unwrap();
+        fs::write(repo.join(".rafaeltab.toml"), "").unwrap();
+
+        let result = discover_layer_paths(&LayerSearch {
+            cwd: &repo,
+            home: Some(&home),
+            xdg_config_home: None,
+        });
+
+        assert!(result.contains(&(ConfigLayerKind::User, home.join(".rafaeltab.yaml"))));
+        assert!(
+            result
+                .iter()
+                .any(|(kind, path)| *kind == ConfigLayerKind::Repository
+                    && path.ends_with(".rafaeltab.toml"))
+        );
+    }
+
+    #[test]
+    fn should_prefer_xdg_config_file_over_home_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let xdg = temp_dir.path().join("xdg");
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(xdg.join("rafaeltab")).unwrap();
+        fs::write(home.join(".rafaeltab.json"), "{}").unwrap();
+        fs::write(xdg.join("rafaeltab").join("config.json"), "{}").unwrap();
+
+        let result = discover_layer_paths(&LayerSearch {
+            cwd: &home,
+            home: Some(&home),
+            xdg_config_home: Some(&xdg),
+        });
+
+        assert!(result.contains(&(
+            ConfigLayerKind::User,
+            xdg.join("rafaeltab").join("config.json")
+        )));
+    }
+}
@@ -0,0 +1,266 @@
+//! Upgrades configuration files written by older versions of `rafaeltab`.
+//!
+//! Every file carries a `version` key, files without one are treated as version 0. When a file is
+//! older than [`CURRENT_VERSION`] the migrations are applied one version at a time on the raw
+//! document, after which it is parsed into the current types. The raw document is what gets
+//! written back, so keys this build does not know about are kept. Comments are not.
+
+use std::{fs, io, path::Path};
+
+use serde_json::{Map, Value};
+
+use crate::storage::{
+    kinds::{
+        atomic_file::{FileLock, write_atomic},
+        format::ConfigFormat,
+    },
+    layers::LayerData,
+};
+
+/// The version of the configuration format written by this build
+pub const CURRENT_VERSION: u32 = 1;
+
+/// A single step that upgrades a file from `from` to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut Value),
+}
+
+/// All migrations, ordered by the version they upgrade from
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "Add a version key and remove keys that are set to null",
+    apply: remove_nulls,
+}];
+
+/// The result of migrating the contents of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigratedConfig {
+    /// The version the file had before migrating
+    pub from: u32,
+    /// Descriptions of the migrations that were applied, in order
+    pub applied: Vec<&'static str>,
+    pub data: LayerData,
+    /// The migrated file, serialized in the format of the original
+    pub content: String,
+}
+
+/// A file that was migrated on disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigratedFile {
+    pub from: u32,
+    /// Where the original contents were saved
+    pub backup: String,
+}
+
+/// Migrate the contents of a file to the current version.
+///
+/// Returns `None` when the file is already up to date.
+pub fn migrate(format: ConfigFormat, content: &str) -> Result<Option<MigratedConfig>, io::Error> {
+    let mut value: Value = format.parse(content)?;
+    let from = file_version(&value)?;
+    if from == CURRENT_VERSION {
+        return Ok(None);
+    }
+
+    let mut applied = vec![];
+    for migration in MIGRATIONS.iter().filter(|x| x.from >= from) {
+        (migration.apply)(&mut value);
+        applied.push(migration.description);
+    }
+    if let Value::Object(map) = &mut value {
+        set_version(map);
+    }
+
+    let data: LayerData = serde_json::from_value(value.clone())?;
+    let content = format.serialize(&value)?;
+    Ok(Some(MigratedConfig {
+        from,
+        applied,
+        data,
+        content,
+    }))
+}
+
+/// Migrate a file on disk, writing a backup of the original next to it first.
+///
+/// Returns `None` when the file is already up to date.
+pub fn migrate_file(path: &str) -> Result<Option<MigratedFile>, io::Error> {
+    let _lock = FileLock::acquire(path)?;
+    // Read while holding the lock, another process may have migrated the file already
+    let original = fs::read_to_string(path)?;
+    let Some(migrated) = migrate(ConfigFormat::from_path(path), &original)? else {
+        return Ok(None);
+    };
+
+    let backup = backup_path(path, migrated.from);
+    fs::write(&backup, &original)?;
+    write_atomic(path, &migrated.content)?;
+
+    Ok(Some(MigratedFile {
+        from: migrated.from,
+        backup,
+    }))
+}
+
+/// Set the version key, placing it at the top of the file below `$schema`
+fn set_version(map: &mut Map<String, Value>) {
+    let rest = std::mem::take(map);
+    if let Some(schema) = rest.get("$schema") {
+        map.insert("$schema".to_string(), schema.clone());
+    }
+    map.insert("version".to_string(), Value::from(CURRENT_VERSION));
+    map.extend(
+        rest.into_iter()
+            .filter(|(key, _)| key != "$schema" && key != "version"),
+    );
+}
+
+/// Tell where the original of a migrated file went, and that its comments are only kept there
+pub fn backup_message(path: &str, backup: &str) -> String {
+    if ConfigFormat::from_path(path).has_comments() {
+        format!(
+            "comments were not kept, the original was saved to {}",
+            backup
+        )
+    } else {
+        format!("the original was saved to {}", backup)
+    }
+}
+
+fn file_version(value: &Value) -> Result<u32, io::Error> {
+    let version = match value.get("version") {
+        None | Some(Value::Null) => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|x| u32::try_from(x).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("version must be a positive number, found {}", version),
+                )
+            })?,
+    };
+
+    if version > CURRENT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "version {} is newer than the supported version {}, update rafaeltab to use this file",
+                version, CURRENT_VERSION
+            ),
+        ));
+    }
+    Ok(version)
+}
+
+/// Pick a backup path that does not exist yet, `<file>.v<version>.bak` when possible
+fn backup_path(path: &str, version: u32) -> String {
+    let backup = format!("{}.v{}.bak", path, version);
+    if !Path::new(&backup).exists() {
+        return backup;
+    }
+    (1..)
+        .map(|i| format!("{}.v{}.{}.bak", path, version, i))
+        .find(|x| !Path::new(x).exists())
+        .expect("an unused backup path exists")
+}
+
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, x| !x.is_null());
+            map.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNVERSIONED: &str = r#"{
+        "workspaces": [{ "id": "api", "name": "Api", "root": "~/api", "tags": null }],
+        "tmux": { "sessions": [], "defaultWindows": [{ "name": "zsh", "command": null }] },
+        "worktree": null
+    }"#;
+
+    #[test]
+    fn should_migrate_unversioned_files() {
+        let migrated = migrate(ConfigFormat::Json, UNVERSIONED).unwrap().unwrap();
+
+        assert_eq!(migrated.from, 0);
+        assert_eq!(migrated.applied.len(), 1);
+        assert_eq!(migrated.data.version, Some(CURRENT_VERSION));
+        assert!(!migrated.content.contains("null"));
+        assert!(migrated.content.contains("\"version\": 1"));
+    }
+
+    #[test]
+    fn should_keep_unknown_keys_and_their_order() {
+        let content = r#"{
+            "$schema": "./schema.json",
+            "workspaces": [{ "id": "api", "name": "Api", "root": "~/api", "owner": "team" }],
+            "experimental": { "enabled": true }
+        }"#;
+
+        let migrated = migrate(ConfigFormat::Json, content).unwrap().unwrap();
+
+        let value: Value = serde_json::from_str(&migrated.content).unwrap();
+        let keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        assert_eq!(
+            keys,
+            vec!["$schema", "version", "workspaces", "experimental"]
+        );
+        assert_eq!(value["workspaces"][0]["owner"], "team");
+        assert_eq!(value["experimental"]["enabled"], true);
+    }
+
+    #[test]
+    fn should_leave_current_files_alone() {
+        let content = r#"{ "version": 1, "workspaces": [] }"#;
+
+        assert_eq!(migrate(ConfigFormat::Json, content).unwrap(), None);
+    }
+
+    #[test]
+    fn should_refuse_newer_files() {
+        let content = format!(r#"{{ "version": {} }}"#, CURRENT_VERSION + 1);
+
+        let err = migrate(ConfigFormat::Json, &content).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("update rafaeltab"));
+    }
+
+    #[test]
+    fn should_keep_the_format_of_the_file() {
+        let content = "[[workspaces]]\nid = \"api\"\nname = \"Api\"\nroot = \"~/api\"\n";
+
+        let migrated = migrate(ConfigFormat::Toml, content).unwrap().unwrap();
+
+        assert!(migrated.content.starts_with("version = 1"));
+        let parsed: LayerData = ConfigFormat::Toml.parse(&migrated.content).unwrap();
+        assert_eq!(parsed, migrated.data);
+    }
+
+    #[test]
+    fn should_write_a_backup_before_migrating() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let path = path.to_str().unwrap();
+        fs::write(path, UNVERSIONED).unwrap();
+        fs::write(format!("{}.v0.bak", path), "older backup").unwrap();
+
+        let migrated = migrate_file(path).unwrap().unwrap();
+
+        assert_eq!(migrated.backup, format!("{}.v0.1.bak", path));
+        assert_eq!(fs::read_to_string(&migrated.backup).unwrap(), UNVERSIONED);
+        let data: LayerData = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(data.version, Some(CURRENT_VERSION));
+        assert_eq!(migrate_file(path).unwrap(), None);
+    }
+}
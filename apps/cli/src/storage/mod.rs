@@ -1,6 +1,18 @@
+pub mod clone;
+pub mod config;
+pub mod config_path;
+pub mod error;
+pub mod frecency;
 pub mod kinds;
+pub mod layers;
+pub mod migrations;
+pub mod schema;
+pub mod snapshot;
+pub mod status_cache;
 pub mod storage_interface;
+pub mod template;
 pub mod test;
 pub mod tmux;
+pub mod validation;
 pub mod workspace;
 pub mod worktree;
@@ -0,0 +1,65 @@
+//! JSON Schema for configuration files, generated from the storage types
+
+use schemars::r#gen::SchemaSettings;
+use serde_json::Value;
+
+use super::layers::LayerData;
+
+/// Generate the JSON Schema of a single configuration file.
+///
+/// Objects do not allow properties other than the ones they define, so editors report typos.
+pub fn config_schema() -> Value {
+    let generator = SchemaSettings::draft07().into_generator();
+    let schema = generator.into_root_schema_for::<LayerData>();
+    let mut schema = serde_json::to_value(schema).expect("Schema is always valid JSON");
+
+    close_objects(&mut schema);
+    schema
+}
+
+fn close_objects(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            if object.contains_key("properties") && !object.contains_key("additionalProperties") {
+                object.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+            object.values_mut().for_each(close_objects);
+        }
+        Value::Array(items) => items.iter_mut().for_each(close_objects),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+
+    #[test]
+    fn checked_in_schema_should_match_generated_schema() {
+        let schema_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("schemas")
+            .join("config-schema.json");
+        let checked_in: Value =
+            serde_json::from_str(&fs::read_to_string(schema_path).unwrap()).unwrap();
+
+        assert_eq!(
+            checked_in,
+            config_schema(),
+            "schemas/config-schema.json is out of date, regenerate it with `rafaeltab config schema`"
+        );
+    }
+
+    #[test]
+    fn should_not_allow_unknown_properties() {
+        let schema = config_schema();
+
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(
+            schema["definitions"]["WorktreeConfig"]["additionalProperties"],
+            false
+        );
+        assert!(schema["properties"]["$schema"].is_object());
+    }
+}
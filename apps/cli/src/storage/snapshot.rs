@@ -0,0 +1,65 @@
+//! Snapshots of running tmux sessions.
+//!
+//! `tmux save` records the windows and panes of every session so `tmux restore` can bring them
+//! back after a reboot. Like the usage history this is state rather than configuration, it is
+//! kept in its own file and never written to a configuration layer.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::storage_interface::Storage;
+
+/// Name of the snapshot `tmux save` and `tmux restore` use without `--name`
+pub const DEFAULT_SNAPSHOT: &str = "default";
+
+pub trait SnapshotStorage: Storage<Snapshots> {}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshots {
+    #[serde(default)]
+    pub snapshots: BTreeMap<String, Snapshot>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// When the snapshot was taken, in seconds since the unix epoch
+    pub saved_at: i64,
+    pub sessions: Vec<SessionSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSnapshot {
+    pub name: String,
+    pub path: String,
+    /// Id of the configured session, absent for sessions that were created by hand
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub windows: Vec<WindowSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowSnapshot {
+    pub name: String,
+    /// Layout string of the panes, as printed by `tmux list-windows`
+    pub layout: String,
+    #[serde(default)]
+    pub active: bool,
+    pub panes: Vec<PaneSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaneSnapshot {
+    pub cwd: String,
+    /// Command line of the program that was running in the pane, absent when it was a shell or
+    /// its arguments could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub active: bool,
+}
@@ -0,0 +1,44 @@
+//! Cached git state for `tmux status`.
+//!
+//! The tmux status line runs `tmux status` every few seconds for every client. The git state of a
+//! directory is kept here between those runs, so git only runs once it is out of date. Like the
+//! usage history this is state rather than configuration, it is kept in its own file.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::storage_interface::Storage;
+
+pub trait StatusCacheStorage: Storage<StatusCache> {}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCache {
+    /// Git state by the expanded path it was read in
+    #[serde(default)]
+    pub paths: BTreeMap<String, CachedGitStatus>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedGitStatus {
+    /// When git was asked, in seconds since the unix epoch
+    pub checked_at: i64,
+    /// Main worktree of the repository, absent outside of a repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Branch that is checked out, absent in detached HEAD state or outside of a repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Whether there are uncommitted changes or untracked files
+    #[serde(default)]
+    pub dirty: bool,
+}
+
+impl CachedGitStatus {
+    /// Whether the state was read less than `max_age` seconds before `now`
+    pub fn is_fresh(&self, now: i64, max_age: u64) -> bool {
+        now.saturating_sub(self.checked_at) < max_age as i64
+    }
+}
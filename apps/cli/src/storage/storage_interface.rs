@@ -1,6 +1,6 @@
-use std::io;
+use super::error::StorageError;
 
 pub trait Storage<TValue> {
     fn read(&self) -> TValue;
-    fn write(&self, value: &TValue) -> Result<(), io::Error>;
+    fn write(&self, value: &TValue) -> Result<(), StorageError>;
 }
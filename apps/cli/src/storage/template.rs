@@ -0,0 +1,45 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{storage_interface::Storage, tmux::Window, worktree::WorkspaceWorktreeConfig};
+
+/// Trait for storage that can read/write the templates of `workspace new`
+pub trait TemplateStorage: Storage<Vec<Template>> {}
+
+/// File in the root of a template with the settings it carries, it is not copied
+pub const TEMPLATE_MANIFEST: &str = "rafaeltab-template.json";
+
+/// A project template that `workspace new` creates workspaces from
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Template {
+    /// Unique name of the template
+    pub name: String,
+    /// Directory or git url to copy the files from. `{{name}}` and `{{id}}` in file contents and
+    /// file names are replaced by the name and id of the new workspace.
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tags of the new workspaces, added to the ones that are given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Worktree configuration of the new workspaces, replaces the one of the template manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<WorkspaceWorktreeConfig>,
+    /// Windows of the tmux session of the new workspaces, replaces the ones of the template
+    /// manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub windows: Option<Vec<Window>>,
+}
+
+/// The settings a template carries in its `rafaeltab-template.json`
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TemplateManifest {
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub worktree: Option<WorkspaceWorktreeConfig>,
+    #[serde(default)]
+    pub windows: Option<Vec<Window>>,
+}
@@ -1,5 +1,10 @@
+use std::cell::RefCell;
+
 use crate::storage::{
+    error::StorageError,
+    frecency::{Frecency, FrecencyStorage},
     storage_interface::Storage,
+    template::{Template, TemplateStorage},
     tmux::{Tmux, TmuxStorage},
     workspace::{Workspace, WorkspaceStorage},
     worktree::{WorktreeConfig, WorktreeStorage},
@@ -23,7 +28,7 @@ impl Storage<Vec<Workspace>> for MockWorkspaceStorage {
         self.data.clone()
     }
 
-    fn write(&self, _: &Vec<Workspace>) -> Result<(), std::io::Error> {
+    fn write(&self, _: &Vec<Workspace>) -> Result<(), StorageError> {
         Ok(())
     }
 }
@@ -34,7 +39,7 @@ impl Storage<Tmux> for MockTmuxStorage {
         self.data.clone()
     }
 
-    fn write(&self, _: &Tmux) -> Result<(), std::io::Error> {
+    fn write(&self, _: &Tmux) -> Result<(), StorageError> {
         Ok(())
     }
 }
@@ -45,7 +50,36 @@ impl Storage<Option<WorktreeConfig>> for MockWorktreeStorage {
         self.data.clone()
     }
 
-    fn write(&self, _: &Option<WorktreeConfig>) -> Result<(), std::io::Error> {
+    fn write(&self, _: &Option<WorktreeConfig>) -> Result<(), StorageError> {
         Ok(())
     }
 }
+
+/// Storage that keeps what is written to it, for tests that check the written value
+pub struct InMemoryStorage<T> {
+    pub data: RefCell<T>,
+}
+
+impl<T> InMemoryStorage<T> {
+    pub fn new(data: T) -> Self {
+        InMemoryStorage {
+            data: RefCell::new(data),
+        }
+    }
+}
+
+impl<T: Clone> Storage<T> for InMemoryStorage<T> {
+    fn read(&self) -> T {
+        self.data.borrow().clone()
+    }
+
+    fn write(&self, value: &T) -> Result<(), StorageError> {
+        self.data.replace(value.clone());
+        Ok(())
+    }
+}
+
+impl WorkspaceStorage for InMemoryStorage<Vec<Workspace>> {}
+impl TmuxStorage for InMemoryStorage<Tmux> {}
+impl TemplateStorage for InMemoryStorage<Vec<Template>> {}
+impl FrecencyStorage for InMemoryStorage<Frecency> {}
@@ -1,42 +1,116 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::storage_interface::Storage;
 
 pub trait TmuxStorage: Storage<Tmux> {}
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Tmux {
+    /// Sessions with custom windows, for a workspace or a path
     pub sessions: Option<Vec<Session>>,
+    /// Windows used for workspaces without a session of their own
     pub default_windows: Vec<Window>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum Session {
     Workspace(WorkspaceSession),
     Path(PathSession),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceSession {
     pub windows: Vec<Window>,
+    /// Id of the workspace this session is for
     pub workspace: String,
+    /// Session name, defaults to the name of the workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PathSession {
     pub windows: Vec<Window>,
+    /// Directory the session is started in
     pub path: String,
     pub name: String,
+    /// Environment variables for the session, they override the ones from `envFile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    /// Dotenv file to load environment variables from, relative to the path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Window {
     pub name: String,
+    /// Command to run in the window, a shell is started when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Environment variables for this window only, they override the ones of the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    /// How `panes` are placed, defaults to `horizontal`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split: Option<PaneSplit>,
+    /// Split the window into panes, `command` runs in the first pane when it has no command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panes: Option<Vec<Pane>>,
+    /// Tmux layout applied once the panes exist, such as `main-vertical`, `tiled` or a layout
+    /// string from `tmux list-windows`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+    /// Directory of the window, relative to the directory of the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Create the window without switching to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<bool>,
+    /// Switch to this window once the session is created, instead of the last window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<bool>,
+}
+
+/// How the panes of a split are placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PaneSplit {
+    /// Side by side
+    #[default]
+    Horizontal,
+    /// Above each other
+    Vertical,
+}
+
+/// A pane of a window, or a pane that is split into more panes
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Pane {
+    /// Command to run in the pane, a shell is started when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub command: Option<String>,
+    /// Directory of the pane, relative to the directory of the window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Percentage of the split the pane takes, panes without a size share the rest equally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+    /// Select this pane when the window is opened instead of the first pane
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<bool>,
+    /// How `panes` are placed, defaults to `horizontal`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split: Option<PaneSplit>,
+    /// Split this pane into more panes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panes: Option<Vec<Pane>>,
 }
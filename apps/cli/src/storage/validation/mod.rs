@@ -0,0 +1,964 @@
+//! Validation of configuration files.
+//!
+//! Files are checked against the generated schema, which catches unknown keys and wrong types,
+//! and against rules the schema can not express: unique workspace ids, sessions that refer to
+//! existing workspaces, workspace roots and included files that exist.
+
+pub mod positions;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    storage::{
+        kinds::format::ConfigFormat,
+        layers::{LayerData, resolve_include},
+        schema::config_schema,
+        tmux::{Pane, Session, Window},
+    },
+    utils::{
+        display::RafaeltabDisplayItem,
+        env::is_valid_name,
+        interpolation::{InterpolationContext, interpolate},
+        path::expand_path,
+    },
+};
+
+use positions::{LineIndex, Position, PositionIndex, join_index, join_key};
+
+/// A single problem found in a configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub file: String,
+    /// JSON path of the offending value, empty for the whole file
+    pub path: String,
+    pub position: Option<Position>,
+    pub message: String,
+}
+
+impl RafaeltabDisplayItem for ConfigProblem {
+    fn to_json(&self) -> Value {
+        json!({
+            "file": self.file,
+            "path": self.path,
+            "line": self.position.map(|x| x.line),
+            "column": self.position.map(|x| x.column),
+            "message": self.message,
+        })
+    }
+
+    fn to_pretty_string(&self) -> String {
+        let location = match self.position {
+            Some(position) => format!("{}:{}:{}", self.file, position.line, position.column),
+            None => self.file.clone(),
+        };
+        if self.path.is_empty() {
+            format!("{}: {}", location, self.message)
+        } else {
+            format!("{}: {}: {}", location, self.path, self.message)
+        }
+    }
+}
+
+struct ParsedFile {
+    path: String,
+    positions: PositionIndex,
+    data: Option<LayerData>,
+}
+
+/// Validate configuration files that are merged together, from lowest to highest precedence
+pub fn validate_files(paths: &[String]) -> Vec<ConfigProblem> {
+    let schema = config_schema();
+    let mut problems = vec![];
+    let mut files = vec![];
+
+    for path in paths {
+        let (file, file_problems) = parse_file(path, &schema);
+        problems.extend(file_problems);
+        if let Some(file) = file {
+            files.push(file);
+        }
+    }
+
+    // Sessions may refer to workspaces defined in any of the files
+    let workspace_ids: HashSet<String> = files
+        .iter()
+        .filter_map(|x| x.data.as_ref())
+        .flat_map(|x| x.workspaces.iter().flatten())
+        .map(|x| x.id.clone())
+        .collect();
+
+    for file in &files {
+        let Some(data) = &file.data else {
+            continue;
+        };
+        for (path, message) in check_rules(data, &workspace_ids) {
+            problems.push(ConfigProblem {
+                file: file.path.clone(),
+                position: file.positions.locate(&path),
+                path,
+                message,
+            });
+        }
+        for (index, pattern) in data.include.iter().flatten().enumerate() {
+            if let Err((_, message)) = resolve_include(&file.path, pattern) {
+                let path = join_index("include", index);
+                problems.push(ConfigProblem {
+                    file: file.path.clone(),
+                    position: file.positions.locate(&path),
+                    path,
+                    message,
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+/// Check a configuration value against the schema, returning the path and message of every problem
+pub fn check_config_value(value: &Value) -> Vec<(String, String)> {
+    let schema = config_schema();
+    let mut problems = vec![];
+    check_schema(&schema, &schema, value, "", &mut problems);
+    problems
+}
+
+fn parse_file(path: &str, schema: &Value) -> (Option<ParsedFile>, Vec<ConfigProblem>) {
+    let problem = |path: &str, position: Option<Position>, message: String| ConfigProblem {
+        file: path.to_string(),
+        path: String::new(),
+        position,
+        message,
+    };
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => return (None, vec![problem(path, None, err.to_string())]),
+    };
+    let format = ConfigFormat::from_path(path);
+    let value = match parse_value(format, &content) {
+        Ok(value) => value,
+        Err((position, message)) => return (None, vec![problem(path, position, message)]),
+    };
+
+    let positions = PositionIndex::build(format, &content);
+    let mut schema_problems = vec![];
+    check_schema(schema, schema, &value, "", &mut schema_problems);
+    let mut problems: Vec<ConfigProblem> = schema_problems
+        .into_iter()
+        .map(|(value_path, message)| ConfigProblem {
+            file: path.to_string(),
+            position: positions.locate(&value_path),
+            path: value_path,
+            message,
+        })
+        .collect();
+
+    let data = match serde_json::from_value::<LayerData>(value) {
+        Ok(data) => Some(data),
+        Err(err) => {
+            if problems.is_empty() {
+                problems.push(problem(path, None, err.to_string()));
+            }
+            None
+        }
+    };
+
+    let file = ParsedFile {
+        path: path.to_string(),
+        positions,
+        data,
+    };
+    (Some(file), problems)
+}
+
+fn parse_value(format: ConfigFormat, content: &str) -> Result<Value, (Option<Position>, String)> {
+    match format {
+        ConfigFormat::Json => serde_json::from_str(content).map_err(|err| {
+            let position = Position {
+                line: err.line(),
+                column: err.column(),
+            };
+            (Some(position), err.to_string())
+        }),
+        ConfigFormat::Toml => toml::from_str(content).map_err(|err| {
+            let position = err
+                .span()
+                .map(|span| LineIndex::new(content).position(span.start));
+            (position, err.message().to_string())
+        }),
+        ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|err| {
+            let position = err.location().map(|location| Position {
+                line: location.line(),
+                column: location.column(),
+            });
+            (position, err.to_string())
+        }),
+    }
+}
+
+/// Check a value against a schema, collecting the path and message of every problem
+fn check_schema(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    problems: &mut Vec<(String, String)>,
+) {
+    if let Some(reference) = schema.get("$ref").and_then(|x| x.as_str()) {
+        if let Some(schema) = reference.strip_prefix('#').and_then(|x| root.pointer(x)) {
+            check_schema(root, schema, value, path, problems);
+        }
+        return;
+    }
+
+    let alternatives = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(|x| x.as_array());
+    if let Some(alternatives) = alternatives {
+        // Report the problems of the alternative that came closest to matching
+        let best = alternatives
+            .iter()
+            .map(|alternative| {
+                let mut alternative_problems = vec![];
+                check_schema(root, alternative, value, path, &mut alternative_problems);
+                alternative_problems
+            })
+            .min_by_key(|x| x.len());
+        if let Some(best) = best {
+            problems.extend(best);
+        }
+        return;
+    }
+
+    if let Some(types) = schema.get("type") {
+        let types: Vec<&str> = match types {
+            Value::String(x) => vec![x.as_str()],
+            Value::Array(x) => x.iter().filter_map(|x| x.as_str()).collect(),
+            _ => vec![],
+        };
+        if !types.iter().any(|x| type_matches(x, value)) {
+            problems.push((
+                path.to_string(),
+                format!(
+                    "expected {}, found {}",
+                    types.join(" or "),
+                    type_name(value)
+                ),
+            ));
+            return;
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(|x| x.as_object());
+            let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+            for (key, property_value) in object {
+                let property_path = join_key(path, key);
+                match properties.and_then(|x| x.get(key)) {
+                    Some(property_schema) => check_schema(
+                        root,
+                        property_schema,
+                        property_value,
+                        &property_path,
+                        problems,
+                    ),
+                    None if closed => {
+                        let known: Vec<&String> =
+                            properties.map(|x| x.keys().collect()).unwrap_or_default();
+                        problems.push((property_path, unknown_key_message(key, &known)));
+                    }
+                    None => {}
+                }
+            }
+            for required in schema
+                .get("required")
+                .and_then(|x| x.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|x| x.as_str())
+            {
+                if !object.contains_key(required) {
+                    problems.push((
+                        path.to_string(),
+                        format!("missing required key `{}`", required),
+                    ));
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check_schema(root, item_schema, item, &join_index(path, index), problems);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn unknown_key_message(key: &str, known: &[&String]) -> String {
+    let suggestion = known
+        .iter()
+        .map(|x| (x, edit_distance(&key.to_lowercase(), &x.to_lowercase())))
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance);
+    match suggestion {
+        Some((suggestion, _)) => format!("unknown key `{}`, did you mean `{}`?", key, suggestion),
+        None => format!("unknown key `{}`", key),
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Rules that can not be expressed in the schema
+fn check_rules(data: &LayerData, workspace_ids: &HashSet<String>) -> Vec<(String, String)> {
+    let mut problems = vec![];
+    let workspaces = data.workspaces.as_deref().unwrap_or_default();
+
+    let mut seen = HashSet::new();
+    let mut seen_aliases = HashSet::new();
+    for (index, workspace) in workspaces.iter().enumerate() {
+        let path = join_index("workspaces", index);
+        if !seen.insert(workspace.id.as_str()) {
+            problems.push((
+                join_key(&path, "id"),
+                format!("duplicate workspace id `{}`", workspace.id),
+            ));
+        }
+        for (alias_index, alias) in workspace.aliases.iter().flatten().enumerate() {
+            let alias_path = join_index(&join_key(&path, "aliases"), alias_index);
+            if workspace_ids.contains(alias) {
+                problems.push((
+                    alias_path,
+                    format!("alias `{}` is also a workspace id", alias),
+                ));
+            } else if !seen_aliases.insert(alias.as_str()) {
+                problems.push((alias_path, format!("duplicate alias `{}`", alias)));
+            }
+        }
+        match workspace.resolved_root() {
+            Ok(root) if !Path::new(&expand_path(&root)).exists() => problems.push((
+                join_key(&path, "root"),
+                format!("root `{}` does not exist", root),
+            )),
+            Ok(_) => {}
+            Err(err) => problems.push((join_key(&path, "root"), err.message)),
+        }
+        problems.extend(check_env(
+            &path,
+            workspace.env.as_ref(),
+            workspace.env_file.as_deref(),
+        ));
+        if let Some(worktree) = &workspace.worktree {
+            problems.extend(check_variables(&[
+                (
+                    join_key(&path, "worktree.symlinkFiles"),
+                    &worktree.symlink_files,
+                ),
+                (join_key(&path, "worktree.onCreate"), &worktree.on_create),
+                (join_key(&path, "worktree.onDestroy"), &worktree.on_destroy),
+            ]));
+        }
+    }
+
+    if let Some(worktree) = &data.worktree {
+        problems.extend(check_variables(&[
+            ("worktree.symlinkFiles".to_string(), &worktree.symlink_files),
+            ("worktree.onCreate".to_string(), &worktree.on_create),
+            ("worktree.onDestroy".to_string(), &worktree.on_destroy),
+        ]));
+    }
+
+    let default_windows = data
+        .tmux
+        .as_ref()
+        .and_then(|x| x.default_windows.as_deref())
+        .unwrap_or_default();
+    problems.extend(check_windows("tmux.defaultWindows", default_windows));
+
+    let sessions = data
+        .tmux
+        .as_ref()
+        .and_then(|x| x.sessions.as_deref())
+        .unwrap_or_default();
+    for (index, session) in sessions.iter().enumerate() {
+        let path = join_index("tmux.sessions", index);
+        match session {
+            Session::Workspace(session) => {
+                if !workspace_ids.contains(&session.workspace) {
+                    problems.push((
+                        join_key(&path, "workspace"),
+                        format!("no workspace with id `{}`", session.workspace),
+                    ));
+                }
+                problems.extend(check_windows(&join_key(&path, "windows"), &session.windows));
+            }
+            Session::Path(session) => {
+                let paths = [session.path.clone()];
+                problems.extend(check_variables(&[(join_key(&path, "path"), &paths)]));
+                problems.extend(check_env(
+                    &path,
+                    session.env.as_ref(),
+                    session.env_file.as_deref(),
+                ));
+                problems.extend(check_windows(&join_key(&path, "windows"), &session.windows));
+            }
+        }
+    }
+
+    if let Some(path) = data.clone.as_ref().and_then(|x| x.path.as_ref())
+        && let Err(err) = interpolate(path, &any_context())
+    {
+        problems.push(("clone.path".to_string(), err.message));
+    }
+
+    let mut seen_templates = HashSet::new();
+    for (index, template) in data.templates.iter().flatten().enumerate() {
+        let path = join_index("templates", index);
+        if !seen_templates.insert(template.name.as_str()) {
+            problems.push((
+                join_key(&path, "name"),
+                format!("duplicate template name `{}`", template.name),
+            ));
+        }
+        let sources = [template.source.clone()];
+        problems.extend(check_variables(&[(join_key(&path, "source"), &sources)]));
+        if let Some(windows) = &template.windows {
+            problems.extend(check_windows(&join_key(&path, "windows"), windows));
+        }
+    }
+
+    problems
+}
+
+/// Built-ins depend on where a value is used, so they are assumed to be available
+fn any_context() -> InterpolationContext {
+    InterpolationContext::for_workspace("", "").with_worktree("", "")
+}
+
+/// Check that the variables in every value can be resolved
+fn check_variables(lists: &[(String, &[String])]) -> Vec<(String, String)> {
+    let context = any_context();
+    lists
+        .iter()
+        .flat_map(|(path, values)| {
+            values.iter().enumerate().filter_map(|(index, value)| {
+                interpolate(value, &context)
+                    .err()
+                    .map(|err| (join_index(path, index), err.message))
+            })
+        })
+        .collect()
+}
+
+/// Check the variables of windows and that their panes and layouts can be built
+fn check_windows(path: &str, windows: &[Window]) -> Vec<(String, String)> {
+    let mut problems = vec![];
+    for (index, window) in windows.iter().enumerate() {
+        let path = join_index(path, index);
+        problems.extend(check_command(&path, window.command.as_ref()));
+        problems.extend(check_cwd(&path, window.cwd.as_ref()));
+        problems.extend(check_env(&path, window.env.as_ref(), None));
+        if let Some(panes) = &window.panes {
+            problems.extend(check_panes(&join_key(&path, "panes"), panes));
+            let focused = count_focused(panes);
+            if focused > 1 {
+                problems.push((
+                    join_key(&path, "panes"),
+                    format!("{} panes have focus, only one pane can be focused", focused),
+                ));
+            }
+        }
+        if let Some(layout) = &window.layout
+            && !TMUX_LAYOUTS.contains(&layout.as_str())
+            && !layout.contains(',')
+        {
+            problems.push((
+                join_key(&path, "layout"),
+                format!(
+                    "unknown layout `{}`, expected one of {} or a layout string",
+                    layout,
+                    TMUX_LAYOUTS.join(", ")
+                ),
+            ));
+        }
+    }
+    let focused = windows.iter().filter(|x| x.focus.unwrap_or(false)).count();
+    if focused > 1 {
+        problems.push((
+            path.to_string(),
+            format!(
+                "{} windows have focus, only one window can be focused",
+                focused
+            ),
+        ));
+    }
+    problems
+}
+
+/// The layouts tmux knows by name, other layouts are the strings tmux prints for a window
+const TMUX_LAYOUTS: [&str; 5] = [
+    "even-horizontal",
+    "even-vertical",
+    "main-horizontal",
+    "main-vertical",
+    "tiled",
+];
+
+fn check_panes(path: &str, panes: &[Pane]) -> Vec<(String, String)> {
+    let mut problems = vec![];
+    let mut total = 0;
+    for (index, pane) in panes.iter().enumerate() {
+        let path = join_index(path, index);
+        problems.extend(check_command(&path, pane.command.as_ref()));
+        problems.extend(check_cwd(&path, pane.cwd.as_ref()));
+        if let Some(size) = pane.size {
+            if !(1..=99).contains(&size) {
+                problems.push((
+                    join_key(&path, "size"),
+                    format!("size {} is not between 1 and 99", size),
+                ));
+            }
+            total += size;
+        }
+        if let Some(children) = &pane.panes {
+            if pane.command.is_some() || pane.cwd.is_some() {
+                problems.push((
+                    path.clone(),
+                    "a pane with panes can not have a command or cwd, set them on its panes"
+                        .to_string(),
+                ));
+            }
+            problems.extend(check_panes(&join_key(&path, "panes"), children));
+        }
+    }
+    if total > 100 {
+        problems.push((
+            path.to_string(),
+            format!(
+                "the sizes of the panes add up to {}%, more than 100%",
+                total
+            ),
+        ));
+    }
+    problems
+}
+
+fn count_focused(panes: &[Pane]) -> usize {
+    panes
+        .iter()
+        .map(|pane| {
+            usize::from(pane.focus.unwrap_or(false))
+                + pane.panes.as_deref().map(count_focused).unwrap_or(0)
+        })
+        .sum()
+}
+
+fn check_command(path: &str, command: Option<&String>) -> Option<(String, String)> {
+    command.and_then(|command| {
+        interpolate(command, &any_context())
+            .err()
+            .map(|err| (join_key(path, "command"), err.message))
+    })
+}
+
+fn check_cwd(path: &str, cwd: Option<&String>) -> Option<(String, String)> {
+    cwd.and_then(|cwd| {
+        interpolate(cwd, &any_context())
+            .err()
+            .map(|err| (join_key(path, "cwd"), err.message))
+    })
+}
+
+/// Check the names and variables of `env` and the variables in the path of `envFile`
+fn check_env(
+    path: &str,
+    env: Option<&BTreeMap<String, String>>,
+    env_file: Option<&str>,
+) -> Vec<(String, String)> {
+    let mut problems = vec![];
+    for (key, value) in env.into_iter().flatten() {
+        let value_path = join_key(&join_key(path, "env"), key);
+        if !is_valid_name(key) {
+            problems.push((
+                value_path,
+                format!("`{}` is not a valid variable name", key),
+            ));
+        } else if let Err(err) = interpolate(value, &any_context()) {
+            problems.push((value_path, err.message));
+        }
+    }
+    if let Some(env_file) = env_file
+        && let Err(err) = interpolate(env_file, &any_context())
+    {
+        problems.push((join_key(path, "envFile"), err.message));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate_content(file_name: &str, content: &str) -> Vec<ConfigProblem> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(file_name);
+        fs::write(
+            &path,
+            content.replace("$ROOT", &temp_dir.path().to_string_lossy()),
+        )
+        .unwrap();
+        validate_files(&[path.to_string_lossy().to_string()])
+    }
+
+    fn messages(problems: &[ConfigProblem]) -> Vec<(String, String)> {
+        problems
+            .iter()
+            .map(|x| (x.path.clone(), x.message.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn should_accept_valid_config() {
+        let problems = validate_content(
+            "config.json",
+            r#"{
+                "$schema": "../schemas/config-schema.json",
+                "workspaces": [{ "id": "api", "name": "Api", "root": "$ROOT", "tags": null }],
+                "tmux": {
+                    "sessions": [
+                        { "workspace": "api", "windows": [{ "name": "nvim", "command": "nvim ." }] },
+                        { "path": "/", "name": "root", "windows": [] }
+                    ],
+                    "defaultWindows": [{ "name": "zsh", "command": null }]
+                },
+                "worktree": { "symlinkFiles": [".env"], "tmux": false }
+            }"#,
+        );
+
+        assert_eq!(problems, vec![]);
+    }
+
+    #[test]
+    fn should_report_unknown_keys_with_suggestion() {
+        let problems = validate_content(
+            "config.json",
+            "{\n  \"worktree\": {\n    \"symlinkfiles\": []\n  }\n}",
+        );
+
+        assert_eq!(
+            messages(&problems),
+            vec![(
+                "worktree.symlinkfiles".to_string(),
+                "unknown key `symlinkfiles`, did you mean `symlinkFiles`?".to_string()
+            )]
+        );
+        assert_eq!(problems[0].position, Some(Position { line: 3, column: 5 }));
+    }
+
+    #[test]
+    fn should_report_wrong_types_in_sessions() {
+        let problems = validate_content(
+            "config.yaml",
+            "tmux:\n  sessions:\n    - workspace: 5\n      windows: []\n",
+        );
+
+        assert_eq!(
+            messages(&problems),
+            vec![(
+                "tmux.sessions[0].workspace".to_string(),
+                "expected string, found number".to_string()
+            )]
+        );
+        assert_eq!(problems[0].position, Some(Position { line: 3, column: 7 }));
+    }
+
+    #[test]
+    fn should_report_duplicate_ids_missing_roots_and_dangling_sessions() {
+        let problems = validate_content(
+            "config.toml",
+            r#"
+[[workspaces]]
+id = "api"
+name = "Api"
+root = "$ROOT"
+
+[[workspaces]]
+id = "api"
+name = "Api again"
+root = "$ROOT/missing"
+
+[[tmux.sessions]]
+workspace = "web"
+windows = []
+"#,
+        );
+
+        let messages = messages(&problems);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[0],
+            (
+                "workspaces[1].id".to_string(),
+                "duplicate workspace id `api`".to_string()
+            )
+        );
+        assert_eq!(messages[1].0, "workspaces[1].root");
+        assert_eq!(
+            messages[2],
+            (
+                "tmux.sessions[0].workspace".to_string(),
+                "no workspace with id `web`".to_string()
+            )
+        );
+        assert_eq!(problems[0].position, Some(Position { line: 8, column: 1 }));
+    }
+
+    #[test]
+    fn should_report_conflicting_aliases() {
+        let problems = validate_content(
+            "config.json",
+            r#"{
+  "workspaces": [
+    { "id": "api", "name": "Api", "root": "$ROOT", "aliases": ["a", "web"] },
+    { "id": "web", "name": "Web", "root": "$ROOT", "aliases": ["a"] }
+  ]
+}"#,
+        );
+
+        assert_eq!(
+            messages(&problems),
+            vec![
+                (
+                    "workspaces[0].aliases[1]".to_string(),
+                    "alias `web` is also a workspace id".to_string()
+                ),
+                (
+                    "workspaces[1].aliases[0]".to_string(),
+                    "duplicate alias `a`".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_syntax_errors_with_position() {
+        let problems = validate_content("config.json", "{\n  \"workspaces\": [,]\n}");
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].position.map(|x| x.line), Some(2));
+    }
+
+    #[test]
+    fn should_report_unresolved_variables() {
+        let problems = validate_content(
+            "config.json",
+            r#"{
+                "workspaces": [{ "id": "api", "name": "Api", "root": "$ROOT" }],
+                "tmux": {
+                    "defaultWindows": [
+                        { "name": "nvim", "command": "cd ${workspace.root} && nvim" },
+                        { "name": "logs", "command": "tail -f ${RAFAELTAB_TEST_UNSET_VARIABLE}" }
+                    ]
+                },
+                "worktree": { "onCreate": ["echo ${worktree.branch}", "${workspace.name}"] }
+            }"#,
+        );
+
+        let paths: Vec<String> = problems.iter().map(|x| x.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec!["worktree.onCreate[1]", "tmux.defaultWindows[1].command"]
+        );
+        assert!(
+            problems[1]
+                .message
+                .contains("RAFAELTAB_TEST_UNSET_VARIABLE")
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_env() {
+        let problems = validate_content(
+            "config.json",
+            r#"{
+                "workspaces": [{
+                    "id": "api",
+                    "name": "Api",
+                    "root": "$ROOT",
+                    "env": { "PORT": "3000", "BAD-NAME": "x" },
+                    "envFile": "${RAFAELTAB_TEST_UNSET_VARIABLE}/.env"
+                }],
+                "tmux": {
+                    "defaultWindows": [
+                        { "name": "api", "env": { "URL": "${workspace.nope}" } }
+                    ]
+                }
+            }"#,
+        );
+
+        let paths: Vec<String> = problems.iter().map(|x| x.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "workspaces[0].env.BAD-NAME",
+                "workspaces[0].envFile",
+                "tmux.defaultWindows[0].env.URL"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_panes_that_can_not_be_built() {
+        let problems = validate_content(
+            "config.json",
+            r#"{
+                "tmux": {
+                    "defaultWindows": [
+                        {
+                            "name": "dev",
+                            "layout": "main-vertical",
+                            "panes": [
+                                { "command": "nvim", "size": 70, "focus": true },
+                                {
+                                    "command": "cargo watch",
+                                    "size": 40,
+                                    "split": "vertical",
+                                    "panes": [
+                                        { "cwd": "${RAFAELTAB_TEST_UNSET_VARIABLE}", "focus": true },
+                                        { "size": 0 }
+                                    ]
+                                }
+                            ]
+                        },
+                        { "name": "logs", "layout": "diagonal" }
+                    ]
+                }
+            }"#,
+        );
+
+        let paths: Vec<String> = problems.iter().map(|x| x.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "tmux.defaultWindows[0].panes[1]",
+                "tmux.defaultWindows[0].panes[1].panes[0].cwd",
+                "tmux.defaultWindows[0].panes[1].panes[1].size",
+                "tmux.defaultWindows[0].panes",
+                "tmux.defaultWindows[0].panes",
+                "tmux.defaultWindows[1].layout",
+            ]
+        );
+        assert!(problems[3].message.contains("110%"));
+        assert!(problems[4].message.contains("2 panes have focus"));
+    }
+
+    #[test]
+    fn should_report_windows_that_can_not_be_created() {
+        let problems = validate_content(
+            "config.json",
+            r#"{
+                "tmux": {
+                    "defaultWindows": [
+                        { "name": "nvim", "command": "nvim", "focus": true },
+                        { "name": "server", "cwd": "${RAFAELTAB_TEST_UNSET_VARIABLE}/api" },
+                        { "name": "logs", "background": true, "focus": true }
+                    ]
+                }
+            }"#,
+        );
+
+        let paths: Vec<String> = problems.iter().map(|x| x.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec!["tmux.defaultWindows[1].cwd", "tmux.defaultWindows"]
+        );
+        assert!(problems[1].message.contains("2 windows have focus"));
+    }
+
+    #[test]
+    fn should_report_duplicate_templates() {
+        let problems = validate_content(
+            "config.json",
+            r#"{
+                "templates": [
+                    { "name": "rust", "source": "~/templates/rust" },
+                    { "name": "rust", "source": "${RAFAELTAB_TEST_UNSET_VARIABLE}/rust" }
+                ]
+            }"#,
+        );
+
+        let paths: Vec<String> = problems.iter().map(|x| x.path.clone()).collect();
+        assert_eq!(paths, vec!["templates[1].name", "templates[1].source[0]"]);
+    }
+
+    #[test]
+    fn should_resolve_sessions_against_workspaces_of_other_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let user = temp_dir.path().join("user.json");
+        let repo = temp_dir.path().join("repo.json");
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::write(
+            &user,
+            json!({ "workspaces": [{ "id": "api", "name": "Api", "root": root }] }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            &repo,
+            json!({ "tmux": { "sessions": [{ "workspace": "api", "windows": [] }] } }).to_string(),
+        )
+        .unwrap();
+
+        let problems = validate_files(&[
+            user.to_string_lossy().to_string(),
+            repo.to_string_lossy().to_string(),
+        ]);
+
+        assert_eq!(problems, vec![]);
+    }
+}
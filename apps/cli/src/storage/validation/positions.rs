@@ -0,0 +1,308 @@
+//! Maps JSON paths to the line and column they are defined at in the source file
+
+use std::collections::HashMap;
+
+use toml_edit::{ImDocument, Item, TableLike, Value};
+use yaml_rust2::{
+    Event,
+    parser::{MarkedEventReceiver, Parser},
+    scanner::Marker,
+};
+
+use crate::storage::kinds::format::ConfigFormat;
+
+/// A line and column, both starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The positions of every key and array item in a configuration file.
+///
+/// Object properties point at their key, array items at the start of the item.
+#[derive(Debug, Default)]
+pub struct PositionIndex {
+    positions: HashMap<String, Position>,
+}
+
+impl PositionIndex {
+    /// Index a file, returns an empty index when the file can not be parsed
+    pub fn build(format: ConfigFormat, content: &str) -> Self {
+        let mut index = PositionIndex::default();
+        match format {
+            // JSON is a subset of YAML, so the YAML parser provides positions for both
+            ConfigFormat::Json | ConfigFormat::Yaml => {
+                let mut receiver = YamlReceiver {
+                    index: &mut index,
+                    frames: vec![],
+                };
+                let _ = Parser::new_from_str(content).load(&mut receiver, false);
+            }
+            ConfigFormat::Toml => {
+                if let Ok(document) = ImDocument::parse(content) {
+                    let lines = LineIndex::new(content);
+                    index_toml_table(&mut index, &lines, "", document.as_table());
+                }
+            }
+        }
+        index
+    }
+
+    /// The position of a path, or of its closest parent that has a position
+    pub fn locate(&self, path: &str) -> Option<Position> {
+        let mut path = path;
+        loop {
+            if let Some(position) = self.positions.get(path) {
+                return Some(*position);
+            }
+            if path.is_empty() {
+                return None;
+            }
+            path = parent_path(path);
+        }
+    }
+
+    fn insert(&mut self, path: String, position: Position) {
+        self.positions.entry(path).or_insert(position);
+    }
+}
+
+/// Append an object property to a path
+pub fn join_key(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Append an array index to a path
+pub fn join_index(path: &str, index: usize) -> String {
+    format!("{}[{}]", path, index)
+}
+
+fn parent_path(path: &str) -> &str {
+    match path.rfind(['.', '[']) {
+        Some(position) => &path[..position],
+        None => "",
+    }
+}
+
+enum Frame {
+    Mapping { path: String, key: Option<String> },
+    Sequence { path: String, index: usize },
+}
+
+struct YamlReceiver<'a> {
+    index: &'a mut PositionIndex,
+    frames: Vec<Frame>,
+}
+
+impl YamlReceiver<'_> {
+    /// Handle the start of a node, returns the path of the node when it is a value
+    fn node(&mut self, key: Option<&str>, mark: Marker) -> Option<String> {
+        let position = Position {
+            line: mark.line(),
+            column: mark.col() + 1,
+        };
+        match self.frames.last_mut() {
+            None => {
+                self.index.insert(String::new(), position);
+                Some(String::new())
+            }
+            Some(Frame::Mapping {
+                path,
+                key: current_key,
+            }) => match current_key.take() {
+                Some(current_key) => Some(join_key(path, &current_key)),
+                None => {
+                    // This node is a key, complex keys are not supported and skipped
+                    let key = key.unwrap_or("?").to_string();
+                    self.index.insert(join_key(path, &key), position);
+                    *current_key = Some(key);
+                    None
+                }
+            },
+            Some(Frame::Sequence { path, index }) => {
+                let item_path = join_index(path, *index);
+                *index += 1;
+                self.index.insert(item_path.clone(), position);
+                Some(item_path)
+            }
+        }
+    }
+}
+
+impl MarkedEventReceiver for YamlReceiver<'_> {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, ..) => {
+                self.node(Some(&value), mark);
+            }
+            Event::Alias(_) => {
+                self.node(None, mark);
+            }
+            Event::MappingStart(..) => {
+                if let Some(path) = self.node(None, mark) {
+                    self.frames.push(Frame::Mapping { path, key: None });
+                } else {
+                    self.frames.push(Frame::Mapping {
+                        path: "?".to_string(),
+                        key: None,
+                    });
+                }
+            }
+            Event::SequenceStart(..) => {
+                let path = self.node(None, mark).unwrap_or_else(|| "?".to_string());
+                self.frames.push(Frame::Sequence { path, index: 0 });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.frames.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Converts byte offsets into positions
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(content: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        LineIndex { line_starts }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        Position {
+            line,
+            column: offset - self.line_starts[line - 1] + 1,
+        }
+    }
+}
+
+fn index_toml_table(
+    index: &mut PositionIndex,
+    lines: &LineIndex,
+    path: &str,
+    table: &dyn TableLike,
+) {
+    for (key, item) in table.iter() {
+        let item_path = join_key(path, key);
+        if let Some(span) = table.get_key_value(key).and_then(|(key, _)| key.span()) {
+            index.insert(item_path.clone(), lines.position(span.start));
+        }
+        index_toml_item(index, lines, &item_path, item);
+    }
+}
+
+fn index_toml_item(index: &mut PositionIndex, lines: &LineIndex, path: &str, item: &Item) {
+    match item {
+        Item::Table(table) => index_toml_table(index, lines, path, table),
+        Item::ArrayOfTables(tables) => {
+            for (i, table) in tables.iter().enumerate() {
+                let item_path = join_index(path, i);
+                if let Some(span) = table.span() {
+                    index.insert(item_path.clone(), lines.position(span.start));
+                }
+                index_toml_table(index, lines, &item_path, table);
+            }
+        }
+        Item::Value(value) => index_toml_value(index, lines, path, value),
+        Item::None => {}
+    }
+}
+
+fn index_toml_value(index: &mut PositionIndex, lines: &LineIndex, path: &str, value: &Value) {
+    match value {
+        Value::Array(array) => {
+            for (i, value) in array.iter().enumerate() {
+                let item_path = join_index(path, i);
+                if let Some(span) = value.span() {
+                    index.insert(item_path.clone(), lines.position(span.start));
+                }
+                index_toml_value(index, lines, &item_path, value);
+            }
+        }
+        Value::InlineTable(table) => index_toml_table(index, lines, path, table),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_locate_json_keys_and_items() {
+        let content = r#"{
+  "workspaces": [
+    { "id": "api" },
+    {
+      "id": "web"
+    }
+  ]
+}"#;
+        let index = PositionIndex::build(ConfigFormat::Json, content);
+
+        assert_eq!(
+            index.locate("workspaces"),
+            Some(Position { line: 2, column: 3 })
+        );
+        assert_eq!(
+            index.locate("workspaces[1].id"),
+            Some(Position { line: 5, column: 7 })
+        );
+        assert_eq!(
+            index.locate("workspaces[0].id"),
+            Some(Position { line: 3, column: 7 })
+        );
+    }
+
+    #[test]
+    fn should_locate_yaml_keys() {
+        let content = "tmux:\n  sessions:\n    - workspace: api\n      windows: []\n";
+        let index = PositionIndex::build(ConfigFormat::Yaml, content);
+
+        assert_eq!(
+            index.locate("tmux.sessions[0].windows"),
+            Some(Position { line: 4, column: 7 })
+        );
+    }
+
+    #[test]
+    fn should_locate_toml_keys() {
+        let content =
+            "[[workspaces]]\nid = \"api\"\n\n[[workspaces]]\nid = \"web\"\ntags = [\"a\", \"b\"]\n";
+        let index = PositionIndex::build(ConfigFormat::Toml, content);
+
+        assert_eq!(
+            index.locate("workspaces[1].id"),
+            Some(Position { line: 5, column: 1 })
+        );
+        assert_eq!(
+            index.locate("workspaces[1].tags[1]"),
+            Some(Position {
+                line: 6,
+                column: 14
+            })
+        );
+    }
+
+    #[test]
+    fn should_fall_back_to_parent_position() {
+        let content = "{\n  \"tmux\": {}\n}";
+        let index = PositionIndex::build(ConfigFormat::Json, content);
+
+        assert_eq!(
+            index.locate("tmux.sessions[3].workspace"),
+            Some(Position { line: 2, column: 3 })
+        );
+    }
+}
@@ -1,16 +1,41 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::{storage_interface::Storage, worktree::WorkspaceWorktreeConfig};
 
-pub trait WorkspaceStorage: Storage<Vec<Workspace>> {}
+pub trait WorkspaceStorage: Storage<Vec<Workspace>> {
+    /// The configuration file that defines the workspace, when known
+    fn workspace_source(&self, _id: &str) -> Option<String> {
+        None
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Workspace {
+    /// Directory of the workspace
     pub root: String,
+    /// Unique identifier of the workspace
     pub id: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+    /// Other names that `workspace find` resolves to this workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<Vec<String>>,
+    /// How important the workspace is, more important workspaces are ranked higher in pickers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importance: Option<i32>,
+    /// Environment variables for the tmux sessions and the `onCreate`/`onDestroy` commands of the
+    /// workspace, they override the ones from `envFile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    /// Dotenv file to load environment variables from, relative to the root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
     /// Optional worktree configuration for this workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub worktree: Option<WorkspaceWorktreeConfig>,
 }
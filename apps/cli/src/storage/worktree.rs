@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::storage_interface::Storage;
@@ -6,7 +7,7 @@ use super::storage_interface::Storage;
 pub trait WorktreeStorage: Storage<Option<WorktreeConfig>> {}
 
 /// Global worktree configuration that applies to all workspaces
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WorktreeConfig {
     /// Glob patterns for files to symlink from main worktree to new worktrees
@@ -20,12 +21,13 @@ pub struct WorktreeConfig {
     pub on_destroy: Vec<String>,
     /// Whether worktree commands should integrate with tmux.
     /// Defaults to true when omitted.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "bool")]
     pub tmux: Option<bool>,
 }
 
 /// Per-workspace worktree configuration
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceWorktreeConfig {
     /// Glob patterns for files to symlink from main worktree to new worktrees
@@ -42,7 +44,8 @@ pub struct WorkspaceWorktreeConfig {
     pub on_destroy: Vec<String>,
     /// Whether worktree commands should integrate with tmux.
     /// Overrides the global worktree setting when present.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "bool")]
     pub tmux: Option<bool>,
 }
 
@@ -0,0 +1,303 @@
+//! Environment variables from the configuration.
+//!
+//! Workspaces, path sessions and windows can set variables with `env`, workspaces and path
+//! sessions can also load them from an `envFile` in dotenv format. Variables in `env` override the
+//! ones from the file. Values in `env` and the path of the file are interpolated, values in the file
+//! are used as written.
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use duct::Expression;
+
+use super::interpolation::{InterpolationContext, InterpolationError, interpolate};
+
+/// Environment variables by name, sorted so they are always applied and shown in the same order
+pub type Environment = BTreeMap<String, String>;
+
+/// Parts of a variable name that mark its value as a secret
+const SECRET_MARKERS: [&str; 7] = [
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "KEY",
+    "CREDENTIAL",
+    "PRIVATE",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvError {
+    Interpolation(InterpolationError),
+    /// The env file could not be read
+    ReadFile {
+        location: String,
+        path: String,
+        error: String,
+    },
+    /// A line of the env file is not a variable assignment
+    Parse {
+        path: String,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::Interpolation(err) => write!(f, "{}", err),
+            EnvError::ReadFile {
+                location,
+                path,
+                error,
+            } => write!(f, "{}: can not read '{}': {}", location, path, error),
+            EnvError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path, line, message),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+impl From<InterpolationError> for EnvError {
+    fn from(err: InterpolationError) -> Self {
+        EnvError::Interpolation(err)
+    }
+}
+
+/// Resolve the `env` and `envFile` of a configuration value.
+///
+/// A relative `env_file` is relative to `base_dir`, `location` is the path of the configuration
+/// value that is used in errors, such as `workspaces[id=api]`.
+pub fn resolve_env(
+    env: Option<&Environment>,
+    env_file: Option<&str>,
+    base_dir: &str,
+    context: &InterpolationContext,
+    location: &str,
+) -> Result<Environment, EnvError> {
+    let mut result = Environment::new();
+
+    if let Some(env_file) = env_file {
+        let file = interpolate(env_file, context)
+            .map_err(|err| err.at(format!("{}.envFile", location)))?;
+        let path = Path::new(base_dir).join(&file);
+        result.extend(read_env_file(&path, &format!("{}.envFile", location))?);
+    }
+
+    result.extend(interpolate_env(env, context, &format!("{}.env", location))?);
+    Ok(result)
+}
+
+/// Expand the variables in the values of an `env` map
+pub fn interpolate_env(
+    env: Option<&Environment>,
+    context: &InterpolationContext,
+    location: &str,
+) -> Result<Environment, InterpolationError> {
+    env.into_iter()
+        .flatten()
+        .map(|(key, value)| {
+            let value = interpolate(value, context)
+                .map_err(|err| err.at(format!("{}.{}", location, key)))?;
+            Ok((key.clone(), value))
+        })
+        .collect()
+}
+
+fn read_env_file(path: &Path, location: &str) -> Result<Environment, EnvError> {
+    let display_path = path.to_string_lossy().to_string();
+    let content = fs::read_to_string(path).map_err(|err| EnvError::ReadFile {
+        location: location.to_string(),
+        path: display_path.clone(),
+        error: err.to_string(),
+    })?;
+    parse_env_file(&content).map_err(|(line, message)| EnvError::Parse {
+        path: display_path,
+        line,
+        message,
+    })
+}
+
+/// Add environment variables to a command
+pub fn with_env(expression: Expression, env: &Environment) -> Expression {
+    env.iter().fold(expression, |expression, (key, value)| {
+        expression.env(key, value)
+    })
+}
+
+/// Parse the content of a dotenv file.
+///
+/// Supports comments, empty lines, an `export` prefix, single quoted values that are used as
+/// written and double quoted values with `\n`, `\"` and `\\` escapes. Returns the line number and
+/// a message for the first line that is not valid.
+pub fn parse_env_file(content: &str) -> Result<Environment, (usize, String)> {
+    let mut result = Environment::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err((line_number, format!("expected `KEY=value`, got `{}`", line)));
+        };
+        let key = key.trim();
+        if !is_valid_name(key) {
+            return Err((
+                line_number,
+                format!("`{}` is not a valid variable name", key),
+            ));
+        }
+
+        let value = parse_value(value.trim())
+            .ok_or_else(|| (line_number, format!("unterminated quote in `{}`", line)))?;
+        result.insert(key.to_string(), value);
+    }
+
+    Ok(result)
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(rest) = value.strip_prefix('\'') {
+        let end = rest.find('\'')?;
+        return Some(rest[..end].to_string());
+    }
+
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut result = String::new();
+        let mut chars = rest.chars();
+        while let Some(char) = chars.next() {
+            match char {
+                '"' => return Some(result),
+                '\\' => match chars.next()? {
+                    'n' => result.push('\n'),
+                    other => result.push(other),
+                },
+                other => result.push(other),
+            }
+        }
+        return None;
+    }
+
+    // An unquoted value ends at a comment
+    let value = match value.find(" #") {
+        Some(index) => &value[..index],
+        None => value,
+    };
+    Some(value.trim_end().to_string())
+}
+
+/// Whether a string can be used as the name of an environment variable
+pub fn is_valid_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|x| x.is_ascii_alphabetic() || x == '_')
+        && name.chars().all(|x| x.is_ascii_alphanumeric() || x == '_')
+}
+
+/// Whether the name of a variable suggests that its value is a secret
+pub fn is_secret(name: &str) -> bool {
+    let name = name.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// The value of a variable for display, secrets are masked
+pub fn display_value<'a>(name: &str, value: &'a str) -> &'a str {
+    if is_secret(name) { "********" } else { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> Environment {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_env_files() {
+        let content = r#"
+# database
+export DATABASE_URL=postgres://localhost/app # local only
+NAME = 'single # quoted'
+GREETING="hello \"world\"\nbye"
+EMPTY=
+"#;
+
+        let result = parse_env_file(content).unwrap();
+
+        assert_eq!(
+            result,
+            env(&[
+                ("DATABASE_URL", "postgres://localhost/app"),
+                ("NAME", "single # quoted"),
+                ("GREETING", "hello \"world\"\nbye"),
+                ("EMPTY", ""),
+            ])
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_lines() {
+        assert_eq!(parse_env_file("A=1\nnot an assignment").unwrap_err().0, 2);
+        assert_eq!(parse_env_file("1A=1").unwrap_err().0, 1);
+        assert_eq!(parse_env_file("A=\"open").unwrap_err().0, 1);
+    }
+
+    #[test]
+    fn should_let_env_override_the_env_file() {
+        let dir = std::env::temp_dir().join(format!("rafaeltab-env-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".env"), "PORT=3000\nHOST=localhost\n").unwrap();
+        let context = InterpolationContext::for_workspace("api", "/src/api");
+
+        let result = resolve_env(
+            Some(&env(&[("PORT", "8080"), ("ROOT", "${workspace.root}")])),
+            Some(".env"),
+            &dir.to_string_lossy(),
+            &context,
+            "workspaces[id=api]",
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            result.unwrap(),
+            env(&[
+                ("HOST", "localhost"),
+                ("PORT", "8080"),
+                ("ROOT", "/src/api")
+            ])
+        );
+    }
+
+    #[test]
+    fn should_point_at_missing_env_files() {
+        let result = resolve_env(
+            None,
+            Some("missing.env"),
+            "/nonexistent",
+            &InterpolationContext::default(),
+            "workspaces[id=api]",
+        );
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.starts_with("workspaces[id=api].envFile: can not read"));
+    }
+
+    #[test]
+    fn should_mask_secrets() {
+        assert_eq!(display_value("GITHUB_TOKEN", "abc"), "********");
+        assert_eq!(display_value("api_key", "abc"), "********");
+        assert_eq!(display_value("PORT", "3000"), "3000");
+    }
+}
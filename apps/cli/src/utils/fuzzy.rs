@@ -0,0 +1,20 @@
+use sublime_fuzzy::{FuzzySearch, Scoring};
+
+/// Score how well `text` matches `query`, higher is better.
+///
+/// Matching ignores case and shorter texts score a little higher, so `api` ranks above
+/// `api-gateway` for the query `api`. Returns `None` when the text does not match at all.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<isize> {
+    let query_lower = query.to_lowercase();
+    let text_lower = text.to_lowercase();
+    let scoring = Scoring::default();
+
+    FuzzySearch::new(&query_lower, &text_lower)
+        .score_with(&scoring)
+        .best_match()
+        .map(|m| {
+            // Small bonus for shorter text (better match density)
+            let length_bonus = (100.0 / text.len().max(1) as f64) as isize;
+            m.score() + length_bonus
+        })
+}
@@ -0,0 +1,287 @@
+//! Variable interpolation for strings from the configuration.
+//!
+//! Supported forms are:
+//! - `${VAR}`, the value of an environment variable
+//! - `${VAR:-default}`, the default when the variable is unset or empty, the default is
+//!   interpolated as well
+//! - `${workspace.id}`, `${workspace.root}`, `${worktree.branch}` and `${worktree.root}`, built-ins
+//!   that are available where the string is used for a workspace or worktree. `${workspace.root}` is
+//!   always the main checkout, `${worktree.root}` the checkout of the worktree
+//! - a leading `~`, the home directory
+//!
+//! `$${` produces a literal `${`, so commands can still use shell parameter expansion.
+
+use std::{env, fmt};
+
+use shellexpand::tilde;
+
+/// Values for the built-in variables, `None` when not available where the string is used
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterpolationContext {
+    pub workspace_id: Option<String>,
+    pub workspace_root: Option<String>,
+    pub worktree_branch: Option<String>,
+    pub worktree_root: Option<String>,
+}
+
+impl InterpolationContext {
+    pub fn for_workspace(id: &str, root: &str) -> Self {
+        InterpolationContext {
+            workspace_id: Some(id.to_string()),
+            workspace_root: Some(root.to_string()),
+            worktree_branch: None,
+            worktree_root: None,
+        }
+    }
+
+    /// Add the branch and the directory of the worktree the string is used for
+    pub fn with_worktree(mut self, branch: &str, root: &str) -> Self {
+        self.worktree_branch = Some(branch.to_string());
+        self.worktree_root = Some(root.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpolationError {
+    /// Path of the configuration value, such as `tmux.defaultWindows[0].command`
+    pub location: Option<String>,
+    pub message: String,
+}
+
+impl InterpolationError {
+    fn new(message: String) -> Self {
+        InterpolationError {
+            location: None,
+            message,
+        }
+    }
+
+    /// Attach the path of the configuration value the error occurred in
+    pub fn at(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for InterpolationError {}
+
+/// Expand the variables in a configuration string
+pub fn interpolate(
+    value: &str,
+    context: &InterpolationContext,
+) -> Result<String, InterpolationError> {
+    interpolate_with(value, context, &|name| env::var(name).ok())
+}
+
+/// Expand the variables in a configuration path, leaving a leading `~` for [`expand_path`]
+///
+/// [`expand_path`]: crate::utils::path::expand_path
+pub fn interpolate_variables(
+    value: &str,
+    context: &InterpolationContext,
+) -> Result<String, InterpolationError> {
+    expand_variables(value, context, &|name| env::var(name).ok())
+}
+
+fn interpolate_with(
+    value: &str,
+    context: &InterpolationContext,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, InterpolationError> {
+    let expanded = expand_variables(value, context, env)?;
+    Ok(tilde(&expanded).to_string())
+}
+
+fn expand_variables(
+    value: &str,
+    context: &InterpolationContext,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, InterpolationError> {
+    let mut result = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        result.push_str(&rest[..start]);
+
+        let body_start = start + 2;
+        let body_len = closing_brace(&rest[body_start..]).ok_or_else(|| {
+            InterpolationError::new(format!("`{}` is missing a closing `}}`", &rest[start..]))
+        })?;
+        let body = &rest[body_start..body_start + body_len];
+        result.push_str(&expand_variable(body, context, env)?);
+        rest = &rest[body_start + body_len + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Find the `}` that closes a variable, skipping over nested variables in defaults
+fn closing_brace(body: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, char) in body.char_indices() {
+        match char {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn expand_variable(
+    body: &str,
+    context: &InterpolationContext,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, InterpolationError> {
+    let (name, default) = match body.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (body, None),
+    };
+
+    let valid_name = name
+        .chars()
+        .next()
+        .is_some_and(|x| x.is_ascii_alphabetic() || x == '_')
+        && name
+            .chars()
+            .all(|x| x.is_ascii_alphanumeric() || x == '_' || x == '.');
+    if !valid_name {
+        return Err(InterpolationError::new(format!(
+            "`${{{}}}` is not a valid variable",
+            body
+        )));
+    }
+
+    let (value, unresolved) = match name {
+        "workspace.id" | "workspace.root" | "worktree.branch" | "worktree.root" => {
+            let (value, available_for) = match name {
+                "workspace.id" => (&context.workspace_id, "workspaces"),
+                "workspace.root" => (&context.workspace_root, "workspaces"),
+                "worktree.branch" => (&context.worktree_branch, "worktrees"),
+                _ => (&context.worktree_root, "worktrees"),
+            };
+            let unresolved = format!("`${{{}}}` is only available for {}", name, available_for);
+            (value.clone(), unresolved)
+        }
+        _ if name.contains('.') => {
+            return Err(InterpolationError::new(format!(
+                "unknown variable `${{{}}}`, the built-in variables are `${{workspace.id}}`, `${{workspace.root}}`, `${{worktree.branch}}` and `${{worktree.root}}`",
+                name
+            )));
+        }
+        _ => {
+            let unresolved = format!(
+                "environment variable `{}` is not set, use `${{{}:-default}}` to provide a default",
+                name, name
+            );
+            (env(name), unresolved)
+        }
+    };
+
+    match default {
+        // Like the shell, a default also replaces an empty value
+        Some(default) => match value.filter(|x| !x.is_empty()) {
+            Some(value) => Ok(value),
+            None => expand_variables(default, context, env),
+        },
+        None => value.ok_or_else(|| InterpolationError::new(unresolved)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "EDITOR" => Some("nvim".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn context_factory() -> InterpolationContext {
+        InterpolationContext::for_workspace("api", "/src/api")
+            .with_worktree("feat/login", "/src/api-feat")
+    }
+
+    fn expand(value: &str) -> Result<String, InterpolationError> {
+        interpolate_with(value, &context_factory(), &env)
+    }
+
+    #[test]
+    fn should_expand_environment_variables() {
+        assert_eq!(expand("${EDITOR} .").unwrap(), "nvim .");
+        assert_eq!(expand("${PAGER:-less}").unwrap(), "less");
+        assert_eq!(expand("[${EMPTY}]").unwrap(), "[]");
+        assert_eq!(expand("${EMPTY:-fallback}").unwrap(), "fallback");
+        assert_eq!(expand("${PAGER:-${EDITOR}}").unwrap(), "nvim");
+    }
+
+    #[test]
+    fn should_expand_built_ins() {
+        assert_eq!(
+            expand("${workspace.root}/.env.${workspace.id}").unwrap(),
+            "/src/api/.env.api"
+        );
+        assert_eq!(
+            expand("echo ${worktree.branch}").unwrap(),
+            "echo feat/login"
+        );
+        assert_eq!(
+            expand("cp ${workspace.root}/.env ${worktree.root}").unwrap(),
+            "cp /src/api/.env /src/api-feat"
+        );
+    }
+
+    #[test]
+    fn should_expand_leading_tilde() {
+        let home = tilde("~").to_string();
+
+        assert_eq!(expand("~/src").unwrap(), format!("{}/src", home));
+        assert_eq!(expand("a~b").unwrap(), "a~b");
+    }
+
+    #[test]
+    fn should_keep_escaped_and_shell_variables() {
+        assert_eq!(expand("echo $${HOME} $PATH").unwrap(), "echo ${HOME} $PATH");
+    }
+
+    #[test]
+    fn should_report_unresolved_variables() {
+        let err = expand("${MISSING}").unwrap_err().at("worktree.onCreate[0]");
+        assert_eq!(
+            err.to_string(),
+            "worktree.onCreate[0]: environment variable `MISSING` is not set, use `${MISSING:-default}` to provide a default"
+        );
+
+        let err = interpolate_with("${worktree.branch}", &InterpolationContext::default(), &env)
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            "`${worktree.branch}` is only available for worktrees"
+        );
+
+        assert!(expand("${workspace.name}").is_err());
+        assert!(expand("${EDITOR").is_err());
+        assert!(expand("${1abc}").is_err());
+    }
+}
@@ -1,5 +1,12 @@
 pub mod data_with_path;
 pub mod display;
+pub mod env;
+pub mod fuzzy;
+pub mod interpolation;
+pub mod parallel;
 pub mod path;
+pub mod prompt;
+pub mod ranking;
+pub mod table;
 pub mod with_terminal;
 pub mod workspace;
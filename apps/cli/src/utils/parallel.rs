@@ -0,0 +1,72 @@
+//! Work that is done side by side on a limited number of threads
+
+use std::{collections::VecDeque, sync::Mutex, thread};
+
+/// Apply `f` to every item with at most `parallel` items being worked on at once.
+///
+/// Items are picked up in order, the results are returned in the order of the items.
+pub fn map_bounded<T: Send, R: Send>(
+    items: Vec<T>,
+    parallel: usize,
+    f: impl Fn(T) -> R + Sync,
+) -> Vec<R> {
+    let count = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate().collect::<VecDeque<_>>());
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..count).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..parallel.clamp(1, count.max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let Some((index, item)) = queue.lock().unwrap().pop_front() else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn should_keep_the_order_of_the_items() {
+        let result = map_bounded((0..20).collect(), 4, |x: u64| {
+            thread::sleep(Duration::from_millis(20 - x));
+            x * 2
+        });
+
+        assert_eq!(result, (0..20).map(|x| x * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn should_not_run_more_than_parallel_at_once() {
+        let running = AtomicUsize::new(0);
+        let most = AtomicUsize::new(0);
+
+        map_bounded((0..12).collect(), 3, |_: u32| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            most.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert!(most.load(Ordering::SeqCst) <= 3);
+    }
+}
@@ -0,0 +1,11 @@
+//! Prompts for commands that ask the user before changing something
+
+use inquire::Confirm;
+
+/// Ask a yes or no question, a prompt that can not be shown or is cancelled counts as no
+pub fn confirm(message: &str, default: bool) -> bool {
+    Confirm::new(message)
+        .with_default(default)
+        .prompt()
+        .unwrap_or(false)
+}
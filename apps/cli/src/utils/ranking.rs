@@ -0,0 +1,66 @@
+use crate::storage::frecency::Frecency;
+
+/// Order items so the most important and most used ones come first.
+///
+/// Importance decides first and frecency breaks ties, items that rank the same keep their
+/// original order. `rank_of` returns the importance and the frecency key of an item.
+pub fn sort_by_rank<T>(
+    items: Vec<T>,
+    frecency: &Frecency,
+    now: i64,
+    rank_of: impl Fn(&T) -> (i32, String),
+) -> Vec<T> {
+    let mut ranked: Vec<(i32, f64, T)> = items
+        .into_iter()
+        .map(|item| {
+            let (importance, key) = rank_of(&item);
+            (importance, frecency.score(&key, now), item)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.total_cmp(&a.1)));
+    ranked.into_iter().map(|(_, _, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn rank(items: &[(&'static str, i32)], frecency: &Frecency) -> Vec<&'static str> {
+        sort_by_rank(items.to_vec(), frecency, NOW, |(key, importance)| {
+            (*importance, key.to_string())
+        })
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect()
+    }
+
+    #[test]
+    fn should_keep_order_without_history() {
+        let items = [("a", 0), ("b", 0), ("c", 0)];
+
+        assert_eq!(rank(&items, &Frecency::default()), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn should_put_most_used_first() {
+        let mut frecency = Frecency::default();
+        frecency.visit("c", NOW - 60);
+        frecency.visit("c", NOW - 30);
+        frecency.visit("b", NOW - 10);
+        let items = [("a", 0), ("b", 0), ("c", 0)];
+
+        assert_eq!(rank(&items, &frecency), vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn should_rank_importance_above_frecency() {
+        let mut frecency = Frecency::default();
+        frecency.visit("a", NOW);
+        let items = [("a", 0), ("b", 1), ("c", -1)];
+
+        assert_eq!(rank(&items, &frecency), vec!["b", "a", "c"]);
+    }
+}
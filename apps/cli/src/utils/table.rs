@@ -0,0 +1,58 @@
+/// Lay out rows as left aligned columns below a header.
+///
+/// Columns are separated by two spaces and the last column is not padded, so lines have no
+/// trailing whitespace.
+pub fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|x| x.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header: Vec<String> = headers.iter().map(|x| x.to_string()).collect();
+    std::iter::once(&header)
+        .chain(rows)
+        .map(|row| {
+            let last = row.len().saturating_sub(1);
+            row.iter()
+                .enumerate()
+                .map(|(index, cell)| {
+                    if index == last {
+                        cell.clone()
+                    } else {
+                        format!("{:width$}", cell, width = widths[index])
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("  ")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_align_columns() {
+        let rows = vec![
+            vec!["api".to_string(), "exit 0".to_string(), "1.0s".to_string()],
+            vec![
+                "website".to_string(),
+                "skipped".to_string(),
+                "0.0s".to_string(),
+            ],
+        ];
+
+        let result = format_table(&["WORKSPACE", "STATUS", "DURATION"], &rows);
+
+        assert_eq!(
+            result,
+            "WORKSPACE  STATUS   DURATION\n\
+             api        exit 0   1.0s\n\
+             website    skipped  0.0s"
+        );
+    }
+}
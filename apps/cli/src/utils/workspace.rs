@@ -2,11 +2,19 @@ use serde_json::{Value, json};
 
 use crate::storage::workspace::{Workspace, WorkspaceStorage};
 
-use super::{data_with_path::DataWithPath, display::RafaeltabDisplayItem, path::expand_path};
+use super::{
+    data_with_path::DataWithPath,
+    display::RafaeltabDisplayItem,
+    env::{EnvError, Environment, resolve_env},
+    fuzzy::fuzzy_score,
+    interpolation::{InterpolationContext, InterpolationError, interpolate_variables},
+    path::expand_path,
+};
 
+/// Every workspace with its resolved root, each on its own so lists can skip the ones that fail
 pub fn get_workspace_paths<TWorkspaceStorage: WorkspaceStorage>(
     workspace_storage: &TWorkspaceStorage,
-) -> Vec<DataWithPath<Workspace>> {
+) -> Vec<Result<DataWithPath<Workspace>, InterpolationError>> {
     workspace_storage
         .read()
         .iter()
@@ -25,9 +33,124 @@ pub fn find_workspace<TWorkspaceStorage: WorkspaceStorage>(
         .find(|x| x.id == id)
 }
 
+/// The outcome of looking up a workspace by a user supplied name
+#[derive(Debug, PartialEq)]
+pub enum WorkspaceLookup {
+    Found(Box<Workspace>),
+    /// Several workspaces match equally well, the best matches come first
+    Ambiguous(Vec<Workspace>),
+    NotFound,
+}
+
+/// Look up a workspace by id, alias or name.
+///
+/// The first step that matches anything decides:
+/// 1. the exact id or alias
+/// 2. the id, alias or name ignoring case
+/// 3. a prefix of the id, alias or name, ignoring case
+/// 4. a fuzzy match on the id, alias or name, using the matcher of the pickers. The best match is
+///    used unless other matches score almost as well
+///
+/// With `exact` only the first step is used.
+pub fn lookup_workspace(workspaces: &[Workspace], query: &str, exact: bool) -> WorkspaceLookup {
+    let by_step = |matches: &dyn Fn(&str) -> bool| -> Vec<Workspace> {
+        workspaces
+            .iter()
+            .filter(|x| x.lookup_names().any(matches))
+            .cloned()
+            .collect()
+    };
+
+    let exact_matches: Vec<Workspace> = workspaces
+        .iter()
+        .filter(|x| x.id == query || x.aliases.iter().flatten().any(|alias| alias == query))
+        .cloned()
+        .collect();
+    if exact || !exact_matches.is_empty() {
+        return WorkspaceLookup::from_matches(exact_matches);
+    }
+
+    let query_lower = query.to_lowercase();
+    let steps: [&dyn Fn(&str) -> bool; 2] = [&|name| name.to_lowercase() == query_lower, &|name| {
+        name.to_lowercase().starts_with(&query_lower)
+    }];
+    for step in steps {
+        let matches = by_step(step);
+        if !matches.is_empty() {
+            return WorkspaceLookup::from_matches(matches);
+        }
+    }
+
+    let mut scored: Vec<(isize, &Workspace)> = workspaces
+        .iter()
+        .filter_map(|x| {
+            x.lookup_names()
+                .filter_map(|name| fuzzy_score(query, name))
+                .max()
+                .map(|score| (score, x))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    // A clearly better match wins, only matches that score close to the best one are ambiguous
+    let best = scored.first().map_or(0, |(score, _)| *score);
+    WorkspaceLookup::from_matches(
+        scored
+            .into_iter()
+            .filter(|(score, _)| is_near_tie(*score, best))
+            .map(|(_, x)| x.clone())
+            .collect(),
+    )
+}
+
+/// Whether a fuzzy score is within a tenth of the best score
+fn is_near_tie(score: isize, best: isize) -> bool {
+    best - score <= best.abs() / 10
+}
+
+impl WorkspaceLookup {
+    fn from_matches(mut matches: Vec<Workspace>) -> Self {
+        match matches.len() {
+            0 => WorkspaceLookup::NotFound,
+            1 => WorkspaceLookup::Found(Box::new(matches.remove(0))),
+            _ => WorkspaceLookup::Ambiguous(matches),
+        }
+    }
+}
+
 impl Workspace {
-    pub fn load_path(&self) -> DataWithPath<Workspace> {
-        DataWithPath::new(self.clone(), expand_path(&self.root))
+    /// The names a workspace can be looked up by: its id, aliases and name
+    fn lookup_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.id.as_str())
+            .chain(self.aliases.iter().flatten().map(|x| x.as_str()))
+            .chain(std::iter::once(self.name.as_str()))
+    }
+
+    pub fn load_path(&self) -> Result<DataWithPath<Workspace>, InterpolationError> {
+        let root = self.resolved_root()?;
+        Ok(DataWithPath::new(self.clone(), expand_path(&root)))
+    }
+
+    /// The root with its variables expanded, only `${workspace.id}` is available as a built-in
+    pub fn resolved_root(&self) -> Result<String, InterpolationError> {
+        let context = InterpolationContext {
+            workspace_id: Some(self.id.clone()),
+            ..Default::default()
+        };
+        interpolate_variables(&self.root, &context)
+            .map_err(|err| err.at(format!("workspaces[id={}].root", self.id)))
+    }
+
+    /// The environment variables from `envFile` and `env`, with their variables expanded
+    pub fn resolved_env(&self) -> Result<Environment, EnvError> {
+        let root = expand_path(&self.resolved_root()?);
+        let context = InterpolationContext::for_workspace(&self.id, &root);
+        resolve_env(
+            self.env.as_ref(),
+            self.env_file.as_deref(),
+            &root,
+            &context,
+            &format!("workspaces[id={}]", self.id),
+        )
     }
 }
 
@@ -83,3 +206,104 @@ impl RafaeltabDisplayItem for DataWithPath<Workspace> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(id: &str, name: &str, aliases: &[&str]) -> Workspace {
+        Workspace {
+            root: "/tmp".to_string(),
+            id: id.to_string(),
+            name: name.to_string(),
+            aliases: Some(aliases.iter().map(|x| x.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    fn workspaces() -> Vec<Workspace> {
+        vec![
+            workspace("payments_api", "Payments Api", &["pay"]),
+            workspace("payments_web", "Payments Web", &[]),
+            workspace("dotfiles", "Dotfiles", &[]),
+        ]
+    }
+
+    fn found_id(lookup: WorkspaceLookup) -> String {
+        match lookup {
+            WorkspaceLookup::Found(workspace) => workspace.id,
+            other => panic!("Expected a single workspace, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_find_by_id_alias_and_name() {
+        assert_eq!(
+            found_id(lookup_workspace(&workspaces(), "dotfiles", false)),
+            "dotfiles"
+        );
+        assert_eq!(
+            found_id(lookup_workspace(&workspaces(), "pay", false)),
+            "payments_api"
+        );
+        assert_eq!(
+            found_id(lookup_workspace(&workspaces(), "payments web", false)),
+            "payments_web"
+        );
+    }
+
+    #[test]
+    fn should_find_by_prefix_and_fuzzy_match() {
+        assert_eq!(
+            found_id(lookup_workspace(&workspaces(), "dot", false)),
+            "dotfiles"
+        );
+        assert_eq!(
+            found_id(lookup_workspace(&workspaces(), "dtfl", false)),
+            "dotfiles"
+        );
+    }
+
+    #[test]
+    fn should_report_ambiguous_matches() {
+        let WorkspaceLookup::Ambiguous(candidates) =
+            lookup_workspace(&workspaces(), "payments", false)
+        else {
+            panic!("Expected an ambiguous lookup");
+        };
+        let ids: Vec<&str> = candidates.iter().map(|x| x.id.as_str()).collect();
+        assert_eq!(ids, vec!["payments_api", "payments_web"]);
+    }
+
+    #[test]
+    fn should_pick_a_clearly_better_fuzzy_match() {
+        assert_eq!(
+            found_id(lookup_workspace(&workspaces(), "pmtsweb", false)),
+            "payments_web"
+        );
+    }
+
+    #[test]
+    fn should_report_fuzzy_matches_that_score_almost_the_same() {
+        let WorkspaceLookup::Ambiguous(candidates) =
+            lookup_workspace(&workspaces(), "pymnts", false)
+        else {
+            panic!("Expected an ambiguous lookup");
+        };
+        let mut ids: Vec<&str> = candidates.iter().map(|x| x.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["payments_api", "payments_web"]);
+    }
+
+    #[test]
+    fn should_only_accept_ids_and_aliases_when_exact() {
+        assert_eq!(
+            lookup_workspace(&workspaces(), "dot", true),
+            WorkspaceLookup::NotFound
+        );
+        assert_eq!(
+            found_id(lookup_workspace(&workspaces(), "pay", true)),
+            "payments_api"
+        );
+    }
+}
@@ -56,6 +56,19 @@ fn builder_with_env_extracts_from_environment() {
     assert!(envs.contains_key("RAFAELTAB_TMUX_SOCKET"));
 }
 
+#[test]
+fn builder_keeps_state_file_next_to_config() {
+    let cmd = CliCommandBuilder::new()
+        .with_config("/path/to/config.json")
+        .build();
+
+    let envs = cmd.build_env();
+    assert_eq!(
+        envs.get("RAFAELTAB_STATE_FILE"),
+        Some(&"/path/to/.rafaeltab-state.json".to_string())
+    );
+}
+
 #[test]
 fn builder_with_cwd_sets_working_directory() {
     let cmd = CliCommandBuilder::new()
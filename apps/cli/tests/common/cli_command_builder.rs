@@ -7,7 +7,7 @@ use test_descriptors::testers::Command;
 ///
 /// This is a convenience wrapper around `Command` that automatically
 /// configures rafaeltab-specific options like the binary path, config path,
-/// and tmux socket.
+/// tmux socket and state file.
 ///
 /// # Example
 ///
@@ -23,6 +23,7 @@ pub struct CliCommandBuilder {
     args: Vec<String>,
     config_path: Option<PathBuf>,
     tmux_socket: Option<String>,
+    state_file: Option<PathBuf>,
     cwd: Option<PathBuf>,
     stdin: Option<String>,
     extra_envs: HashMap<String, String>,
@@ -35,21 +36,24 @@ impl CliCommandBuilder {
             args: Vec::new(),
             config_path: None,
             tmux_socket: None,
+            state_file: None,
             cwd: None,
             stdin: None,
             extra_envs: HashMap::new(),
         }
     }
 
-    /// Configure using TestEnvironment (sets config path and tmux socket)
+    /// Configure using TestEnvironment (sets config path, tmux socket and state file)
     ///
     /// This extracts the config path and tmux socket from the test environment
-    /// and applies them to the command.
+    /// and applies them to the command. The usage history is kept inside the
+    /// test environment.
     pub fn with_env(mut self, env: &TestEnvironment) -> Self {
         if let Some(config_path) = env.context().config_path() {
             self.config_path = Some(config_path);
         }
         self.tmux_socket = Some(env.tmux_socket().to_string());
+        self.state_file = Some(env.root_path().join(".rafaeltab-state.json"));
         self
     }
 
@@ -108,6 +112,8 @@ impl CliCommandBuilder {
     /// - The rafaeltab binary path
     /// - `--config <path>` prepended to args (if config path is set)
     /// - `RAFAELTAB_TMUX_SOCKET` environment variable (if tmux socket is set)
+    /// - `RAFAELTAB_STATE_FILE` environment variable, next to the config file
+    ///   when no test environment is used
     /// - Any additional environment variables
     /// - Working directory (if set)
     pub fn build(self) -> Command {
@@ -131,6 +137,19 @@ impl CliCommandBuilder {
             cmd = cmd.env("RAFAELTAB_TMUX_SOCKET", socket);
         }
 
+        // Keep the usage history out of the home directory
+        let state_file = self.state_file.clone().or_else(|| {
+            self.config_path
+                .as_ref()
+                .map(|config_path| config_path.with_file_name(".rafaeltab-state.json"))
+        });
+        if let Some(ref state_file) = state_file {
+            cmd = cmd.env(
+                "RAFAELTAB_STATE_FILE",
+                state_file.to_string_lossy().to_string(),
+            );
+        }
+
         // Add extra environment variables
         for (key, value) in &self.extra_envs {
             cmd = cmd.env(key, value);
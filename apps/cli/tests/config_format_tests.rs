@@ -0,0 +1,137 @@
+mod common;
+
+use common::CliCommandBuilder;
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+/// Test that a TOML config file is picked up by its extension
+#[test]
+pub fn test_toml_config_file() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.toml");
+
+    let input = r#"
+[[workspaces]]
+root = "~/toml/path"
+id = "toml_workspace"
+name = "Toml Workspace"
+tags = ["toml"]
+
+[tmux]
+sessions = []
+defaultWindows = []
+"#;
+
+    std::fs::write(&config_path, input).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["workspace", "list"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace list with a TOML config failed: {}",
+        result.stderr
+    );
+    assert!(
+        result.stdout.contains("Toml Workspace"),
+        "Output should contain the workspace from the TOML config.\nGot: {}",
+        result.stdout
+    );
+}
+
+/// Test that `config convert` produces a YAML file the CLI can read
+#[test]
+pub fn test_config_convert_json_to_yaml() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let json_path = env.root_path().join("config.json");
+    let yaml_path = env.root_path().join("config.yaml");
+
+    let input = r#"{
+  "workspaces": [
+    {
+      "root": "~/yaml/path",
+      "id": "yaml_workspace",
+      "name": "Yaml Workspace",
+      "tags": ["yaml"]
+    }
+  ],
+  "tmux": {
+    "sessions": [],
+    "defaultWindows": [{ "name": "shell" }]
+  },
+  "worktree": {
+    "onCreate": ["pnpm install", "pnpm build"]
+  }
+}
+"#;
+
+    std::fs::write(&json_path, input).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .args(&[
+            "config",
+            "convert",
+            &json_path.to_string_lossy(),
+            &yaml_path.to_string_lossy(),
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "config convert failed: {}", result.stderr);
+
+    let yaml = std::fs::read_to_string(&yaml_path).expect("YAML file should be written");
+    assert!(
+        yaml.contains("pnpm build"),
+        "YAML should contain the worktree commands.\nGot: {}",
+        yaml
+    );
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&yaml_path)
+        .args(&["workspace", "list"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.stdout.contains("Yaml Workspace"),
+        "Output should contain the workspace from the converted config.\nGot: {}",
+        result.stdout
+    );
+}
+
+/// Test that `config convert` refuses to overwrite an existing file
+#[test]
+pub fn test_config_convert_refuses_to_overwrite() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let json_path = env.root_path().join("config.json");
+    let toml_path = env.root_path().join("config.toml");
+
+    std::fs::write(&json_path, r#"{ "workspaces": [] }"#).expect("Failed to write config");
+    std::fs::write(&toml_path, "# keep me\n").expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .args(&[
+            "config",
+            "convert",
+            &json_path.to_string_lossy(),
+            &toml_path.to_string_lossy(),
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        !result.success,
+        "config convert should fail without --force"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&toml_path).unwrap(),
+        "# keep me\n",
+        "Existing file should not be touched"
+    );
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+#[test]
+fn test_config_get_value_by_id() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "get", "workspaces[id=api].tags", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "config get should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+    let value: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    assert_eq!(value, serde_json::json!(["work"]));
+}
+
+#[test]
+fn test_config_set_appends_and_persists() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "set", "worktree.onCreate[+]", "pnpm install"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "config set should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "get", "worktree.onCreate", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert_eq!(
+        result.stdout.trim(),
+        r#"["pnpm install"]"#,
+        "The appended command should be stored. STDERR: {}",
+        result.stderr
+    );
+}
+
+#[test]
+fn test_config_set_rejects_invalid_values() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                });
+            });
+        });
+    })
+    .create();
+
+    for (path, value) in [("worktree.tmux", "yes"), ("workspaces[id=api].tagz", "[]")] {
+        let cmd = CliCommandBuilder::new()
+            .with_env(&env)
+            .args(&["config", "set", path, value])
+            .build();
+        let result = env.testers().cmd().run(&cmd);
+
+        assert!(
+            !result.success,
+            "Setting {} to {} should fail.\nSTDOUT: {}",
+            path, value, result.stdout
+        );
+        assert!(
+            result.stderr.contains("Error:"),
+            "Expected an error message. Got: {}",
+            result.stderr
+        );
+    }
+}
+
+#[test]
+fn test_config_unset_removes_value() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "unset", "workspaces[id=api].tags"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "config unset should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "get", "workspaces[id=api].tags"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        !result.success,
+        "The tags should be gone. Got: {}",
+        result.stdout
+    );
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use common::CliCommandBuilder;
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+const TEAM: &str = r#"{
+  "version": 1,
+  "workspaces": [
+    { "id": "team_api", "name": "Team Api", "root": "/tmp" }
+  ]
+}"#;
+
+const PERSONAL: &str = r#"{
+  "version": 1,
+  "include": ["team/*.json"],
+  "workspaces": [
+    { "id": "notes", "name": "Notes", "root": "/tmp" }
+  ]
+}"#;
+
+/// Test that included workspaces are listed together with the file they come from
+#[test]
+pub fn test_included_workspaces_report_their_source() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let team_dir = env.root_path().join("team");
+    std::fs::create_dir_all(&team_dir).expect("Failed to create team dir");
+    let team_path = team_dir.join("shared.json");
+    std::fs::write(&team_path, TEAM).expect("Failed to write team config");
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, PERSONAL).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["workspace", "list", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "workspace list failed: {}", result.stderr);
+    let workspaces: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    let source_of = |id: &str| {
+        workspaces
+            .as_array()
+            .expect("Output should be a list")
+            .iter()
+            .find(|x| x["id"] == id)
+            .unwrap_or_else(|| panic!("{} should be listed", id))["source"]
+            .clone()
+    };
+    assert_eq!(source_of("team_api"), team_path.to_string_lossy().as_ref());
+    assert_eq!(source_of("notes"), config_path.to_string_lossy().as_ref());
+}
+
+/// Test that new workspaces are written to the personal file, not the included one
+#[test]
+pub fn test_new_workspace_is_written_to_personal_file() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let team_dir = env.root_path().join("team");
+    std::fs::create_dir_all(&team_dir).expect("Failed to create team dir");
+    let team_path = team_dir.join("shared.json");
+    std::fs::write(&team_path, TEAM).expect("Failed to write team config");
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, PERSONAL).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&[
+            "config",
+            "set",
+            "workspaces[+]",
+            r#"{"id":"web","name":"Web","root":"/tmp"}"#,
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "config set failed: {}", result.stderr);
+    let personal = std::fs::read_to_string(&config_path).expect("Failed to read config");
+    let team = std::fs::read_to_string(&team_path).expect("Failed to read team config");
+    assert!(
+        personal.contains("\"web\""),
+        "The personal file should contain the new workspace.\nGot: {}",
+        personal
+    );
+    assert_eq!(team, TEAM, "The included file should not be changed");
+}
+
+/// Test that changing an included workspace writes an override to the personal file
+#[test]
+pub fn test_changed_included_workspace_is_written_to_personal_file() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let team_dir = env.root_path().join("team");
+    std::fs::create_dir_all(&team_dir).expect("Failed to create team dir");
+    let team_path = team_dir.join("shared.json");
+    std::fs::write(&team_path, TEAM).expect("Failed to write team config");
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, PERSONAL).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["config", "set", "workspaces[id=team_api].name", "Renamed"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "config set failed: {}", result.stderr);
+    let personal = std::fs::read_to_string(&config_path).expect("Failed to read config");
+    let team = std::fs::read_to_string(&team_path).expect("Failed to read team config");
+    assert!(
+        personal.contains("\"team_api\"") && personal.contains("\"Renamed\""),
+        "The personal file should override the included workspace.\nGot: {}",
+        personal
+    );
+    assert_eq!(team, TEAM, "The included file should not be changed");
+}
+
+/// Test that removing an included workspace fails and names the included file
+#[test]
+pub fn test_removing_included_workspace_names_included_file() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let team_dir = env.root_path().join("team");
+    std::fs::create_dir_all(&team_dir).expect("Failed to create team dir");
+    let team_path = team_dir.join("shared.json");
+    std::fs::write(&team_path, TEAM).expect("Failed to write team config");
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, PERSONAL).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["config", "unset", "workspaces[id=team_api]"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        !result.success,
+        "Removing an included workspace should fail"
+    );
+    assert!(
+        result.stderr.contains(team_path.to_string_lossy().as_ref()),
+        "The error should name the included file.\nGot: {}",
+        result.stderr
+    );
+    let team = std::fs::read_to_string(&team_path).expect("Failed to read team config");
+    assert_eq!(team, TEAM, "The included file should not be changed");
+}
+
+/// Test that an include that does not exist is reported with its location
+#[test]
+pub fn test_missing_include_is_reported() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(
+        &config_path,
+        r#"{ "version": 1, "include": ["missing.json"] }"#,
+    )
+    .expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["config", "validate"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "A missing include should be a problem");
+    assert!(
+        result.stdout.contains("include[0]") && result.stdout.contains("does not exist"),
+        "Output should name the include.\nGot: {}",
+        result.stdout
+    );
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::CliCommandBuilder;
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+const UNVERSIONED: &str = r#"{
+  "workspaces": [
+    {
+      "root": "~/old/path",
+      "id": "old_workspace",
+      "name": "Old Workspace",
+      "tags": null
+    }
+  ],
+  "tmux": {
+    "sessions": [],
+    "defaultWindows": []
+  },
+  "worktree": null
+}"#;
+
+/// Test that `config migrate --dry-run` shows the diff without touching the file
+#[test]
+pub fn test_config_migrate_dry_run() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, UNVERSIONED).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["config", "migrate", "--dry-run"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "config migrate --dry-run failed: {}",
+        result.stderr
+    );
+    assert!(
+        result.stdout.contains("-  \"worktree\": null")
+            && result.stdout.contains("+  \"version\": 1"),
+        "Output should contain a diff of the migration.\nGot: {}",
+        result.stdout
+    );
+
+    let content = std::fs::read_to_string(&config_path).expect("Failed to read config");
+    assert_eq!(content, UNVERSIONED, "A dry run should not change the file");
+}
+
+/// Test that loading an old config migrates it in memory and leaves the file alone
+#[test]
+pub fn test_old_config_is_migrated_on_load() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, UNVERSIONED).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["workspace", "list"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace list with an old config failed: {}",
+        result.stderr
+    );
+    assert!(
+        result.stdout.contains("Old Workspace"),
+        "Output should contain the workspace from the old config.\nGot: {}",
+        result.stdout
+    );
+
+    let content = std::fs::read_to_string(&config_path).expect("Failed to read config");
+    assert_eq!(content, UNVERSIONED, "Loading should not change the file");
+    assert!(
+        !env.root_path().join("config.json.v0.bak").exists(),
+        "Loading should not write a backup"
+    );
+}
+
+/// Test that `config migrate` rewrites an old config and keeps a backup of the original
+#[test]
+pub fn test_config_migrate_rewrites_old_config() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, UNVERSIONED).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["config", "migrate"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "config migrate failed: {}", result.stderr);
+
+    let migrated: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&config_path).expect("Failed to read config"),
+    )
+    .expect("Migrated config should be valid JSON");
+    assert_eq!(
+        migrated["version"], 1,
+        "Config should be stamped with a version"
+    );
+
+    let backup_path = env.root_path().join("config.json.v0.bak");
+    let backup = std::fs::read_to_string(&backup_path).expect("Backup should be written");
+    assert_eq!(
+        backup, UNVERSIONED,
+        "Backup should contain the original file"
+    );
+}
+
+/// Test that a config written by a newer version is rejected instead of being rewritten
+#[test]
+pub fn test_newer_config_is_rejected() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    let input = r#"{ "version": 999, "workspaces": [] }"#;
+    std::fs::write(&config_path, input).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["workspace", "list"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "An unsupported version should fail");
+    assert!(
+        result.stderr.contains("update rafaeltab"),
+        "Error should explain how to fix it.\nGot: {}",
+        result.stderr
+    );
+    let content = std::fs::read_to_string(&config_path).expect("Failed to read config");
+    assert_eq!(content, input, "The newer config should not be changed");
+}
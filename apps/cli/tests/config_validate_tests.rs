@@ -0,0 +1,116 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+#[test]
+fn test_config_schema_prints_json_schema() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let cmd = CliCommandBuilder::new().args(&["config", "schema"]).build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "config schema should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+
+    let schema: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    assert!(
+        schema["properties"]["workspaces"].is_object(),
+        "Schema should describe workspaces. Got: {}",
+        schema
+    );
+}
+
+#[test]
+fn test_config_validate_accepts_valid_config() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.defaults();
+            c.tmux_session("valid_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("valid_ws", |d| {
+                d.rafaeltab_workspace("valid_ws", "Valid Workspace", |w| {
+                    w.tag("valid");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "validate"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "config validate should succeed for a valid config.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+}
+
+#[test]
+fn test_config_validate_reports_problems_with_position() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    let input = r#"{
+  "workspaces": [],
+  "tmux": {
+    "sessions": [{ "workspace": "missing_ws", "windows": [] }],
+    "defaultWindows": []
+  },
+  "worktree": {
+    "symlinkfiles": [".env"]
+  }
+}
+"#;
+    std::fs::write(&config_path, input).expect("Failed to write config");
+
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&["config", "validate", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        !result.success,
+        "config validate should fail for an invalid config"
+    );
+
+    let problems: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    let problems = problems.as_array().expect("Output should be a list");
+    assert_eq!(
+        problems.len(),
+        2,
+        "Expected two problems. Got: {:?}",
+        problems
+    );
+
+    let unknown_key = problems
+        .iter()
+        .find(|x| x["path"] == "worktree.symlinkfiles")
+        .expect("Unknown key should be reported");
+    assert_eq!(unknown_key["line"], 8);
+    assert_eq!(unknown_key["column"], 5);
+
+    assert!(
+        problems
+            .iter()
+            .any(|x| x["path"] == "tmux.sessions[0].workspace"),
+        "Dangling session should be reported. Got: {:?}",
+        problems
+    );
+}
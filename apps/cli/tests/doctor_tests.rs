@@ -0,0 +1,161 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn run_doctor(env: &TestEnvironment, args: &[&str]) -> (bool, serde_json::Value) {
+    let mut all_args = vec!["doctor", "--json"];
+    all_args.extend_from_slice(args);
+    let cmd = CliCommandBuilder::new()
+        .with_env(env)
+        .args(&all_args)
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    let report = serde_json::from_str(&result.stdout).unwrap_or_else(|_| {
+        panic!(
+            "Output should be valid JSON.\nSTDOUT: {}\nSTDERR: {}",
+            result.stdout, result.stderr
+        )
+    });
+    (result.success, report)
+}
+
+fn check_status(report: &serde_json::Value, name: &str) -> String {
+    report["checks"]
+        .as_array()
+        .expect("checks should be an array")
+        .iter()
+        .find(|x| x["name"] == name)
+        .unwrap_or_else(|| panic!("Report should have a `{}` check", name))["status"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_doctor_fails_for_missing_workspace_root() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|_c| {});
+
+        root.test_dir(|td| {
+            td.dir("doctor_ws", |d| {
+                d.rafaeltab_workspace("doctor_ws", "Doctor Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "config",
+            "set",
+            "workspaces[id=doctor_ws].root",
+            "/nonexistent/doctor_ws",
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config set should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+
+    let (success, report) = run_doctor(&env, &[]);
+
+    assert!(
+        !success,
+        "doctor should fail when a workspace root is missing"
+    );
+    assert_eq!(report["status"], "fail");
+    assert_eq!(check_status(&report, "workspace roots"), "fail");
+}
+
+#[test]
+fn test_doctor_warns_about_sessions_of_unknown_workspaces() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|_c| {});
+
+        root.test_dir(|td| {
+            td.dir("doctor_ws", |d| {
+                d.rafaeltab_workspace("doctor_ws", "Doctor Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "config",
+            "set",
+            "tmux.sessions",
+            r#"[{ "workspace": "ghost", "windows": [] }]"#,
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config set should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+
+    let (_, report) = run_doctor(&env, &[]);
+
+    assert_eq!(check_status(&report, "sessions"), "warn");
+}
+
+#[test]
+fn test_doctor_fix_removes_sessions_of_unknown_workspaces() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|_c| {});
+
+        root.test_dir(|td| {
+            td.dir("doctor_ws", |d| {
+                d.rafaeltab_workspace("doctor_ws", "Doctor Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "config",
+            "set",
+            "tmux.sessions",
+            r#"[{ "workspace": "ghost", "windows": [] }, { "workspace": "doctor_ws", "windows": [] }]"#,
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config set should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+
+    let (_, report) = run_doctor(&env, &["--fix"]);
+    assert_eq!(check_status(&report, "sessions"), "pass");
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "get", "tmux.sessions", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config get should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let sessions: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    assert_eq!(
+        sessions,
+        serde_json::json!([{ "workspace": "doctor_ws", "windows": [] }]),
+        "Only the session of the known workspace should be left"
+    );
+}
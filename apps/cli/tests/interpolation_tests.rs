@@ -0,0 +1,84 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+#[test]
+fn test_window_commands_expand_built_ins() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window_with_command("editor", "echo ${workspace.id} ${EDITOR_UNSET:-vi}");
+        });
+
+        root.test_dir(|td| {
+            td.dir("interp_ws", |d| {
+                d.rafaeltab_workspace("interp_ws", "Interpolated Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "list", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "tmux list should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+
+    let sessions: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    let session = sessions
+        .as_array()
+        .expect("Output should be a list")
+        .iter()
+        .find(|x| x["name"] == "Interpolated Workspace")
+        .expect("The workspace session should be listed");
+    assert_eq!(
+        session["windows"][0]["command"], "echo interp_ws vi",
+        "Variables in the window command should be expanded"
+    );
+}
+
+#[test]
+fn test_unresolved_variable_reports_location() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window_with_command("logs", "tail -f ${RAFAELTAB_TEST_UNSET_VARIABLE}");
+        });
+
+        root.test_dir(|td| {
+            td.dir("unset_ws", |d| {
+                d.rafaeltab_workspace("unset_ws", "Unset Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "list"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        !result.success,
+        "An unresolved variable should fail.\nSTDOUT: {}",
+        result.stdout
+    );
+    assert!(
+        result
+            .stderr
+            .contains("tmux.defaultWindows[0].command: environment variable `RAFAELTAB_TEST_UNSET_VARIABLE` is not set"),
+        "Error should name the config location. Got: {}",
+        result.stderr
+    );
+}
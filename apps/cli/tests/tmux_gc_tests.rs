@@ -0,0 +1,89 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn gc(env: &TestEnvironment, args: &[&str]) -> test_descriptors::testers::CommandResult {
+    let mut all_args = vec!["tmux", "gc"];
+    all_args.extend_from_slice(args);
+    let cmd = CliCommandBuilder::new()
+        .with_env(env)
+        .args(&all_args)
+        .build();
+    env.testers().cmd().run(&cmd)
+}
+
+#[test]
+fn test_gc_kills_sessions_whose_directory_is_gone() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("gc_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("gc_ws", |d| {
+                d.rafaeltab_workspace("gc_ws", "gc ws", |_w| {});
+            });
+            td.dir("gc_removed", |_d| {});
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+    let removed = env.find_dir("gc_removed").expect("Dir not found");
+    let removed_path = removed.path().to_string_lossy().to_string();
+    env.tmux()
+        .run_tmux(&["new-session", "-d", "-s", "gc_orphan", "-c", &removed_path])
+        .expect("Failed to create session");
+    std::fs::remove_dir_all(removed.path()).expect("Failed to remove directory");
+
+    let result = gc(&env, &["--yes"]);
+
+    assert!(result.success, "Command failed: {}", result.stderr);
+    assert!(
+        result.stdout.contains("Killed 'gc_orphan'"),
+        "Unexpected output: {}",
+        result.stdout
+    );
+    assert!(!env.tmux().session_exists("gc_orphan"));
+    assert!(env.tmux().session_exists("gc ws"));
+}
+
+#[test]
+fn test_gc_keeps_sessions_that_are_still_described() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("gc_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("gc_ws", |d| {
+                d.rafaeltab_workspace("gc_ws", "gc ws", |_w| {});
+            });
+            td.dir("gc_removed", |_d| {});
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+
+    let result = gc(&env, &["--yes"]);
+
+    assert!(result.success, "Command failed: {}", result.stderr);
+    assert!(result.stdout.contains("No orphaned sessions"));
+    assert!(env.tmux().session_exists("gc ws"));
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn start_with_windows(windows: &str) -> TestEnvironment {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("layout_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("layout_ws", |d| {
+                d.dir("src", |_d| {});
+                d.rafaeltab_workspace("layout_ws", "layout ws", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    for args in [
+        vec!["config", "set", "tmux.sessions[0].windows", windows],
+        vec!["tmux", "start"],
+    ] {
+        let cmd = CliCommandBuilder::new().with_env(&env).args(&args).build();
+        let result = env.testers().cmd().run(&cmd);
+        assert!(
+            result.success,
+            "Command {:?} failed:\nstdout: {}\nstderr: {}",
+            args, result.stdout, result.stderr
+        );
+    }
+
+    env
+}
+
+fn list_panes(env: &TestEnvironment, window: &str, format: &str) -> Vec<String> {
+    env.tmux()
+        .run_tmux(&[
+            "list-panes",
+            "-t",
+            &format!("layout ws:{}", window),
+            "-F",
+            format,
+        ])
+        .expect("Failed to list panes")
+        .lines()
+        .map(|x| x.to_string())
+        .collect()
+}
+
+#[test]
+fn test_start_splits_windows_into_panes() {
+    let env = start_with_windows(
+        r#"[
+            { "name": "dev", "panes": [
+                { "command": "sleep 1000" },
+                { "split": "vertical", "panes": [{ "cwd": "src", "focus": true }, {}] }
+            ] },
+            { "name": "logs", "panes": [{}, {}, {}] }
+        ]"#,
+    );
+
+    let dev = list_panes(
+        &env,
+        "dev",
+        "#{pane_active} #{b:pane_current_path} #{pane_start_command}",
+    );
+    assert_eq!(dev.len(), 3, "Expected 3 panes in dev, found {:?}", dev);
+    assert!(
+        dev[0].starts_with("0 layout_ws") && dev[0].contains("sleep 1000"),
+        "Expected the first pane to run the command, found {:?}",
+        dev
+    );
+    assert!(
+        dev[1].starts_with("1 src"),
+        "Expected the focused pane to start in src, found {:?}",
+        dev
+    );
+
+    let logs = list_panes(&env, "logs", "#{pane_id}");
+    assert_eq!(logs.len(), 3, "Expected 3 panes in logs, found {:?}", logs);
+}
+
+#[test]
+fn test_start_applies_window_layout() {
+    let env = start_with_windows(
+        r#"[{ "name": "grid", "layout": "even-vertical", "panes": [{}, {}, {}] }]"#,
+    );
+
+    let panes = list_panes(&env, "grid", "#{pane_left}");
+    assert_eq!(panes.len(), 3, "Expected 3 panes, found {:?}", panes);
+    assert!(
+        panes.iter().all(|x| x == "0"),
+        "Expected the panes to be stacked by the layout, found {:?}",
+        panes
+    );
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn run(env: &TestEnvironment, args: &[&str]) -> String {
+    let cmd = CliCommandBuilder::new().with_env(env).args(args).build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "Command {:?} failed:\nstdout: {}\nstderr: {}",
+        args, result.stdout, result.stderr
+    );
+    result.stdout
+}
+
+fn list_panes(env: &TestEnvironment) -> Vec<String> {
+    env.tmux()
+        .run_tmux(&[
+            "list-panes",
+            "-s",
+            "-t",
+            "snap ws",
+            "-F",
+            "#{window_name} #{pane_index}",
+        ])
+        .expect("Failed to list panes")
+        .lines()
+        .map(|x| x.to_string())
+        .collect()
+}
+
+fn pane_geometry(env: &TestEnvironment) -> Vec<String> {
+    env.tmux()
+        .run_tmux(&[
+            "list-panes",
+            "-t",
+            "snap ws:shell",
+            "-F",
+            "#{pane_left},#{pane_top} #{pane_width}x#{pane_height}",
+        ])
+        .expect("Failed to list panes")
+        .lines()
+        .map(|x| x.to_string())
+        .collect()
+}
+
+#[test]
+fn test_restore_recreates_saved_windows_and_panes() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("snap_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("snap_ws", |d| {
+                d.rafaeltab_workspace("snap_ws", "snap ws", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    run(&env, &["tmux", "start"]);
+    env.tmux()
+        .run_tmux(&["new-window", "-t", "snap ws", "-n", "adhoc"])
+        .expect("Failed to create window");
+    env.tmux()
+        .run_tmux(&["split-window", "-t", "snap ws:adhoc"])
+        .expect("Failed to split window");
+    let before = list_panes(&env);
+
+    let saved = run(&env, &["tmux", "save"]);
+    assert!(
+        saved.contains("Saved 1 sessions"),
+        "Unexpected output: {}",
+        saved
+    );
+
+    env.tmux()
+        .run_tmux(&["kill-session", "-t", "snap ws"])
+        .expect("Failed to kill session");
+    let restored = run(&env, &["tmux", "restore"]);
+    assert!(
+        restored.contains("Restored 1 sessions"),
+        "Unexpected output: {}",
+        restored
+    );
+
+    assert_eq!(list_panes(&env), before);
+}
+
+#[test]
+fn test_restore_keeps_the_saved_layout() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("snap_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("snap_ws", |d| {
+                d.rafaeltab_workspace("snap_ws", "snap ws", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    run(&env, &["tmux", "start"]);
+    // One pane on the left, two stacked panes on the right
+    for args in [
+        vec!["split-window", "-h", "-t", "snap ws:shell"],
+        vec!["split-window", "-v", "-t", "snap ws:shell.1"],
+        vec!["resize-pane", "-t", "snap ws:shell.0", "-x", "30"],
+    ] {
+        env.tmux().run_tmux(&args).expect("Failed to arrange panes");
+    }
+    let before = pane_geometry(&env);
+
+    run(&env, &["tmux", "save"]);
+    env.tmux()
+        .run_tmux(&["kill-session", "-t", "snap ws"])
+        .expect("Failed to kill session");
+    run(&env, &["tmux", "restore"]);
+
+    assert_eq!(pane_geometry(&env), before);
+}
+
+#[test]
+fn test_save_keeps_the_arguments_of_running_programs() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("snap_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("snap_ws", |d| {
+                d.rafaeltab_workspace("snap_ws", "snap ws", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    run(&env, &["tmux", "start"]);
+    env.tmux()
+        .run_tmux(&["respawn-pane", "-k", "-t", "snap ws:shell", "sleep 300"])
+        .expect("Failed to start a program in the pane");
+
+    run(&env, &["tmux", "save"]);
+
+    let snapshots: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(env.root_path().join("snapshots.json"))
+            .expect("Failed to read the snapshots"),
+    )
+    .expect("Snapshots should be valid JSON");
+    assert_eq!(
+        snapshots["snapshots"]["default"]["sessions"][0]["windows"][0]["panes"][0]["command"],
+        "sleep 300"
+    );
+}
+
+#[test]
+fn test_restore_skips_running_sessions() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("snap_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("snap_ws", |d| {
+                d.rafaeltab_workspace("snap_ws", "snap ws", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    run(&env, &["tmux", "start"]);
+    run(&env, &["tmux", "save", "--name", "work"]);
+
+    let restored = run(&env, &["tmux", "restore", "--name", "work"]);
+
+    assert!(
+        restored.contains("Skipped 'snap ws'"),
+        "Expected the running session to be skipped: {}",
+        restored
+    );
+    assert_eq!(list_panes(&env), vec!["shell 0"]);
+}
+
+#[test]
+fn test_restore_fails_for_unknown_snapshot() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("snap_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("snap_ws", |d| {
+                d.rafaeltab_workspace("snap_ws", "snap ws", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    run(&env, &["tmux", "start"]);
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "restore", "--name", "missing"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "Restoring an unknown snapshot should fail");
+    assert!(
+        result.stderr.contains("no snapshot named 'missing'"),
+        "Unexpected error: {}",
+        result.stderr
+    );
+}
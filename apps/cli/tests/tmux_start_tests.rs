@@ -190,3 +190,119 @@ fn test_start_creates_multiple_sessions() {
         sessions
     );
 }
+
+#[test]
+fn test_start_only_starts_filtered_sessions() {
+    let env = TestEnvironment::describe(|root| {
+        use crate::common::rafaeltab_descriptors::RafaeltabDirMixin;
+
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("work", |d| {
+                d.rafaeltab_workspace("work", "work ws", |w| {
+                    w.tag("work");
+                });
+            });
+            td.dir("hobby", |d| {
+                d.rafaeltab_workspace("hobby", "hobby ws", |w| {
+                    w.tag("hobby");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start", "--filter", "work"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "Command failed:\nstdout: {}\nstderr: {}",
+        result.stdout, result.stderr
+    );
+    assert!(
+        env.tmux().session_exists("work ws"),
+        "Expected session 'work ws' to be created. Found sessions: {:?}",
+        env.tmux().list_sessions()
+    );
+    assert!(
+        !env.tmux().session_exists("hobby ws"),
+        "Session 'hobby ws' should not be started. Found sessions: {:?}",
+        env.tmux().list_sessions()
+    );
+}
+
+#[test]
+fn test_start_honors_every_window() {
+    let env = TestEnvironment::describe(|root| {
+        use crate::common::rafaeltab_descriptors::RafaeltabDirMixin;
+
+        root.rafaeltab_config(|c| {
+            c.tmux_session("ws_windows", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("ws_windows", |d| {
+                d.dir("api", |_d| {});
+                d.rafaeltab_workspace("ws_windows", "windows ws", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let windows = r#"[
+        { "name": "editor", "command": "sleep 1001" },
+        { "name": "server", "command": "sleep 1002", "cwd": "api", "focus": true },
+        { "name": "logs", "command": "sleep 1003", "background": true }
+    ]"#;
+    for args in [
+        vec!["config", "set", "tmux.sessions[0].windows", windows],
+        vec!["tmux", "start"],
+    ] {
+        let cmd = CliCommandBuilder::new().with_env(&env).args(&args).build();
+        let result = env.testers().cmd().run(&cmd);
+        assert!(
+            result.success,
+            "Command {:?} failed:\nstdout: {}\nstderr: {}",
+            args, result.stdout, result.stderr
+        );
+    }
+
+    let windows: Vec<String> = env
+        .tmux()
+        .run_tmux(&[
+            "list-panes",
+            "-s",
+            "-t",
+            "windows ws",
+            "-F",
+            "#{window_name} #{window_active} #{b:pane_current_path} #{pane_start_command}",
+        ])
+        .expect("Failed to list panes")
+        .lines()
+        .map(|x| x.to_string())
+        .collect();
+
+    assert_eq!(windows.len(), 3, "Expected 3 windows, found {:?}", windows);
+    assert!(
+        windows[0].starts_with("editor 0 ws_windows") && windows[0].contains("sleep 1001"),
+        "Unexpected editor window: {:?}",
+        windows
+    );
+    assert!(
+        windows[1].starts_with("server 1 api") && windows[1].contains("sleep 1002"),
+        "Expected the focused server window to start in api: {:?}",
+        windows
+    );
+    assert!(
+        windows[2].starts_with("logs 0 ws_windows") && windows[2].contains("sleep 1003"),
+        "Expected the logs window to run its command in the background: {:?}",
+        windows
+    );
+}
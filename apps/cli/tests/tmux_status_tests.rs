@@ -0,0 +1,133 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn status(env: &TestEnvironment, path: &str, extra: &[&str]) -> String {
+    let mut args = vec![
+        "tmux",
+        "status",
+        "--path",
+        path,
+        "--template",
+        "{workspace} {branch}{dirty}",
+    ];
+    args.extend_from_slice(extra);
+    let cmd = CliCommandBuilder::new().with_env(env).args(&args).build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "Command failed: {}", result.stderr);
+    result.stdout.trim().to_string()
+}
+
+#[test]
+fn test_status_shows_workspace_and_branch() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|_c| {});
+
+        root.test_dir(|td| {
+            td.dir("status_ws", |d| {
+                d.git(".", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial commit", |c| {
+                            c.file("README.md", "# Test");
+                        });
+                    });
+                });
+                d.rafaeltab_workspace("status_ws", "Status WS", |_w| {});
+            });
+        });
+    })
+    .create();
+    let repo_dir = env.find_dir("status_ws").expect("Dir not found");
+    let path = repo_dir.path().to_string_lossy().to_string();
+
+    assert_eq!(status(&env, &path, &[]), "Status WS main");
+}
+
+#[test]
+fn test_status_reuses_git_state_until_it_is_too_old() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|_c| {});
+
+        root.test_dir(|td| {
+            td.dir("status_ws", |d| {
+                d.git(".", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial commit", |c| {
+                            c.file("README.md", "# Test");
+                        });
+                    });
+                });
+                d.rafaeltab_workspace("status_ws", "Status WS", |_w| {});
+            });
+        });
+    })
+    .create();
+    let repo_dir = env.find_dir("status_ws").expect("Dir not found");
+    let path = repo_dir.path().to_string_lossy().to_string();
+
+    assert_eq!(status(&env, &path, &[]), "Status WS main");
+    std::fs::write(repo_dir.path().join("new.txt"), "changed").expect("Failed to write file");
+
+    assert_eq!(status(&env, &path, &[]), "Status WS main");
+    assert_eq!(status(&env, &path, &["--max-age", "0"]), "Status WS main*");
+}
+
+#[test]
+fn test_status_is_empty_outside_of_workspaces() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|_c| {});
+
+        root.test_dir(|td| {
+            td.dir("status_ws", |d| {
+                d.git(".", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial commit", |c| {
+                            c.file("README.md", "# Test");
+                        });
+                    });
+                });
+                d.rafaeltab_workspace("status_ws", "Status WS", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    assert_eq!(status(&env, "/", &[]), "");
+}
+
+#[test]
+fn test_status_falls_back_to_the_workspace_of_the_session() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("status_ws", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("status_ws", |d| {
+                d.rafaeltab_workspace("status_ws", "Status WS", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+    let session_id = env
+        .tmux()
+        .run_tmux(&["display-message", "-p", "-t", "Status WS", "#{session_id}"])
+        .expect("Failed to get the session id");
+
+    assert_eq!(
+        status(&env, "/", &["--session", session_id.trim()]),
+        "Status WS"
+    );
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn stop(env: &TestEnvironment, args: &[&str]) -> test_descriptors::testers::CommandResult {
+    let mut all_args = vec!["tmux", "stop"];
+    all_args.extend_from_slice(args);
+    let cmd = CliCommandBuilder::new()
+        .with_env(env)
+        .args(&all_args)
+        .build();
+    env.testers().cmd().run(&cmd)
+}
+
+#[test]
+fn test_stop_kills_session_by_workspace_id() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("stop_api", None, &[("shell", None)]);
+            c.tmux_session("stop_web", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("stop_api", |d| {
+                d.rafaeltab_workspace("stop_api", "stop api", |w| {
+                    w.tag("backend");
+                });
+            });
+            td.dir("stop_web", |d| {
+                d.rafaeltab_workspace("stop_web", "stop web", |w| {
+                    w.tag("frontend");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+
+    let result = stop(&env, &["stop_api"]);
+
+    assert!(result.success, "Command failed: {}", result.stderr);
+    assert!(result.stdout.contains("Stopped 'stop api'"));
+    assert!(!env.tmux().session_exists("stop api"));
+    assert!(env.tmux().session_exists("stop web"));
+}
+
+#[test]
+fn test_stop_by_tag_with_dry_run_keeps_sessions() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("stop_api", None, &[("shell", None)]);
+            c.tmux_session("stop_web", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("stop_api", |d| {
+                d.rafaeltab_workspace("stop_api", "stop api", |w| {
+                    w.tag("backend");
+                });
+            });
+            td.dir("stop_web", |d| {
+                d.rafaeltab_workspace("stop_web", "stop web", |w| {
+                    w.tag("frontend");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+
+    let result = stop(&env, &["--tag", "backend", "--dry-run"]);
+
+    assert!(result.success, "Command failed: {}", result.stderr);
+    assert!(
+        result.stdout.contains("Would stop 'stop api'") && !result.stdout.contains("stop web"),
+        "Unexpected output: {}",
+        result.stdout
+    );
+    assert!(env.tmux().session_exists("stop api"));
+}
+
+#[test]
+fn test_stop_all_kills_configured_sessions() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("stop_api", None, &[("shell", None)]);
+            c.tmux_session("stop_web", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("stop_api", |d| {
+                d.rafaeltab_workspace("stop_api", "stop api", |w| {
+                    w.tag("backend");
+                });
+            });
+            td.dir("stop_web", |d| {
+                d.rafaeltab_workspace("stop_web", "stop web", |w| {
+                    w.tag("frontend");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+
+    let result = stop(&env, &["--all"]);
+
+    assert!(result.success, "Command failed: {}", result.stderr);
+    assert!(!env.tmux().session_exists("stop api"));
+    assert!(!env.tmux().session_exists("stop web"));
+}
+
+#[test]
+fn test_stop_requires_a_target() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.tmux_session("stop_api", None, &[("shell", None)]);
+            c.tmux_session("stop_web", None, &[("shell", None)]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("stop_api", |d| {
+                d.rafaeltab_workspace("stop_api", "stop api", |w| {
+                    w.tag("backend");
+                });
+            });
+            td.dir("stop_web", |d| {
+                d.rafaeltab_workspace("stop_web", "stop web", |w| {
+                    w.tag("frontend");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+
+    let result = stop(&env, &[]);
+
+    assert!(!result.success, "Stopping without a target should fail");
+    assert!(env.tmux().session_exists("stop api"));
+}
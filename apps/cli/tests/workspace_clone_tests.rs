@@ -0,0 +1,261 @@
+mod common;
+
+use std::process::Command;
+
+use crate::common::{CliCommandBuilder, rafaeltab_descriptors::RafaeltabRootMixin};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn set_clone_path(env: &TestEnvironment) {
+    let layout = format!(
+        "{}/src/{{host}}/{{owner}}/{{repo}}",
+        env.root_path().display()
+    );
+    let cmd = CliCommandBuilder::new()
+        .with_env(env)
+        .args(&["config", "set", "clone.path", &layout])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config set should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+}
+
+#[test]
+fn test_workspace_clone_registers_bare_remote_in_layout() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("remotes", |d| {
+                d.git("upstream", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Api");
+                        });
+                    });
+                });
+            });
+        });
+    })
+    .create();
+
+    // A bare copy, like the repositories on a git server
+    let remotes = env.root_path().join("remotes");
+    let output = Command::new("git")
+        .args(["clone", "--quiet", "--bare", "upstream", "acme/api.git"])
+        .current_dir(&remotes)
+        .output()
+        .expect("Failed to run git");
+    assert!(output.status.success(), "Failed to create bare repository");
+    set_clone_path(&env);
+
+    let url = format!("file://{}/remotes/acme/api.git", env.root_path().display());
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "clone", &url, "--tags", "work", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace clone should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+    let workspace: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    assert_eq!(workspace["id"], "api");
+    assert_eq!(workspace["name"], "api");
+    assert_eq!(
+        workspace["tags"],
+        serde_json::json!([{ "name": "acme" }, { "name": "work" }])
+    );
+
+    let clone = env.root_path().join("src/local/acme/api");
+    assert!(
+        clone.join("README.md").exists(),
+        "The repository should be cloned"
+    );
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "find", "api"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.stdout.contains("src/local/acme/api"),
+        "The workspace should be registered. Got: {}",
+        result.stdout
+    );
+}
+
+#[test]
+fn test_workspace_clone_into_explicit_path() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("remotes", |d| {
+                d.git("upstream", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Api");
+                        });
+                    });
+                });
+            });
+        });
+    })
+    .create();
+
+    // A bare copy, like the repositories on a git server
+    let remotes = env.root_path().join("remotes");
+    let output = Command::new("git")
+        .args(["clone", "--quiet", "--bare", "upstream", "acme/api.git"])
+        .current_dir(&remotes)
+        .output()
+        .expect("Failed to run git");
+    assert!(output.status.success(), "Failed to create bare repository");
+
+    let url = format!("{}/remotes/upstream", env.root_path().display());
+    let path = env.root_path().join("checkout");
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "clone",
+            &url,
+            "--path",
+            &path.to_string_lossy(),
+            "--name",
+            "Upstream Api",
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace clone should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+    assert!(
+        path.join("README.md").exists(),
+        "The repository should be cloned"
+    );
+    assert!(
+        result.stdout.contains("upstream-api"),
+        "The id should be derived from the name. Got: {}",
+        result.stdout
+    );
+}
+
+#[test]
+fn test_workspace_clone_fails_when_destination_exists() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("remotes", |d| {
+                d.git("upstream", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Api");
+                        });
+                    });
+                });
+            });
+        });
+    })
+    .create();
+
+    // A bare copy, like the repositories on a git server
+    let remotes = env.root_path().join("remotes");
+    let output = Command::new("git")
+        .args(["clone", "--quiet", "--bare", "upstream", "acme/api.git"])
+        .current_dir(&remotes)
+        .output()
+        .expect("Failed to run git");
+    assert!(output.status.success(), "Failed to create bare repository");
+
+    let url = format!("{}/remotes/upstream", env.root_path().display());
+    let path = env.root_path().join("remotes");
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "clone",
+            &url,
+            "--path",
+            &path.to_string_lossy(),
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "Cloning into a used directory should fail");
+    assert!(
+        result.stderr.contains("already exists"),
+        "Expected an error about the directory. Got: {}",
+        result.stderr
+    );
+}
+
+#[test]
+fn test_workspace_clone_does_not_pass_the_url_as_an_option() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("remotes", |d| {
+                d.git("upstream", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Api");
+                        });
+                    });
+                });
+            });
+        });
+    })
+    .create();
+
+    // A bare copy, like the repositories on a git server
+    let remotes = env.root_path().join("remotes");
+    let output = Command::new("git")
+        .args(["clone", "--quiet", "--bare", "upstream", "acme/api.git"])
+        .current_dir(&remotes)
+        .output()
+        .expect("Failed to run git");
+    assert!(output.status.success(), "Failed to create bare repository");
+
+    let marker = env.root_path().join("marker");
+    let url = format!("--upload-pack=touch {}", marker.display());
+    let path = env.root_path().join("checkout");
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "clone",
+            "--path",
+            &path.to_string_lossy(),
+            "--",
+            &url,
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "Cloning an option should fail");
+    assert!(
+        !marker.exists(),
+        "The url should not be passed to git as an option"
+    );
+}
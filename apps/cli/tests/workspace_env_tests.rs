@@ -0,0 +1,220 @@
+mod common;
+
+use std::{fs, thread, time::Duration};
+
+use crate::common::{
+    CliCommandBuilder, rafaeltab_descriptors::RafaeltabDirMixin,
+    rafaeltab_descriptors::RafaeltabRootMixin,
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+#[test]
+fn test_workspace_find_json_shows_env() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window_with_command("first", "echo \"$PORT $WIN\" > env.txt");
+            c.default_window("second");
+        });
+
+        root.test_dir(|td| {
+            td.dir("env_ws", |d| {
+                d.rafaeltab_workspace("env_ws", "Env Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    fs::write(
+        env.root_path().join("env_ws/.env"),
+        "# local settings\nexport PORT=3000\nAPI_TOKEN=\"very secret\"\n",
+    )
+    .expect("Failed to write the env file");
+
+    for (path, value) in [
+        ("workspaces[id=env_ws].envFile", ".env"),
+        (
+            "workspaces[id=env_ws].env",
+            r#"{ "PORT": "8080", "ID": "${workspace.id}" }"#,
+        ),
+        ("tmux.defaultWindows[0].env", r#"{ "WIN": "first" }"#),
+    ] {
+        let cmd = CliCommandBuilder::new()
+            .with_env(&env)
+            .args(&["config", "set", path, value])
+            .build();
+        let result = env.testers().cmd().run(&cmd);
+        assert!(
+            result.success,
+            "config set should succeed.\nSTDERR: {}",
+            result.stderr
+        );
+    }
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "find", "env_ws", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace find should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let workspace: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    assert_eq!(
+        workspace["env"],
+        serde_json::json!({ "API_TOKEN": "very secret", "ID": "env_ws", "PORT": "8080" }),
+        "env should override the env file and have its variables expanded"
+    );
+}
+
+#[test]
+fn test_workspace_find_masks_secrets() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window_with_command("first", "echo \"$PORT $WIN\" > env.txt");
+            c.default_window("second");
+        });
+
+        root.test_dir(|td| {
+            td.dir("env_ws", |d| {
+                d.rafaeltab_workspace("env_ws", "Env Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    fs::write(
+        env.root_path().join("env_ws/.env"),
+        "# local settings\nexport PORT=3000\nAPI_TOKEN=\"very secret\"\n",
+    )
+    .expect("Failed to write the env file");
+
+    for (path, value) in [
+        ("workspaces[id=env_ws].envFile", ".env"),
+        (
+            "workspaces[id=env_ws].env",
+            r#"{ "PORT": "8080", "ID": "${workspace.id}" }"#,
+        ),
+        ("tmux.defaultWindows[0].env", r#"{ "WIN": "first" }"#),
+    ] {
+        let cmd = CliCommandBuilder::new()
+            .with_env(&env)
+            .args(&["config", "set", path, value])
+            .build();
+        let result = env.testers().cmd().run(&cmd);
+        assert!(
+            result.success,
+            "config set should succeed.\nSTDERR: {}",
+            result.stderr
+        );
+    }
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "find", "env_ws"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace find should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    assert!(
+        result.stdout.contains("API_TOKEN=********"),
+        "Secrets should be masked.\nSTDOUT: {}",
+        result.stdout
+    );
+    assert!(
+        result.stdout.contains("PORT=8080"),
+        "Other values should be shown.\nSTDOUT: {}",
+        result.stdout
+    );
+    assert!(!result.stdout.contains("very secret"));
+}
+
+#[test]
+fn test_tmux_start_injects_env() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window_with_command("first", "echo \"$PORT $WIN\" > env.txt");
+            c.default_window("second");
+        });
+
+        root.test_dir(|td| {
+            td.dir("env_ws", |d| {
+                d.rafaeltab_workspace("env_ws", "Env Workspace", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    fs::write(
+        env.root_path().join("env_ws/.env"),
+        "# local settings\nexport PORT=3000\nAPI_TOKEN=\"very secret\"\n",
+    )
+    .expect("Failed to write the env file");
+
+    for (path, value) in [
+        ("workspaces[id=env_ws].envFile", ".env"),
+        (
+            "workspaces[id=env_ws].env",
+            r#"{ "PORT": "8080", "ID": "${workspace.id}" }"#,
+        ),
+        ("tmux.defaultWindows[0].env", r#"{ "WIN": "first" }"#),
+    ] {
+        let cmd = CliCommandBuilder::new()
+            .with_env(&env)
+            .args(&["config", "set", path, value])
+            .build();
+        let result = env.testers().cmd().run(&cmd);
+        assert!(
+            result.success,
+            "config set should succeed.\nSTDERR: {}",
+            result.stderr
+        );
+    }
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["tmux", "start"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "tmux start should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+
+    let session_env = env
+        .tmux()
+        .run_tmux(&["show-environment", "-t", "Env Workspace"])
+        .expect("Failed to show the session environment");
+    assert!(session_env.contains("PORT=8080"), "{}", session_env);
+    assert!(
+        session_env.contains("API_TOKEN=very secret"),
+        "{}",
+        session_env
+    );
+    assert!(
+        !session_env.contains("WIN=first"),
+        "Window variables should not leak into the session.\n{}",
+        session_env
+    );
+
+    let output = env.root_path().join("env_ws/env.txt");
+    for _ in 0..50 {
+        if fs::read_to_string(&output).is_ok_and(|x| x.ends_with('\n')) {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert_eq!(
+        fs::read_to_string(&output).expect("The window command should have run"),
+        "8080 first\n"
+    );
+}
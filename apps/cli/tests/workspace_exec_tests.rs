@@ -0,0 +1,187 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder,
+    rafaeltab_descriptors::{RafaeltabDirMixin, RafaeltabRootMixin},
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+#[test]
+fn test_workspace_exec_prefixes_output_per_workspace() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |w| {
+                    w.tag("node");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "exec", "--", "basename \"$PWD\""])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace exec should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+    assert!(
+        result.stdout.contains("[api] api") && result.stdout.contains("[web] web"),
+        "Output should be prefixed with the workspace. Got: {}",
+        result.stdout
+    );
+    assert!(
+        result.stdout.contains("WORKSPACE") && result.stdout.contains("exit 0"),
+        "A summary should be shown. Got: {}",
+        result.stdout
+    );
+}
+
+#[test]
+fn test_workspace_exec_json_reports_exit_codes() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |w| {
+                    w.tag("node");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "exec",
+            "--tag",
+            "rust",
+            "--json",
+            "--",
+            "exit 3",
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "A failing command should fail the exec");
+    let results: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    let results = results.as_array().expect("Output should be a list");
+    assert_eq!(results.len(), 1, "Only the rust workspace should run");
+    assert_eq!(results[0]["id"], "api");
+    assert_eq!(results[0]["exitCode"], 3);
+    assert_eq!(results[0]["status"], "failed");
+}
+
+#[test]
+fn test_workspace_exec_json_pretty_keeps_output_out_of_the_json() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |w| {
+                    w.tag("node");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "exec",
+            "--tag",
+            "rust",
+            "--json-pretty",
+            "--",
+            "echo hello",
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "exec failed: {}", result.stderr);
+    let results: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    let results = results.as_array().expect("Output should be a list");
+    assert_eq!(results.len(), 1, "Only the rust workspace should run");
+    assert_eq!(results[0]["stdout"], "hello\n");
+}
+
+#[test]
+fn test_workspace_exec_fail_fast_skips_remaining_workspaces() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |w| {
+                    w.tag("node");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "exec",
+            "--parallel",
+            "1",
+            "--fail-fast",
+            "--json",
+            "--",
+            "false",
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "A failing command should fail the exec");
+    let results: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    assert_eq!(results[0]["status"], "failed");
+    assert_eq!(results[1]["status"], "skipped");
+}
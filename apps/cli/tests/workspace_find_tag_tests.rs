@@ -554,3 +554,95 @@ fn test_workspace_find_tag_empty_tag_list() {
         result.stdout
     );
 }
+
+#[test]
+fn test_workspace_find_tag_boolean_query() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Payments Api", |w| {
+                    w.tag("backend");
+                    w.tag("team:payments");
+                });
+            });
+            td.dir("old", |d| {
+                d.rafaeltab_workspace("old", "Old Infra", |w| {
+                    w.tag("infra");
+                    w.tag("team:payments");
+                    w.tag("archived");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Public Web", |w| {
+                    w.tag("frontend");
+                });
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "find-tag",
+            "(backend || infra) && team:payments && !archived",
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace find-tag command should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    assert!(
+        result.stdout.contains("Payments Api")
+            && !result.stdout.contains("Old Infra")
+            && !result.stdout.contains("Public Web"),
+        "Only the active payments workspace should match. Got: {}",
+        result.stdout
+    );
+
+    // Globs match parts of tag names
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "list", "--filter", "tag:*end"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.stdout.contains("Payments Api")
+            && result.stdout.contains("Public Web")
+            && !result.stdout.contains("Old Infra"),
+        "Workspaces with a tag ending in 'end' should be listed. Got: {}",
+        result.stdout
+    );
+}
+
+#[test]
+fn test_workspace_find_tag_invalid_query() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "find-tag", "rust && (lua"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "An invalid query should fail");
+    assert!(
+        result.stderr.contains("unmatched `(`"),
+        "The error should explain the problem. Got: {}",
+        result.stderr
+    );
+}
@@ -80,7 +80,7 @@ fn test_workspace_find_nonexistent_id() {
     // Run workspace find with a non-existent ID
     let cmd = CliCommandBuilder::new()
         .with_env(&env)
-        .args(&["workspace", "find", "nonexistent_workspace"])
+        .args(&["workspace", "find", "nonexistent_workspace", "--exact"])
         .build();
     let result = env.testers().cmd().run(&cmd);
 
@@ -0,0 +1,104 @@
+mod common;
+
+use common::CliCommandBuilder;
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::{CommandResult, CommandTester};
+
+const CONFIG: &str = r#"{
+  "version": 1,
+  "workspaces": [
+    { "id": "payments_api", "name": "Payments Api", "root": "/tmp", "aliases": ["pay"] },
+    { "id": "payments_web", "name": "Payments Web", "root": "/tmp" },
+    { "id": "dotfiles", "name": "Dotfiles", "root": "/tmp" }
+  ]
+}"#;
+
+fn find(args: &[&str]) -> CommandResult {
+    let env = TestEnvironment::describe(|_root| {}).create();
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, CONFIG).expect("Failed to write config");
+
+    let mut all_args = vec!["workspace", "find"];
+    all_args.extend_from_slice(args);
+    let cmd = CliCommandBuilder::new()
+        .with_config(&config_path)
+        .args(&all_args)
+        .build();
+    env.testers().cmd().run(&cmd)
+}
+
+/// Test that aliases, prefixes and fuzzy matches resolve to a workspace
+#[test]
+pub fn test_find_resolves_aliases_prefixes_and_fuzzy_matches() {
+    for (query, expected) in [
+        ("pay", "payments_api"),
+        ("dot", "dotfiles"),
+        ("dtfl", "dotfiles"),
+        ("payments web", "payments_web"),
+    ] {
+        let result = find(&[query, "--json"]);
+
+        assert!(result.success, "find {} failed: {}", query, result.stderr);
+        let workspace: serde_json::Value =
+            serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+        assert_eq!(workspace["id"], expected, "for query {}", query);
+    }
+}
+
+/// Test that a fuzzy match that clearly beats the others is used
+#[test]
+pub fn test_find_picks_a_clearly_better_fuzzy_match() {
+    let result = find(&["pmtsweb", "--json"]);
+
+    assert!(result.success, "find failed: {}", result.stderr);
+    let workspace: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    assert_eq!(workspace["id"], "payments_web");
+}
+
+/// Test that a query without any match fails unless --exact is passed
+#[test]
+pub fn test_find_fails_when_nothing_matches() {
+    let result = find(&["nothing_like_this"]);
+
+    assert!(!result.success, "A query without matches should fail");
+    assert!(
+        result
+            .stderr
+            .contains("no workspace matches 'nothing_like_this'"),
+        "Expected an error on stderr. Got: {}",
+        result.stderr
+    );
+}
+
+/// Test that a query matching several workspaces lists the candidates
+#[test]
+pub fn test_find_reports_ambiguous_queries() {
+    let result = find(&["payments"]);
+
+    assert!(!result.success, "An ambiguous query should fail");
+    assert!(
+        result.stderr.contains("payments_api") && result.stderr.contains("payments_web"),
+        "The candidates should be listed. Got: {}",
+        result.stderr
+    );
+}
+
+/// Test that --exact only accepts ids and aliases
+#[test]
+pub fn test_find_exact_only_accepts_ids_and_aliases() {
+    let result = find(&["dot", "--exact"]);
+    assert!(result.success, "find failed: {}", result.stderr);
+    assert!(
+        result.stdout.trim().is_empty(),
+        "A prefix should not match with --exact. Got: {}",
+        result.stdout
+    );
+
+    let result = find(&["pay", "--exact"]);
+    assert!(
+        result.stdout.contains("Payments Api"),
+        "An alias should match with --exact. Got: {}",
+        result.stdout
+    );
+}
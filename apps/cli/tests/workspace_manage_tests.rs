@@ -0,0 +1,458 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder,
+    rafaeltab_descriptors::{RafaeltabDirMixin, RafaeltabRootMixin},
+};
+use std::fs;
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn read_rafaeltab_config(env: &TestEnvironment) -> serde_json::Value {
+    let config_path = env
+        .context()
+        .config_path()
+        .expect("Config path should be set");
+    let content = fs::read_to_string(&config_path).expect("Failed to read config file");
+    serde_json::from_str(&content).expect("Failed to parse config JSON")
+}
+
+fn workspace_ids(config: &serde_json::Value) -> Vec<String> {
+    config["workspaces"]
+        .as_array()
+        .expect("workspaces should be a list")
+        .iter()
+        .map(|x| x["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn session_workspaces(config: &serde_json::Value) -> Vec<String> {
+    config["tmux"]["sessions"]
+        .as_array()
+        .map(|sessions| {
+            sessions
+                .iter()
+                .filter_map(|x| x["workspace"].as_str().map(|x| x.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn run(env: &TestEnvironment, args: &[&str]) -> test_descriptors::testers::CommandResult {
+    let cmd = CliCommandBuilder::new().with_env(env).args(args).build();
+    env.testers().cmd().run(&cmd)
+}
+
+#[test]
+fn test_workspace_remove_with_yes_removes_session_config() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let result = run(&env, &["workspace", "remove", "api", "--yes"]);
+
+    assert!(
+        result.success,
+        "workspace remove should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    assert_eq!(workspace_ids(&config), vec!["web"]);
+    assert!(
+        session_workspaces(&config).is_empty(),
+        "The tmux.sessions entry should be removed. Got: {}",
+        config
+    );
+}
+
+#[test]
+fn test_workspace_remove_can_keep_sessions() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let result = run(
+        &env,
+        &["workspace", "remove", "api", "--yes", "--keep-sessions"],
+    );
+
+    assert!(
+        result.success,
+        "workspace remove should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    assert_eq!(workspace_ids(&config), vec!["web"]);
+    assert_eq!(session_workspaces(&config), vec!["api"]);
+
+    // A session for a workspace that no longer exists should not break other commands
+    let result = run(&env, &["tmux", "list"]);
+    assert!(
+        result.success,
+        "tmux list should still work.\nSTDERR: {}",
+        result.stderr
+    );
+}
+
+#[test]
+fn test_workspace_remove_unknown_workspace_fails() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let result = run(&env, &["workspace", "remove", "missing", "--yes"]);
+
+    assert!(!result.success, "Removing an unknown workspace should fail");
+    assert!(
+        result.stderr.contains("does not exist"),
+        "Expected an error message. Got: {}",
+        result.stderr
+    );
+}
+
+#[test]
+fn test_workspace_rename_keeps_id() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let result = run(
+        &env,
+        &["workspace", "rename", "api", "Backend Api", "--json"],
+    );
+
+    assert!(
+        result.success,
+        "workspace rename should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    assert_eq!(config["workspaces"][0]["id"], "api");
+    assert_eq!(config["workspaces"][0]["name"], "Backend Api");
+}
+
+#[test]
+fn test_workspace_rename_reslug_moves_session_config() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let result = run(
+        &env,
+        &["workspace", "rename", "api", "Backend Api", "--reslug"],
+    );
+
+    assert!(
+        result.success,
+        "workspace rename should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    assert_eq!(workspace_ids(&config), vec!["backend_api", "web"]);
+    assert_eq!(session_workspaces(&config), vec!["backend_api"]);
+}
+
+#[test]
+fn test_workspace_rename_reslug_moves_running_session() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+    let result = run(&env, &["tmux", "start"]);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+
+    let result = run(
+        &env,
+        &["workspace", "rename", "api", "Backend Api", "--reslug"],
+    );
+
+    assert!(
+        result.success,
+        "workspace rename should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    assert!(!env.tmux().session_exists("Api"));
+    assert!(env.tmux().session_exists("Backend Api"));
+
+    // The moved session is recognized as the session of the renamed workspace
+    let result = run(&env, &["tmux", "start"]);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+    let sessions = env
+        .tmux()
+        .run_tmux(&["list-sessions", "-F", "#{session_name}"])
+        .expect("Failed to list sessions");
+    assert_eq!(
+        sessions.lines().filter(|x| *x == "Backend Api").count(),
+        1,
+        "Expected a single session for the workspace. Got: {}",
+        sessions
+    );
+}
+
+#[test]
+fn test_workspace_rename_reslug_can_kill_running_session() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+    let result = run(&env, &["tmux", "start"]);
+    assert!(result.success, "tmux start failed: {}", result.stderr);
+
+    let result = run(
+        &env,
+        &[
+            "workspace",
+            "rename",
+            "api",
+            "Backend Api",
+            "--reslug",
+            "--kill-sessions",
+        ],
+    );
+
+    assert!(
+        result.success,
+        "workspace rename should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    assert!(!env.tmux().session_exists("Api"));
+    assert!(!env.tmux().session_exists("Backend Api"));
+    assert!(env.tmux().session_exists("Web"));
+}
+
+#[test]
+fn test_workspace_rename_reslug_rejects_existing_id() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let result = run(&env, &["workspace", "rename", "api", "Web", "--reslug"]);
+
+    assert!(!result.success, "Renaming onto an existing id should fail");
+    assert_eq!(
+        workspace_ids(&read_rafaeltab_config(&env)),
+        vec!["api", "web"]
+    );
+}
+
+#[test]
+fn test_workspace_edit_changes_tags_and_root() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+    let new_root = env.root_path().join("web");
+
+    let result = run(
+        &env,
+        &[
+            "workspace",
+            "edit",
+            "api",
+            "--add-tag",
+            "backend",
+            "--remove-tag",
+            "rust",
+            "--root",
+            new_root.to_str().unwrap(),
+        ],
+    );
+
+    assert!(
+        result.success,
+        "workspace edit should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    assert_eq!(
+        config["workspaces"][0]["tags"],
+        serde_json::json!(["work", "backend"])
+    );
+    assert_eq!(config["workspaces"][0]["root"], new_root.to_str().unwrap());
+}
+
+#[test]
+fn test_workspace_edit_sets_worktree_config() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+            c.tmux_session("api", None, &[("editor", Some("nvim ."))]);
+        });
+
+        root.test_dir(|td| {
+            td.dir("api", |d| {
+                d.rafaeltab_workspace("api", "Api", |w| {
+                    w.tag("work");
+                    w.tag("rust");
+                });
+            });
+            td.dir("web", |d| {
+                d.rafaeltab_workspace("web", "Web", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let result = run(
+        &env,
+        &[
+            "workspace",
+            "edit",
+            "web",
+            "--worktree",
+            r#"{"onCreate": ["pnpm install"]}"#,
+        ],
+    );
+
+    assert!(
+        result.success,
+        "workspace edit should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    assert_eq!(
+        config["workspaces"][1]["worktree"]["onCreate"],
+        serde_json::json!(["pnpm install"])
+    );
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use std::fs;
+
+use crate::common::{CliCommandBuilder, rafaeltab_descriptors::RafaeltabRootMixin};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn get_config(env: &TestEnvironment, path: &str) -> serde_json::Value {
+    let cmd = CliCommandBuilder::new()
+        .with_env(env)
+        .args(&["config", "get", path, "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config get should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    serde_json::from_str(&result.stdout).expect("Output should be valid JSON")
+}
+
+#[test]
+fn test_workspace_new_renders_template_and_registers_workspace() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+    })
+    .create();
+
+    let template = env.root_path().join("templates/rust");
+    fs::create_dir_all(template.join("src")).expect("Failed to create the template");
+    fs::write(template.join("README.md"), "# {{name}}\n").expect("Failed to write README.md");
+    fs::write(template.join("src/{{id}}.rs"), "fn main() {}\n").expect("Failed to write main");
+    fs::write(
+        template.join("rafaeltab-template.json"),
+        r#"{ "windows": [{ "name": "editor" }] }"#,
+    )
+    .expect("Failed to write the manifest");
+
+    let templates = serde_json::json!([{
+        "name": "rust",
+        "source": template.to_string_lossy(),
+        "tags": ["rust"],
+        "worktree": { "onCreate": ["cargo build"] }
+    }]);
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "set", "templates", &templates.to_string()])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config set should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let destination = env.root_path().join("src/payments-api");
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "new",
+            "Payments Api",
+            "--template",
+            "rust",
+            "--tags",
+            "work",
+            "--path",
+            destination.to_str().unwrap(),
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "workspace new should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+
+    assert_eq!(
+        fs::read_to_string(destination.join("README.md")).unwrap(),
+        "# Payments Api\n"
+    );
+    assert!(destination.join("src/payments-api.rs").exists());
+    assert!(
+        destination.join(".git").is_dir(),
+        "A repository should be created"
+    );
+    assert!(!destination.join("rafaeltab-template.json").exists());
+
+    let workspace = get_config(&env, "workspaces[id=payments-api]");
+    assert_eq!(workspace["tags"], serde_json::json!(["rust", "work"]));
+    assert_eq!(
+        workspace["worktree"]["onCreate"],
+        serde_json::json!(["cargo build"])
+    );
+
+    let sessions = get_config(&env, "tmux.sessions");
+    assert_eq!(
+        sessions,
+        serde_json::json!([{ "workspace": "payments-api", "windows": [{ "name": "editor" }] }]),
+        "The windows of the manifest should become the session of the workspace"
+    );
+}
+
+#[test]
+fn test_workspace_new_fails_for_unknown_template() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+    })
+    .create();
+
+    let template = env.root_path().join("templates/rust");
+    fs::create_dir_all(template.join("src")).expect("Failed to create the template");
+    fs::write(template.join("README.md"), "# {{name}}\n").expect("Failed to write README.md");
+    fs::write(template.join("src/{{id}}.rs"), "fn main() {}\n").expect("Failed to write main");
+    fs::write(
+        template.join("rafaeltab-template.json"),
+        r#"{ "windows": [{ "name": "editor" }] }"#,
+    )
+    .expect("Failed to write the manifest");
+
+    let templates = serde_json::json!([{
+        "name": "rust",
+        "source": template.to_string_lossy(),
+        "tags": ["rust"],
+        "worktree": { "onCreate": ["cargo build"] }
+    }]);
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["config", "set", "templates", &templates.to_string()])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+    assert!(
+        result.success,
+        "config set should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "new", "Api", "--template", "go"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "workspace new should fail");
+    assert!(
+        result.stderr.contains("available templates: rust"),
+        "STDERR: {}",
+        result.stderr
+    );
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::CliCommandBuilder;
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+const CONFIG: &str = r#"{
+  "version": 1,
+  "workspaces": [
+    { "id": "api", "name": "Api", "root": "/tmp" },
+    { "id": "web", "name": "Web", "root": "/tmp" },
+    { "id": "notes", "name": "Notes", "root": "/tmp", "importance": 5 }
+  ]
+}"#;
+
+fn listed_ids(env: &TestEnvironment, config_path: &std::path::Path) -> Vec<String> {
+    let cmd = CliCommandBuilder::new()
+        .with_config(config_path)
+        .args(&["workspace", "list", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "workspace list failed: {}", result.stderr);
+    let workspaces: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    workspaces
+        .as_array()
+        .expect("Output should be a list")
+        .iter()
+        .map(|x| x["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+/// Test that important workspaces are listed first and the rest keep their order
+#[test]
+pub fn test_workspace_list_puts_important_workspaces_first() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, CONFIG).expect("Failed to write config");
+
+    assert_eq!(listed_ids(&env, &config_path), vec!["notes", "api", "web"]);
+}
+
+/// Test that recently used workspaces are listed before unused ones
+#[test]
+pub fn test_workspace_list_ranks_by_frecency() {
+    let env = TestEnvironment::describe(|_root| {}).create();
+
+    let config_path = env.root_path().join("config.json");
+    std::fs::write(&config_path, CONFIG).expect("Failed to write config");
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    std::fs::write(
+        env.root_path().join(".rafaeltab-state.json"),
+        format!(
+            r#"{{ "entries": {{ "workspace:web": {{ "count": 3, "lastUsed": {} }} }} }}"#,
+            now
+        ),
+    )
+    .expect("Failed to write state");
+
+    assert_eq!(listed_ids(&env, &config_path), vec!["notes", "web", "api"]);
+}
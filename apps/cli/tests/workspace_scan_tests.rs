@@ -0,0 +1,151 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder,
+    rafaeltab_descriptors::{RafaeltabDirMixin, RafaeltabRootMixin},
+};
+use std::fs;
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+fn read_rafaeltab_config(env: &TestEnvironment) -> serde_json::Value {
+    let config_path = env
+        .context()
+        .config_path()
+        .expect("Config path should be set");
+    let content = fs::read_to_string(&config_path).expect("Failed to read config file");
+    serde_json::from_str(&content).expect("Failed to parse config JSON")
+}
+
+#[test]
+fn test_workspace_scan_adds_new_repositories() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("src", |d| {
+                d.dir("existing", |d| {
+                    d.rafaeltab_workspace("existing", "Existing", |_w| {});
+                });
+            });
+        });
+    })
+    .create();
+
+    let src = env.root_path().join("src");
+    fs::create_dir_all(src.join("existing/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("work/api/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("notes/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("plain")).expect("Failed to create directory");
+    let src = env.root_path().join("src");
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "scan", src.to_str().unwrap(), "--yes"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace scan should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    let workspaces = config["workspaces"]
+        .as_array()
+        .expect("workspaces should be a list");
+    let ids: Vec<&str> = workspaces
+        .iter()
+        .map(|x| x["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["existing", "notes", "api"]);
+    assert_eq!(workspaces[2]["tags"], serde_json::json!(["work"]));
+}
+
+#[test]
+fn test_workspace_scan_respects_depth() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("src", |d| {
+                d.dir("existing", |d| {
+                    d.rafaeltab_workspace("existing", "Existing", |_w| {});
+                });
+            });
+        });
+    })
+    .create();
+
+    let src = env.root_path().join("src");
+    fs::create_dir_all(src.join("existing/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("work/api/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("notes/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("plain")).expect("Failed to create directory");
+    let src = env.root_path().join("src");
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&[
+            "workspace",
+            "scan",
+            src.to_str().unwrap(),
+            "--depth",
+            "1",
+            "--yes",
+        ])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace scan should succeed.\nSTDERR: {}",
+        result.stderr
+    );
+    let config = read_rafaeltab_config(&env);
+    assert_eq!(config["workspaces"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_workspace_scan_requires_yes_when_not_interactive() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("src", |d| {
+                d.dir("existing", |d| {
+                    d.rafaeltab_workspace("existing", "Existing", |_w| {});
+                });
+            });
+        });
+    })
+    .create();
+
+    let src = env.root_path().join("src");
+    fs::create_dir_all(src.join("existing/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("work/api/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("notes/.git")).expect("Failed to create repository");
+    fs::create_dir_all(src.join("plain")).expect("Failed to create directory");
+    let src = env.root_path().join("src");
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "scan", src.to_str().unwrap()])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(!result.success, "workspace scan should fail without --yes");
+    assert_eq!(
+        read_rafaeltab_config(&env)["workspaces"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+}
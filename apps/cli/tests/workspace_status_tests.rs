@@ -0,0 +1,235 @@
+mod common;
+
+use crate::common::{
+    CliCommandBuilder,
+    rafaeltab_descriptors::{RafaeltabDirMixin, RafaeltabGitMixin, RafaeltabRootMixin},
+};
+use test_descriptors::TestEnvironment;
+use test_descriptors::testers::CommandTester;
+
+#[test]
+fn test_workspace_status_json_reports_git_state() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("project", |d| {
+                d.git("repo", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Project");
+                        });
+                    });
+                    g.staged(|c| {
+                        c.file("staged.txt", "staged");
+                    });
+                    g.unstaged(|c| {
+                        c.file("README.md", "# Changed");
+                    });
+                    g.rafaeltab_workspace("project", "Project", |w| {
+                        w.tag("rust");
+                    });
+                });
+            });
+            td.dir("notes", |d| {
+                d.rafaeltab_workspace("notes", "Notes", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "status", "--json"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(
+        result.success,
+        "workspace status should succeed.\nSTDOUT: {}\nSTDERR: {}",
+        result.stdout, result.stderr
+    );
+    let statuses: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+    let statuses = statuses.as_array().expect("Output should be a list");
+    assert_eq!(statuses.len(), 2);
+
+    let project = statuses
+        .iter()
+        .find(|x| x["id"] == "project")
+        .expect("project should be listed");
+    assert_eq!(project["git"]["branch"], "main");
+    assert_eq!(project["git"]["dirty"], true);
+    assert_eq!(project["git"]["staged"], 1);
+    assert_eq!(project["git"]["modified"], 1);
+    assert_eq!(project["session"], false);
+
+    let notes = statuses
+        .iter()
+        .find(|x| x["id"] == "notes")
+        .expect("notes should be listed");
+    assert!(notes["git"].is_null(), "notes is not a git repository");
+}
+
+#[test]
+fn test_workspace_status_keeps_workspace_order_with_limited_parallelism() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("project", |d| {
+                d.git("repo", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Project");
+                        });
+                    });
+                    g.staged(|c| {
+                        c.file("staged.txt", "staged");
+                    });
+                    g.unstaged(|c| {
+                        c.file("README.md", "# Changed");
+                    });
+                    g.rafaeltab_workspace("project", "Project", |w| {
+                        w.tag("rust");
+                    });
+                });
+            });
+            td.dir("notes", |d| {
+                d.rafaeltab_workspace("notes", "Notes", |_w| {});
+            });
+        });
+    })
+    .create();
+    let ids = |args: &[&str]| -> Vec<String> {
+        let cmd = CliCommandBuilder::new().with_env(&env).args(args).build();
+        let result = env.testers().cmd().run(&cmd);
+        assert!(result.success, "Command failed: {}", result.stderr);
+        let items: serde_json::Value =
+            serde_json::from_str(&result.stdout).expect("Output should be valid JSON");
+        items
+            .as_array()
+            .expect("Output should be a list")
+            .iter()
+            .map(|x| x["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let statuses = ids(&["workspace", "status", "--parallel", "1", "--json"]);
+
+    assert_eq!(statuses.len(), 2);
+    assert_eq!(statuses, ids(&["workspace", "list", "--json"]));
+}
+
+#[test]
+fn test_workspace_status_filters_by_tag() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("project", |d| {
+                d.git("repo", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Project");
+                        });
+                    });
+                    g.staged(|c| {
+                        c.file("staged.txt", "staged");
+                    });
+                    g.unstaged(|c| {
+                        c.file("README.md", "# Changed");
+                    });
+                    g.rafaeltab_workspace("project", "Project", |w| {
+                        w.tag("rust");
+                    });
+                });
+            });
+            td.dir("notes", |d| {
+                d.rafaeltab_workspace("notes", "Notes", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "status", "--tag", "!rust"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "workspace status should succeed");
+    assert!(
+        result
+            .stdout
+            .contains("Notes (notes): not a git repository"),
+        "Expected the notes workspace. Got: {}",
+        result.stdout
+    );
+    assert!(
+        !result.stdout.contains("Project"),
+        "The rust workspace should be filtered out. Got: {}",
+        result.stdout
+    );
+}
+
+#[test]
+fn test_workspace_status_table() {
+    let env = TestEnvironment::describe(|root| {
+        root.rafaeltab_config(|c| {
+            c.default_window("shell");
+        });
+
+        root.test_dir(|td| {
+            td.dir("project", |d| {
+                d.git("repo", |g| {
+                    g.branch("main", |b| {
+                        b.commit("Initial", |c| {
+                            c.file("README.md", "# Project");
+                        });
+                    });
+                    g.staged(|c| {
+                        c.file("staged.txt", "staged");
+                    });
+                    g.unstaged(|c| {
+                        c.file("README.md", "# Changed");
+                    });
+                    g.rafaeltab_workspace("project", "Project", |w| {
+                        w.tag("rust");
+                    });
+                });
+            });
+            td.dir("notes", |d| {
+                d.rafaeltab_workspace("notes", "Notes", |_w| {});
+            });
+        });
+    })
+    .create();
+
+    let cmd = CliCommandBuilder::new()
+        .with_env(&env)
+        .args(&["workspace", "status", "--table"])
+        .build();
+    let result = env.testers().cmd().run(&cmd);
+
+    assert!(result.success, "workspace status should succeed");
+    let lines: Vec<&str> = result.stdout.lines().collect();
+    assert!(
+        lines[0].starts_with("WORKSPACE") && lines[0].contains("BEHIND"),
+        "Expected a table header. Got: {}",
+        result.stdout
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|x| x.starts_with("project") && x.contains("main")),
+        "Expected a row for the project. Got: {}",
+        result.stdout
+    );
+}